@@ -15,10 +15,11 @@ fn bench_transaction_creation(c: &mut Criterion) {
     c.bench_function("transaction_creation", |b| {
         let mut wallet = MobileWallet::new("ValidPass123").unwrap();
         wallet.set_balance(10000);
+        let recipient = Account::address_from_public_key(&[2u8; 33], Network::Mainnet);
         
         b.iter(|| {
             wallet.create_transaction(
-                black_box("silver_recipient"),
+                black_box(&recipient),
                 black_box(1000),
                 black_box(100),
             )
@@ -1,7 +1,36 @@
 //! Account management for mobile wallet
 
 use serde::{Deserialize, Serialize};
-use crate::errors::Result;
+use crate::errors::{MobileError, Result};
+use crate::hd::{DerivationPath, ExtendedPublicKey, Purpose};
+use crate::network::Network;
+use crate::utxo::Utxo;
+
+/// Length of the address payload (hash of the public key)
+const ADDRESS_PAYLOAD_LEN: usize = 20;
+
+/// Length of the checksum appended to the address payload
+const ADDRESS_CHECKSUM_LEN: usize = 4;
+
+/// Longest valid address: the longest network prefix and the hex body
+pub const MAX_ADDRESS_LEN: usize = "tsilver_".len() + 2 * (ADDRESS_PAYLOAD_LEN + ADDRESS_CHECKSUM_LEN);
+
+/// Maximum length of an account or wallet name, in characters
+pub const MAX_NAME_LEN: usize = 64;
+
+/// Where an account's signing key comes from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccountKind {
+    /// Derived from the wallet seed at `index`
+    #[default]
+    Hd,
+    
+    /// External address with no key material
+    WatchOnly,
+    
+    /// Single private key stored in imported-key slot `index`
+    Imported,
+}
 
 /// Account
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,34 +49,134 @@ pub struct Account {
     
     /// Balance
     pub balance: u64,
+    
+    /// Key source
+    #[serde(default)]
+    pub kind: AccountKind,
+    
+    /// Unspent outputs (empty when the node only reports a balance)
+    #[serde(default)]
+    pub utxos: Vec<Utxo>,
+    
+    /// Derivation path of an HD account (BIP44 at `index` when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<DerivationPath>,
 }
 
 impl Account {
-    /// Create a new account
-    pub fn new(index: u32) -> Result<Self> {
-        use blake3::Hasher;
-        
-        // Generate address from index
-        let mut hasher = Hasher::new();
-        hasher.update(&index.to_le_bytes());
-        let address_bytes = hasher.finalize().as_bytes().to_vec();
-        let address = format!("silver_{}", hex::encode(&address_bytes[..8]));
-        
-        // Generate public key
-        let mut key_hasher = Hasher::new();
-        key_hasher.update(&index.to_le_bytes());
-        key_hasher.update(b"public_key");
-        let public_key = key_hasher.finalize().as_bytes().to_vec();
+    /// Create a new account at `index` on the external chain of the BIP44 `account_key`
+    pub fn new(index: u32, account_key: &ExtendedPublicKey, network: Network) -> Result<Self> {
+        let path = DerivationPath::new(Purpose::Bip44, network.coin_type(), index);
+        Self::derived(path, account_key, network)
+    }
+    
+    /// Create an account at `path`, given the extended key for `path.account_path()`
+    pub fn derived(path: DerivationPath, account_key: &ExtendedPublicKey, network: Network) -> Result<Self> {
+        // Derive public key at <account>/<change>/<index>
+        let public_key = account_key
+            .derive_child(path.change)?
+            .derive_child(path.index)?
+            .public_key()
+            .to_vec();
+        
+        // Generate address from public key
+        let address = Self::address_from_public_key(&public_key, network);
+        
+        let name = match path.purpose {
+            _ if path.change == 1 => format!("Change {}", path.index),
+            Purpose::Bip44 => format!("Account {}", path.index),
+            purpose => format!("{} Account {}", purpose, path.index),
+        };
         
         Ok(Self {
-            index,
-            name: format!("Account {}", index),
+            index: path.index,
+            name,
             address,
             public_key,
             balance: 0,
+            kind: AccountKind::Hd,
+            utxos: Vec::new(),
+            path: Some(path),
         })
     }
     
+    /// Create a watch-only account for an external address
+    pub fn watch_only(address: String) -> Self {
+        Self {
+            index: 0,
+            name: "Watch-only".to_string(),
+            address,
+            public_key: Vec::new(),
+            balance: 0,
+            kind: AccountKind::WatchOnly,
+            utxos: Vec::new(),
+            path: None,
+        }
+    }
+    
+    /// Create an account for a private key held in imported-key slot `slot`
+    pub fn imported(slot: u32, public_key: Vec<u8>, network: Network) -> Self {
+        Self {
+            index: slot,
+            name: format!("Imported {}", slot),
+            address: Self::address_from_public_key(&public_key, network),
+            public_key,
+            balance: 0,
+            kind: AccountKind::Imported,
+            utxos: Vec::new(),
+            path: None,
+        }
+    }
+    
+    /// Derive an address from a public key
+    ///
+    /// Format: `<network prefix><hex(payload (20 bytes) || checksum (4 bytes))>`
+    pub fn address_from_public_key(public_key: &[u8], network: Network) -> String {
+        let prefix = network.address_prefix();
+        let hash = blake3::hash(public_key);
+        let payload = &hash.as_bytes()[..ADDRESS_PAYLOAD_LEN];
+        
+        let mut body = payload.to_vec();
+        body.extend_from_slice(&Self::address_checksum(prefix, payload));
+        
+        format!("{}{}", prefix, hex::encode(body))
+    }
+    
+    /// Validate address prefix, body length and checksum
+    pub fn validate_address(address: &str) -> bool {
+        if address.len() > MAX_ADDRESS_LEN {
+            return false;
+        }
+        
+        let network = match Network::from_address(address) {
+            Some(network) => network,
+            None => return false,
+        };
+        
+        let prefix = network.address_prefix();
+        let body = match hex::decode(&address[prefix.len()..]) {
+            Ok(body) => body,
+            Err(_) => return false,
+        };
+        if body.len() != ADDRESS_PAYLOAD_LEN + ADDRESS_CHECKSUM_LEN {
+            return false;
+        }
+        
+        let (payload, checksum) = body.split_at(ADDRESS_PAYLOAD_LEN);
+        Self::address_checksum(prefix, payload)[..] == *checksum
+    }
+    
+    /// Checksum over the prefix and payload, so a body moved to another network fails
+    fn address_checksum(prefix: &str, payload: &[u8]) -> [u8; ADDRESS_CHECKSUM_LEN] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(prefix.as_bytes());
+        hasher.update(payload);
+        
+        let mut checksum = [0u8; ADDRESS_CHECKSUM_LEN];
+        checksum.copy_from_slice(&hasher.finalize().as_bytes()[..ADDRESS_CHECKSUM_LEN]);
+        checksum
+    }
+    
     /// Get account index
     pub fn index(&self) -> u32 {
         self.index
@@ -58,9 +187,11 @@ impl Account {
         &self.name
     }
     
-    /// Set account name
-    pub fn set_name(&mut self, name: String) {
+    /// Set account name, rejecting blank names and names over `MAX_NAME_LEN`
+    pub fn set_name(&mut self, name: String) -> Result<()> {
+        validate_name(&name)?;
         self.name = name;
+        Ok(())
     }
     
     /// Get address
@@ -82,22 +213,133 @@ impl Account {
     pub fn set_balance(&mut self, balance: u64) {
         self.balance = balance;
     }
+    
+    /// Get unspent outputs
+    pub fn utxos(&self) -> &[Utxo] {
+        &self.utxos
+    }
+    
+    /// Set unspent outputs
+    pub fn set_utxos(&mut self, utxos: Vec<Utxo>) {
+        self.utxos = utxos;
+    }
+    
+    /// Get key source
+    pub fn kind(&self) -> AccountKind {
+        self.kind
+    }
+    
+    /// Get derivation path (only recorded for HD accounts)
+    pub fn path(&self) -> Option<DerivationPath> {
+        self.path
+    }
+    
+    /// Is this a watch-only account
+    pub fn is_watch_only(&self) -> bool {
+        self.kind == AccountKind::WatchOnly
+    }
+    
+    /// Is this an internal account holding change, rather than one the user added
+    pub fn is_change(&self) -> bool {
+        self.path.is_some_and(|path| path.change == 1)
+    }
+}
+
+/// Check a display name is not blank and at most `MAX_NAME_LEN` characters
+pub(crate) fn validate_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(MobileError::EmptyName);
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(MobileError::NameTooLong);
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hd::ExtendedPrivateKey;
+    
+    fn account_key(seed: u8) -> ExtendedPublicKey {
+        ExtendedPrivateKey::from_seed(&[seed; 64]).unwrap().public_key()
+    }
     
     #[test]
     fn test_account_creation() {
-        let account = Account::new(0);
+        let account = Account::new(0, &account_key(1), Network::Mainnet);
         assert!(account.is_ok());
     }
     
     #[test]
     fn test_account_properties() {
-        let account = Account::new(0).unwrap();
+        let account = Account::new(0, &account_key(1), Network::Mainnet).unwrap();
         assert_eq!(account.index(), 0);
         assert!(!account.address().is_empty());
     }
+    
+    #[test]
+    fn test_account_derivation() {
+        let first = Account::new(0, &account_key(1), Network::Mainnet).unwrap();
+        let second = Account::new(1, &account_key(1), Network::Mainnet).unwrap();
+        let other_seed = Account::new(0, &account_key(2), Network::Mainnet).unwrap();
+        
+        assert_eq!(first.address(), Account::new(0, &account_key(1), Network::Mainnet).unwrap().address());
+        assert_eq!(first.public_key().len(), 33);
+        assert_ne!(first.address(), second.address());
+        assert_ne!(first.address(), other_seed.address());
+    }
+    
+    #[test]
+    fn test_account_network_prefix() {
+        let mainnet = Account::new(0, &account_key(1), Network::Mainnet).unwrap();
+        let testnet = Account::new(0, &account_key(1), Network::Testnet).unwrap();
+        
+        assert!(mainnet.address().starts_with("silver_"));
+        assert!(testnet.address().starts_with("tsilver_"));
+    }
+    
+    #[test]
+    fn test_validate_address() {
+        let account = Account::new(0, &account_key(1), Network::Mainnet).unwrap();
+        assert!(Account::validate_address(account.address()));
+        
+        let testnet = Account::new(0, &account_key(1), Network::Testnet).unwrap();
+        assert!(Account::validate_address(testnet.address()));
+    }
+    
+    #[test]
+    fn test_set_name_limits() {
+        let mut account = Account::new(0, &account_key(1), Network::Mainnet).unwrap();
+        account.set_name("Savings".to_string()).unwrap();
+        account.set_name("é".repeat(MAX_NAME_LEN)).unwrap();
+        
+        assert!(matches!(account.set_name("  ".to_string()), Err(MobileError::EmptyName)));
+        assert!(matches!(account.set_name("x".repeat(MAX_NAME_LEN + 1)), Err(MobileError::NameTooLong)));
+        assert_eq!(account.name(), "é".repeat(MAX_NAME_LEN));
+    }
+    
+    #[test]
+    fn test_validate_address_wrong_prefix() {
+        let account = Account::new(0, &account_key(1), Network::Mainnet).unwrap();
+        let body = account.address().strip_prefix("silver_").unwrap();
+        
+        assert!(!Account::validate_address(&format!("bitcoin_{}", body)));
+        assert!(!Account::validate_address(&format!("tsilver_{}", body)));
+    }
+    
+    #[test]
+    fn test_validate_address_checksum() {
+        let account = Account::new(0, &account_key(1), Network::Mainnet).unwrap();
+        let address = account.address();
+        
+        // Flip a single hex character in the body
+        let last = address.chars().last().unwrap();
+        let flipped = if last == '0' { '1' } else { '0' };
+        let tampered = format!("{}{}", &address[..address.len() - 1], flipped);
+        assert!(!Account::validate_address(&tampered));
+        
+        // Wrong body length
+        assert!(!Account::validate_address(&address[..address.len() - 2]));
+    }
 }
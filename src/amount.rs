@@ -0,0 +1,80 @@
+//! Conversion between base units and decimal display amounts
+
+use crate::errors::{MobileError, Result};
+
+/// Decimal places of one coin
+pub const DECIMALS: u8 = 8;
+
+/// Format base units as a decimal string, trimming trailing zeros
+///
+/// `format_units(123_456_789, 8)` is `"1.23456789"`; `format_units(100_000_000, 8)` is `"1"`.
+pub fn format_units(value: u64, decimals: u8) -> String {
+    let decimals = decimals as usize;
+    let digits = format!("{:0>width$}", value, width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// Parse a decimal string into base units
+///
+/// Rejects signs, exponents, stray characters, more than `decimals` fraction
+/// digits and values that don't fit in a `u64`.
+pub fn parse_units(s: &str, decimals: u8) -> Result<u64> {
+    let s = s.trim();
+    let (whole, fraction) = match s.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (s, ""),
+    };
+    
+    // Digits on both sides of the point, if there is one
+    let is_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    if !is_digits(whole) || (s.contains('.') && !is_digits(fraction)) {
+        return Err(MobileError::InvalidAmount);
+    }
+    if fraction.len() > decimals as usize {
+        return Err(MobileError::InvalidAmount);
+    }
+    
+    let digits = format!("{}{:0<width$}", whole, fraction, width = decimals as usize);
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    digits.parse().map_err(|_| MobileError::InvalidAmount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_round_trip() {
+        let value = parse_units("1.23456789", DECIMALS).unwrap();
+        assert_eq!(value, 123_456_789);
+        assert_eq!(format_units(value, DECIMALS), "1.23456789");
+        
+        assert_eq!(format_units(100_000_000, DECIMALS), "1");
+        assert_eq!(format_units(50, DECIMALS), "0.0000005");
+        assert_eq!(format_units(0, DECIMALS), "0");
+        assert_eq!(parse_units("0.5", DECIMALS).unwrap(), 50_000_000);
+        assert_eq!(format_units(u64::MAX, DECIMALS), "184467440737.09551615");
+        assert_eq!(parse_units("184467440737.09551615", DECIMALS).unwrap(), u64::MAX);
+    }
+    
+    #[test]
+    fn test_rejects_invalid_amounts() {
+        for input in ["1.234567891", "184467440737.09551616", "", ".5", "1.", "-1", "1e8", "1.2.3", "1,5"] {
+            assert!(
+                matches!(parse_units(input, DECIMALS), Err(MobileError::InvalidAmount)),
+                "accepted {:?}",
+                input
+            );
+        }
+    }
+}
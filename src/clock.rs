@@ -0,0 +1,27 @@
+//! Time sources
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of the current time, so tests can replace the device clock
+pub trait Clock: Send + Sync {
+    /// Current time
+    fn now(&self) -> SystemTime;
+    
+    /// Seconds since the Unix epoch, or 0 when the clock is set before it
+    fn unix_secs(&self) -> u64 {
+        self.now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0)
+    }
+}
+
+/// The device clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
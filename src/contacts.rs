@@ -0,0 +1,55 @@
+//! Address book
+
+use serde::{Deserialize, Serialize};
+use crate::account::Account;
+use crate::errors::{MobileError, Result};
+
+/// Address book entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contact {
+    /// Unique label
+    pub label: String,
+    
+    /// Address
+    pub address: String,
+    
+    /// Optional note
+    pub note: Option<String>,
+}
+
+impl Contact {
+    /// Create a contact, validating the label and address
+    pub fn new(label: String, address: String, note: Option<String>) -> Result<Self> {
+        if label.trim().is_empty() {
+            return Err(MobileError::InvalidContact);
+        }
+        
+        if !Account::validate_address(&address) {
+            return Err(MobileError::InvalidAddress);
+        }
+        
+        Ok(Self {
+            label,
+            address,
+            note,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    
+    #[test]
+    fn test_contact_validation() {
+        let address = Account::address_from_public_key(&[2u8; 33], Network::Mainnet);
+        assert!(Contact::new("Alice".to_string(), address.clone(), None).is_ok());
+        
+        let result = Contact::new(" ".to_string(), address, None);
+        assert!(matches!(result, Err(MobileError::InvalidContact)));
+        
+        let result = Contact::new("Bob".to_string(), "silver_bogus".to_string(), None);
+        assert!(matches!(result, Err(MobileError::InvalidAddress)));
+    }
+}
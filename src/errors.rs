@@ -8,27 +8,114 @@ pub enum MobileError {
     #[error("No wallet loaded")]
     NoWalletLoaded,
     
+    #[error("Wallet not found")]
+    WalletNotFound,
+    
+    #[error("Account not found")]
+    AccountNotFound,
+    
+    #[error("Cannot remove the last account")]
+    CannotRemoveLastAccount,
+    
+    #[error("Account limit reached")]
+    AccountLimitReached,
+    
+    #[error("Account already exists")]
+    DuplicateAccount,
+    
+    #[error("Watch-only account cannot sign")]
+    WatchOnlyAccount,
+    
+    #[error("Contact not found")]
+    ContactNotFound,
+    
+    #[error("Contact label already in use")]
+    DuplicateContact,
+    
+    #[error("Invalid contact")]
+    InvalidContact,
+    
     #[error("Invalid password")]
     InvalidPassword,
     
+    #[error("Wallet is locked")]
+    WalletLocked,
+    
+    #[error("Invalid PIN")]
+    InvalidPin,
+    
     #[error("Wallet creation failed: {0}")]
     WalletCreationFailed(String),
     
     #[error("Invalid mnemonic")]
     InvalidMnemonic,
     
+    #[error("Word position is outside the mnemonic")]
+    InvalidWordPosition,
+    
+    #[error("Mnemonic is a publicly known test phrase")]
+    WeakMnemonic,
+    
     #[error("Insufficient balance")]
     InsufficientBalance,
     
     #[error("Invalid transaction")]
     InvalidTransaction,
     
+    #[error("Invalid amount")]
+    InvalidAmount,
+    
+    #[error("Invalid fee rate")]
+    InvalidFeeRate,
+    
+    #[error("Send would exceed the daily spend limit")]
+    SpendLimitExceeded,
+    
+    #[error("Transaction has expired")]
+    TransactionExpired,
+    
+    #[error("Change address is not one of this wallet's change addresses")]
+    UnknownChangeAddress,
+    
+    #[error("Amount is below the dust limit")]
+    DustAmount,
+    
+    #[error("Memo is too long")]
+    MemoTooLong,
+    
+    #[error("Name cannot be empty")]
+    EmptyName,
+    
+    #[error("Name is too long")]
+    NameTooLong,
+    
+    #[error("Invalid address")]
+    InvalidAddress,
+    
+    #[error("Address is too long")]
+    AddressTooLong,
+    
+    #[error("Address belongs to a different network")]
+    NetworkMismatch,
+    
+    #[error("Transaction not found")]
+    TransactionNotFound,
+    
+    #[error("Transaction has already been broadcast")]
+    AlreadyBroadcast,
+    
     #[error("Transaction failed: {0}")]
     TransactionFailed(String),
     
+    #[error("Transaction conflicts with one the node already has: {0}")]
+    TransactionConflict(String),
+    
     #[error("Sync failed: {0}")]
     SyncFailed(String),
     
+    #[error("Sync was cancelled")]
+    SyncCancelled,
+    
     #[error("Biometric authentication failed")]
     BiometricAuthFailed,
     
@@ -41,8 +128,91 @@ pub enum MobileError {
     #[error("Cryptographic error: {0}")]
     CryptoError(String),
     
-    #[error("Network error: {0}")]
-    NetworkError(String),
+    #[error("Invalid QR frame: {0}")]
+    InvalidQrFrame(String),
+    
+    #[error("Missing QR frames: {0:?}")]
+    MissingQrFrames(Vec<usize>),
+    
+    #[error("Payload of {len} bytes does not fit a version {max_version} QR code at level {level:?}")]
+    QrPayloadTooLarge {
+        len: usize,
+        level: crate::qr::ErrorCorrection,
+        max_version: u8,
+    },
+    
+    #[error("Unsupported currency: {0}")]
+    UnsupportedCurrency(String),
+    
+    #[error("Network error ({kind}): {detail}")]
+    NetworkError {
+        kind: NetworkErrorKind,
+        detail: String,
+    },
+    
+    #[error("Wallet was saved by a newer version (schema {0})")]
+    UnsupportedSchemaVersion(u32),
+    
+    #[error("Storage error: {0}")]
+    StorageError(String),
+}
+
+/// Category of a network failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkErrorKind {
+    /// Request did not complete within the timeout
+    Timeout,
+    
+    /// Node could not be reached
+    Connection,
+    
+    /// Node answered with a non-success HTTP status
+    Http(u16),
+    
+    /// Response body could not be decoded
+    Decode,
+    
+    /// Client or endpoint is misconfigured
+    InvalidConfig,
+}
+
+impl std::fmt::Display for NetworkErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            NetworkErrorKind::Timeout => write!(f, "timeout"),
+            NetworkErrorKind::Connection => write!(f, "connection"),
+            NetworkErrorKind::Http(status) => write!(f, "HTTP {}", status),
+            NetworkErrorKind::Decode => write!(f, "decode"),
+            NetworkErrorKind::InvalidConfig => write!(f, "invalid config"),
+        }
+    }
+}
+
+impl MobileError {
+    /// Build a network error
+    pub fn network(kind: NetworkErrorKind, detail: impl Into<String>) -> Self {
+        MobileError::NetworkError {
+            kind,
+            detail: detail.into(),
+        }
+    }
+}
+
+impl From<reqwest::Error> for MobileError {
+    fn from(error: reqwest::Error) -> Self {
+        let kind = if error.is_timeout() {
+            NetworkErrorKind::Timeout
+        } else if error.is_decode() {
+            NetworkErrorKind::Decode
+        } else if let Some(status) = error.status() {
+            NetworkErrorKind::Http(status.as_u16())
+        } else if error.is_builder() {
+            NetworkErrorKind::InvalidConfig
+        } else {
+            NetworkErrorKind::Connection
+        };
+        MobileError::network(kind, error.to_string())
+    }
 }
 
 /// Result type for mobile wallet operations
@@ -0,0 +1,83 @@
+//! Wallet change notifications
+
+use std::sync::Arc;
+use parking_lot::RwLock;
+use crate::transaction::MobileTransaction;
+
+/// Callback fired with the new balance
+pub type BalanceCallback = Box<dyn Fn(u64) + Send + Sync>;
+
+/// Callback fired with a new or updated transaction
+pub type TransactionCallback = Box<dyn Fn(&MobileTransaction) + Send + Sync>;
+
+/// Registered observers
+#[derive(Default)]
+pub struct WalletObservers {
+    /// Balance observers
+    balance: RwLock<Vec<Arc<dyn Fn(u64) + Send + Sync>>>,
+    
+    /// Transaction observers
+    transaction: RwLock<Vec<Arc<dyn Fn(&MobileTransaction) + Send + Sync>>>,
+}
+
+impl WalletObservers {
+    /// Create an empty observer set
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Register a balance observer
+    pub fn on_balance_changed(&self, callback: BalanceCallback) {
+        self.balance.write().push(Arc::from(callback));
+    }
+    
+    /// Register a transaction observer
+    pub fn on_transaction_update(&self, callback: TransactionCallback) {
+        self.transaction.write().push(Arc::from(callback));
+    }
+    
+    /// Notify balance observers
+    ///
+    /// Callbacks run on a snapshot of the list, so they may register further observers.
+    pub fn notify_balance(&self, balance: u64) {
+        let callbacks = self.balance.read().clone();
+        for callback in callbacks {
+            callback(balance);
+        }
+    }
+    
+    /// Notify transaction observers
+    pub fn notify_transaction(&self, transaction: &MobileTransaction) {
+        let callbacks = self.transaction.read().clone();
+        for callback in callbacks {
+            callback(transaction);
+        }
+    }
+}
+
+impl std::fmt::Debug for WalletObservers {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("WalletObservers")
+            .field("balance", &self.balance.read().len())
+            .field("transaction", &self.transaction.read().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    
+    #[test]
+    fn test_notify_balance() {
+        let observers = WalletObservers::new();
+        let seen = Arc::new(AtomicU64::new(0));
+        
+        let sink = seen.clone();
+        observers.on_balance_changed(Box::new(move |balance| sink.store(balance, Ordering::SeqCst)));
+        observers.notify_balance(42);
+        
+        assert_eq!(seen.load(Ordering::SeqCst), 42);
+    }
+}
@@ -0,0 +1,140 @@
+//! Fee estimation
+
+use serde::{Deserialize, Serialize};
+use crate::errors::{MobileError, Result};
+use crate::transaction::MobileTransaction;
+
+/// Fallback base fee rate (base units per byte) when the node hasn't reported one
+pub const DEFAULT_FEE_RATE: u64 = 10;
+
+/// Typical size of a signed single-recipient transaction in bytes, for quotes before one is built
+pub const TYPICAL_TRANSACTION_SIZE: usize = 250;
+
+/// Thousandths of a base unit in one base unit, the precision of a `FeeRate`
+const MILLIS_PER_UNIT: u64 = 1000;
+
+/// Fee rate in base units per byte, with three decimal places (e.g. 1.5 units per byte)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FeeRate(u64);
+
+impl FeeRate {
+    /// Rate of whole base units per byte
+    pub fn from_units_per_byte(units: u64) -> Self {
+        Self(units.saturating_mul(MILLIS_PER_UNIT))
+    }
+    
+    /// Rate of thousandths of a base unit per byte
+    pub fn from_millis_per_byte(millis: u64) -> Self {
+        Self(millis)
+    }
+    
+    /// Thousandths of a base unit per byte
+    pub fn millis_per_byte(&self) -> u64 {
+        self.0
+    }
+    
+    /// Fee for `tx_bytes`, rounded up so the rate is never underpaid
+    pub fn fee_for(&self, tx_bytes: usize) -> u64 {
+        let millis = u128::from(self.0) * tx_bytes as u128;
+        u64::try_from(millis.div_ceil(u128::from(MILLIS_PER_UNIT))).unwrap_or(u64::MAX)
+    }
+    
+    /// Fail on a zero rate, which would never confirm
+    pub fn check(&self) -> Result<()> {
+        if self.0 == 0 {
+            return Err(MobileError::InvalidFeeRate);
+        }
+        Ok(())
+    }
+}
+
+/// Fee priority
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FeePriority {
+    /// Low priority, cheapest
+    Low,
+    
+    /// Medium priority
+    #[default]
+    Medium,
+    
+    /// High priority, fastest confirmation
+    High,
+}
+
+impl FeePriority {
+    /// Multiplier applied to the base fee rate
+    pub fn multiplier(&self) -> u64 {
+        match self {
+            FeePriority::Low => 1,
+            FeePriority::Medium => 2,
+            FeePriority::High => 4,
+        }
+    }
+}
+
+/// Estimate the fee for a transaction of `tx_bytes` using the default fee rate
+pub fn estimate_fee(tx_bytes: usize, priority: FeePriority) -> u64 {
+    estimate_fee_with_rate(tx_bytes, priority, DEFAULT_FEE_RATE)
+}
+
+/// Estimate the fee for a transaction of `tx_bytes` using a base fee rate observed from the node
+pub fn estimate_fee_with_rate(tx_bytes: usize, priority: FeePriority, base_rate: u64) -> u64 {
+    (tx_bytes as u64)
+        .saturating_mul(base_rate)
+        .saturating_mul(priority.multiplier())
+}
+
+/// Estimate the fee for a transaction from its estimated signed size
+pub fn estimate_transaction_fee(transaction: &MobileTransaction, priority: FeePriority, base_rate: u64) -> u64 {
+    estimate_fee_with_rate(transaction.estimated_size(), priority, base_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_higher_priority_costs_more() {
+        let low = estimate_fee(TYPICAL_TRANSACTION_SIZE, FeePriority::Low);
+        let medium = estimate_fee(TYPICAL_TRANSACTION_SIZE, FeePriority::Medium);
+        let high = estimate_fee(TYPICAL_TRANSACTION_SIZE, FeePriority::High);
+        
+        assert!(low < medium);
+        assert!(medium < high);
+    }
+    
+    #[test]
+    fn test_fee_scales_with_size() {
+        let small = estimate_fee(200, FeePriority::Medium);
+        let large = estimate_fee(400, FeePriority::Medium);
+        
+        assert_eq!(large, small * 2);
+        assert_eq!(estimate_fee_with_rate(200, FeePriority::Low, 3), 600);
+    }
+    
+    #[test]
+    fn test_fee_rate_rounds_up() {
+        let rate = FeeRate::from_millis_per_byte(1500);
+        assert_eq!(rate.fee_for(200), 300);
+        assert_eq!(rate.fee_for(201), 302);
+        assert_eq!(FeeRate::from_millis_per_byte(1).fee_for(1), 1);
+        assert_eq!(FeeRate::from_units_per_byte(3).fee_for(200), 600);
+        assert_eq!(FeeRate::from_millis_per_byte(u64::MAX).fee_for(usize::MAX), u64::MAX);
+        assert!(matches!(FeeRate::from_millis_per_byte(0).check(), Err(MobileError::InvalidFeeRate)));
+    }
+    
+    #[test]
+    fn test_transaction_fee_is_size_times_rate() {
+        let tx = MobileTransaction::new(
+            "silver_sender".to_string(),
+            "silver_recipient".to_string(),
+            1000,
+            0,
+        ).unwrap();
+        let size = tx.estimated_size() as u64;
+        
+        assert_eq!(estimate_transaction_fee(&tx, FeePriority::Low, 7), size * 7);
+        assert_eq!(estimate_transaction_fee(&tx, FeePriority::High, 7), size * 7 * 4);
+    }
+}
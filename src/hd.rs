@@ -0,0 +1,407 @@
+//! BIP32 hierarchical deterministic key derivation
+
+use hmac::{Hmac, Mac};
+use secp256k1::{PublicKey, Scalar, Secp256k1, SecretKey, VerifyOnly};
+use serde::{Deserialize, Serialize};
+use sha2::Sha512;
+use zeroize::Zeroize;
+use crate::errors::{MobileError, Result};
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Offset added to hardened child indices
+pub const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+/// SLIP-44 coin type used in SilverBitcoin derivation paths
+pub const COIN_TYPE: u32 = 5342;
+
+/// Extended private key (secret key + chain code), erased when dropped
+#[derive(Clone)]
+pub struct ExtendedPrivateKey {
+    /// Secret key
+    secret_key: SecretKey,
+    
+    /// Chain code
+    chain_code: [u8; 32],
+}
+
+/// Extended public key (compressed public key + chain code)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtendedPublicKey {
+    /// Compressed SEC1 public key (33 bytes)
+    public_key: Vec<u8>,
+    
+    /// Chain code
+    chain_code: [u8; 32],
+}
+
+impl ExtendedPrivateKey {
+    /// Create the master key from a BIP39 seed
+    pub fn from_seed(seed: &[u8]) -> Result<Self> {
+        let mut mac = HmacSha512::new_from_slice(b"Bitcoin seed")
+            .map_err(|_| MobileError::CryptoError("Invalid HMAC key".to_string()))?;
+        mac.update(seed);
+        let output = mac.finalize().into_bytes();
+        
+        let (key_bytes, chain_code) = split_output(&output);
+        let secret_key = SecretKey::from_slice(&key_bytes)
+            .map_err(|_| MobileError::CryptoError("Invalid master key".to_string()))?;
+        
+        Ok(Self {
+            secret_key,
+            chain_code,
+        })
+    }
+    
+    /// Derive a child key (indices >= `HARDENED_OFFSET` are hardened)
+    pub fn derive_child(&self, index: u32) -> Result<Self> {
+        let secp = Secp256k1::new();
+        
+        let mut mac = HmacSha512::new_from_slice(&self.chain_code)
+            .map_err(|_| MobileError::CryptoError("Invalid chain code".to_string()))?;
+        if index >= HARDENED_OFFSET {
+            mac.update(&[0u8]);
+            mac.update(&self.secret_key.secret_bytes());
+        } else {
+            mac.update(&PublicKey::from_secret_key(&secp, &self.secret_key).serialize());
+        }
+        mac.update(&index.to_be_bytes());
+        let output = mac.finalize().into_bytes();
+        
+        let (tweak_bytes, chain_code) = split_output(&output);
+        let tweak = Scalar::from_be_bytes(tweak_bytes)
+            .map_err(|_| MobileError::CryptoError("Invalid child key".to_string()))?;
+        let secret_key = self.secret_key
+            .add_tweak(&tweak)
+            .map_err(|_| MobileError::CryptoError("Invalid child key".to_string()))?;
+        
+        Ok(Self {
+            secret_key,
+            chain_code,
+        })
+    }
+    
+    /// Derive a key along a path such as `m/44'/5342'/0'/0/0`
+    pub fn derive_path(&self, path: &str) -> Result<Self> {
+        parse_path(path)?
+            .into_iter()
+            .try_fold(self.clone(), |key, index| key.derive_child(index))
+    }
+    
+    /// Get secret key
+    pub fn secret_key(&self) -> &SecretKey {
+        &self.secret_key
+    }
+    
+    /// Get chain code
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+    
+    /// Get the matching extended public key
+    pub fn public_key(&self) -> ExtendedPublicKey {
+        let secp = Secp256k1::new();
+        ExtendedPublicKey {
+            public_key: PublicKey::from_secret_key(&secp, &self.secret_key).serialize().to_vec(),
+            chain_code: self.chain_code,
+        }
+    }
+}
+
+impl Drop for ExtendedPrivateKey {
+    fn drop(&mut self) {
+        self.secret_key.non_secure_erase();
+        self.chain_code.zeroize();
+    }
+}
+
+impl std::fmt::Debug for ExtendedPrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ExtendedPrivateKey").finish_non_exhaustive()
+    }
+}
+
+impl ExtendedPublicKey {
+    /// Derive a non-hardened child public key
+    pub fn derive_child(&self, index: u32) -> Result<Self> {
+        self.derive_child_with(&Secp256k1::verification_only(), index)
+    }
+    
+    /// Derive the non-hardened children at `indices`, sharing one secp256k1 context
+    pub fn derive_children(&self, indices: std::ops::Range<u32>) -> impl Iterator<Item = Result<Self>> + '_ {
+        let secp = Secp256k1::verification_only();
+        indices.map(move |index| self.derive_child_with(&secp, index))
+    }
+    
+    /// Derive a non-hardened child public key with an existing context
+    fn derive_child_with(&self, secp: &Secp256k1<VerifyOnly>, index: u32) -> Result<Self> {
+        if index >= HARDENED_OFFSET {
+            return Err(MobileError::CryptoError(
+                "Cannot derive hardened child from public key".to_string(),
+            ));
+        }
+        
+        let parent = PublicKey::from_slice(&self.public_key)
+            .map_err(|_| MobileError::CryptoError("Invalid public key".to_string()))?;
+        
+        let mut mac = HmacSha512::new_from_slice(&self.chain_code)
+            .map_err(|_| MobileError::CryptoError("Invalid chain code".to_string()))?;
+        mac.update(&self.public_key);
+        mac.update(&index.to_be_bytes());
+        let output = mac.finalize().into_bytes();
+        
+        let (tweak_bytes, chain_code) = split_output(&output);
+        let tweak = Scalar::from_be_bytes(tweak_bytes)
+            .map_err(|_| MobileError::CryptoError("Invalid child key".to_string()))?;
+        let child = parent
+            .add_exp_tweak(secp, &tweak)
+            .map_err(|_| MobileError::CryptoError("Invalid child key".to_string()))?;
+        
+        Ok(Self {
+            public_key: child.serialize().to_vec(),
+            chain_code,
+        })
+    }
+    
+    /// Get compressed public key bytes
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+    
+    /// Get chain code
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+}
+
+/// Parse a derivation path such as `m/44'/5342'/0'` into child indices
+pub fn parse_path(path: &str) -> Result<Vec<u32>> {
+    let invalid = || MobileError::CryptoError(format!("Invalid derivation path: {}", path));
+    
+    let mut parts = path.split('/');
+    if parts.next() != Some("m") {
+        return Err(invalid());
+    }
+    
+    parts
+        .map(|part| {
+            let (digits, hardened) = match part.strip_suffix('\'').or_else(|| part.strip_suffix('h')) {
+                Some(digits) => (digits, true),
+                None => (part, false),
+            };
+            let index: u32 = digits.parse().map_err(|_| invalid())?;
+            if index >= HARDENED_OFFSET {
+                return Err(invalid());
+            }
+            Ok(if hardened { index + HARDENED_OFFSET } else { index })
+        })
+        .collect()
+}
+
+/// Default BIP44 account path (`m/44'/coin'/0'`) for a coin type
+pub fn account_path(coin_type: u32) -> String {
+    format!("m/44'/{}'/0'", coin_type)
+}
+
+/// BIP43 purpose of a derivation path
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Purpose {
+    /// BIP44 (legacy)
+    #[default]
+    Bip44,
+    
+    /// BIP49 (nested segwit)
+    Bip49,
+    
+    /// BIP84 (native segwit)
+    Bip84,
+}
+
+impl Purpose {
+    /// Purpose field value
+    pub fn value(&self) -> u32 {
+        match self {
+            Purpose::Bip44 => 44,
+            Purpose::Bip49 => 49,
+            Purpose::Bip84 => 84,
+        }
+    }
+    
+    /// Purpose for a path field value
+    pub fn from_value(value: u32) -> Option<Self> {
+        match value {
+            44 => Some(Purpose::Bip44),
+            49 => Some(Purpose::Bip49),
+            84 => Some(Purpose::Bip84),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Purpose {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "BIP{}", self.value())
+    }
+}
+
+/// Address key path: `m/purpose'/coin'/account'/change/index`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DerivationPath {
+    /// Purpose
+    pub purpose: Purpose,
+    
+    /// Coin type
+    pub coin_type: u32,
+    
+    /// Account (hardened)
+    pub account: u32,
+    
+    /// Chain: 0 external, 1 change
+    pub change: u32,
+    
+    /// Address index
+    pub index: u32,
+}
+
+impl DerivationPath {
+    /// Path of an external address in the first account for a purpose
+    pub fn new(purpose: Purpose, coin_type: u32, index: u32) -> Self {
+        Self {
+            purpose,
+            coin_type,
+            account: 0,
+            change: 0,
+            index,
+        }
+    }
+    
+    /// Parse and validate a full path such as `m/84'/5342'/0'/0/3`
+    pub fn parse(path: &str) -> Result<Self> {
+        let invalid = || MobileError::CryptoError(format!("Invalid derivation path: {}", path));
+        
+        let indices = parse_path(path)?;
+        let [purpose, coin_type, account, change, index] = indices[..] else {
+            return Err(invalid());
+        };
+        
+        let hardened = |value: u32| value.checked_sub(HARDENED_OFFSET);
+        let (Some(purpose), Some(coin_type), Some(account)) =
+            (hardened(purpose), hardened(coin_type), hardened(account))
+        else {
+            return Err(invalid());
+        };
+        if change >= HARDENED_OFFSET || index >= HARDENED_OFFSET || change > 1 {
+            return Err(invalid());
+        }
+        
+        Ok(Self {
+            purpose: Purpose::from_value(purpose).ok_or_else(invalid)?,
+            coin_type,
+            account,
+            change,
+            index,
+        })
+    }
+    
+    /// Hardened account prefix (`m/purpose'/coin'/account'`)
+    pub fn account_path(&self) -> String {
+        format!("m/{}'/{}'/{}'", self.purpose.value(), self.coin_type, self.account)
+    }
+}
+
+impl std::fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}/{}/{}", self.account_path(), self.change, self.index)
+    }
+}
+
+impl std::str::FromStr for DerivationPath {
+    type Err = MobileError;
+    
+    fn from_str(path: &str) -> Result<Self> {
+        Self::parse(path)
+    }
+}
+
+/// Split an HMAC-SHA512 output into key material and chain code
+fn split_output(output: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let mut left = [0u8; 32];
+    let mut right = [0u8; 32];
+    left.copy_from_slice(&output[..32]);
+    right.copy_from_slice(&output[32..64]);
+    (left, right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_bip32_vector_1() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = ExtendedPrivateKey::from_seed(&seed).unwrap();
+        assert_eq!(
+            hex::encode(master.secret_key().secret_bytes()),
+            "e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35"
+        );
+        assert_eq!(
+            hex::encode(master.chain_code()),
+            "873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508"
+        );
+        
+        let child = master.derive_path("m/0'/1").unwrap();
+        assert_eq!(
+            hex::encode(child.secret_key().secret_bytes()),
+            "3c6cb8d0f6a264c91ea8b5030fadaa8e538b020f0a387421a12de9319dc93368"
+        );
+        assert_eq!(
+            hex::encode(child.public_key().public_key()),
+            "03501e454bf00751f24b1b489aa925215d66af2234e3891c3b21a52bedb3cd711c"
+        );
+    }
+    
+    #[test]
+    fn test_public_derivation_matches_private() {
+        let seed = [7u8; 64];
+        let account = ExtendedPrivateKey::from_seed(&seed)
+            .unwrap()
+            .derive_path(&account_path(COIN_TYPE))
+            .unwrap();
+        
+        let from_private = account.derive_path("m/0/3").unwrap().public_key();
+        let from_public = account
+            .public_key()
+            .derive_child(0)
+            .unwrap()
+            .derive_child(3)
+            .unwrap();
+        assert_eq!(from_private, from_public);
+        
+        let children: Vec<ExtendedPublicKey> = account
+            .public_key()
+            .derive_children(0..4)
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(children[3], account.public_key().derive_child(3).unwrap());
+    }
+    
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(parse_path("m/44'/0h/1").unwrap(), vec![44 + HARDENED_OFFSET, HARDENED_OFFSET, 1]);
+        assert!(parse_path("44'/0'").is_err());
+        assert!(parse_path("m/abc").is_err());
+    }
+    
+    #[test]
+    fn test_derivation_path_parse() {
+        let path = DerivationPath::parse("m/84'/5342'/0'/0/3").unwrap();
+        assert_eq!(path, DerivationPath::new(Purpose::Bip84, COIN_TYPE, 3));
+        assert_eq!(path.to_string(), "m/84'/5342'/0'/0/3");
+        
+        // Unknown purpose, unhardened account level, hardened index, too short
+        assert!(DerivationPath::parse("m/45'/5342'/0'/0/3").is_err());
+        assert!(DerivationPath::parse("m/44'/5342'/0/0/3").is_err());
+        assert!(DerivationPath::parse("m/44'/5342'/0'/0/3'").is_err());
+        assert!(DerivationPath::parse("m/44'/5342'/0'").is_err());
+    }
+}
@@ -0,0 +1,240 @@
+//! Transaction history filtering and export
+
+use serde::{Deserialize, Serialize};
+use crate::amount;
+use crate::transaction::{MobileTransaction, TransactionStatus};
+
+/// Column order of the history export, also the CSV header row
+pub const EXPORT_COLUMNS: [&str; 7] = ["id", "timestamp", "direction", "amount", "fee", "status", "counterparty"];
+
+/// Direction of a transaction relative to the wallet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    /// Sent from one of the wallet's addresses
+    Sent,
+    
+    /// Received by one of the wallet's addresses
+    Received,
+    
+    /// Sent from one of the wallet's addresses to others of its own
+    SelfTransfer,
+}
+
+impl Direction {
+    /// Direction of a transaction given the wallet's own addresses (None when it involves none)
+    pub fn classify(transaction: &MobileTransaction, addresses: &[&str]) -> Option<Self> {
+        let owned = |address: &str| addresses.contains(&address);
+        let recipients = transaction.recipients();
+        let sent = owned(&transaction.from);
+        
+        if sent && recipients.iter().all(|output| owned(&output.address)) {
+            Some(Direction::SelfTransfer)
+        } else if sent {
+            Some(Direction::Sent)
+        } else if recipients.iter().any(|output| owned(&output.address)) {
+            Some(Direction::Received)
+        } else {
+            None
+        }
+    }
+}
+
+/// History filter (unset fields match everything)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryFilter {
+    /// Only transactions with this status
+    pub status: Option<TransactionStatus>,
+    
+    /// Only transactions in this direction
+    pub direction: Option<Direction>,
+    
+    /// Only transactions at or after this timestamp
+    pub since: Option<u64>,
+    
+    /// Only transactions at or before this timestamp
+    pub until: Option<u64>,
+}
+
+impl HistoryFilter {
+    /// Does a transaction match, given the wallet's own addresses
+    pub fn matches(&self, transaction: &MobileTransaction, addresses: &[&str]) -> bool {
+        if self.status.is_some_and(|status| status != transaction.status) {
+            return false;
+        }
+        
+        if self.direction.is_some() && Direction::classify(transaction, addresses) != self.direction {
+            return false;
+        }
+        
+        if self.since.is_some_and(|since| transaction.timestamp < since) {
+            return false;
+        }
+        
+        if self.until.is_some_and(|until| transaction.timestamp > until) {
+            return false;
+        }
+        
+        true
+    }
+}
+
+/// One row of the history export
+///
+/// Fields appear in `EXPORT_COLUMNS` order in both formats:
+/// - `timestamp` is ISO-8601 UTC, e.g. `2024-01-02T03:04:05Z`
+/// - `direction` is `sent`, `received` or `internal` (between the wallet's own accounts)
+/// - `amount` and `fee` are decimal coins, e.g. `1.5`; a batch reports its total
+/// - `status` is `pending`, `confirmed`, `failed`, `cancelled`, `replaced` (superseded by a
+///   fee bump) or `expired` (still pending past its `valid_until`)
+/// - `counterparty` is the other address (the recipient for sent and internal transfers)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportRecord {
+    /// Transaction ID
+    pub id: String,
+    
+    /// Timestamp
+    pub timestamp: String,
+    
+    /// Direction
+    pub direction: &'static str,
+    
+    /// Amount
+    pub amount: String,
+    
+    /// Fee
+    pub fee: String,
+    
+    /// Status
+    pub status: &'static str,
+    
+    /// Counterparty address
+    pub counterparty: String,
+}
+
+impl ExportRecord {
+    /// Build the export row of a transaction, given the wallet's own addresses
+    pub fn new(transaction: &MobileTransaction, addresses: &[&str]) -> Self {
+        let (direction, counterparty) = match Direction::classify(transaction, addresses) {
+            Some(Direction::SelfTransfer) => ("internal", &transaction.to),
+            Some(Direction::Received) => ("received", &transaction.from),
+            _ => ("sent", &transaction.to),
+        };
+        let status = match transaction.status {
+            TransactionStatus::Pending => "pending",
+            TransactionStatus::Confirmed => "confirmed",
+            TransactionStatus::Failed => "failed",
+            TransactionStatus::Cancelled => "cancelled",
+            TransactionStatus::Replaced => "replaced",
+            TransactionStatus::Expired => "expired",
+        };
+        
+        Self {
+            id: transaction.id.clone(),
+            timestamp: format_timestamp(transaction.timestamp),
+            direction,
+            amount: amount::format_units(transaction.amount, amount::DECIMALS),
+            fee: amount::format_units(transaction.fee, amount::DECIMALS),
+            status,
+            counterparty: counterparty.clone(),
+        }
+    }
+    
+    /// Fields in `EXPORT_COLUMNS` order
+    fn fields(&self) -> [&str; 7] {
+        [&self.id, &self.timestamp, self.direction, &self.amount, &self.fee, self.status, &self.counterparty]
+    }
+}
+
+/// Render rows as CSV with a header row, quoting fields only where needed
+pub fn to_csv(records: &[ExportRecord]) -> String {
+    let mut csv = EXPORT_COLUMNS.join(",");
+    csv.push('\n');
+    for record in records {
+        let row: Vec<String> = record.fields().iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quote a CSV field containing a separator, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Format Unix seconds as an ISO-8601 UTC timestamp
+pub fn format_timestamp(secs: u64) -> String {
+    let days = secs / 86_400;
+    let seconds = secs % 86_400;
+    
+    // Civil date from days since 1970-01-01 (proleptic Gregorian calendar)
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_filter_matches() {
+        let mut tx = MobileTransaction::new(
+            "silver_abc123".to_string(),
+            "silver_def456".to_string(),
+            1000,
+            100,
+        ).unwrap();
+        tx.timestamp = 50;
+        
+        assert!(HistoryFilter::default().matches(&tx, &[]));
+        
+        let sent = HistoryFilter {
+            direction: Some(Direction::Sent),
+            ..Default::default()
+        };
+        assert!(sent.matches(&tx, &["silver_abc123"]));
+        assert!(!sent.matches(&tx, &["silver_def456"]));
+        
+        let range = HistoryFilter {
+            since: Some(10),
+            until: Some(40),
+            ..Default::default()
+        };
+        assert!(!range.matches(&tx, &[]));
+    }
+    
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(1_704_164_645), "2024-01-02T03:04:05Z");
+    }
+    
+    #[test]
+    fn test_csv_quotes_fields() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
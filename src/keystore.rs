@@ -1,7 +1,178 @@
 //! Keystore for mobile wallet
 
+use std::sync::Arc;
+use secp256k1::SecretKey;
 use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, Zeroizing};
 use crate::errors::{MobileError, Result};
+use crate::mnemonic::{self, DEFAULT_WORD_COUNT};
+use crate::network::Network;
+use crate::security;
+
+/// Length of the random salt used for password key derivation
+const SALT_LEN: usize = 16;
+
+/// Length of the ChaCha20-Poly1305 nonce prefixed to each ciphertext
+const NONCE_LEN: usize = 12;
+
+/// Current version of the portable keystore JSON format
+pub const KEYSTORE_JSON_VERSION: u32 = 1;
+
+/// Most memory, in KiB, an imported document may ask key derivation for (256 MiB)
+pub const MAX_KDF_M_COST: u32 = 256 * 1024;
+
+/// Most passes an imported document may ask key derivation for
+pub const MAX_KDF_T_COST: u32 = 16;
+
+/// Most lanes an imported document may ask key derivation for
+pub const MAX_KDF_P_COST: u32 = 16;
+
+/// Identifier of the built-in software backend
+pub const SOFTWARE_BACKEND_ID: &str = "software";
+
+/// Known plaintext sealed in software to check the password of hardware-backed keystores
+const PASSWORD_CHECK: &[u8] = b"silver-mobile-keystore";
+
+/// Encrypts and decrypts keystore secrets
+///
+/// Platforms can implement this over the Secure Enclave or Android Keystore
+/// and inject it over FFI, so the mnemonic is only ever sealed in hardware.
+pub trait KeystoreBackend: Send + Sync {
+    /// Stable identifier recorded in the keystore, checked when the backend is attached
+    fn id(&self) -> &str;
+    
+    /// Encrypt a secret
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>>;
+    
+    /// Decrypt a secret produced by `encrypt`
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>>;
+}
+
+/// Source of one account's signing key
+///
+/// Signing goes through this whichever way the key is held: derived from the wallet
+/// seed, or imported on its own.
+pub trait KeyProvider {
+    /// Secret key, unlocked with `password`
+    fn secret_key(&self, password: &str) -> Result<SecretKey>;
+}
+
+impl<T: KeyProvider + ?Sized> KeyProvider for &T {
+    fn secret_key(&self, password: &str) -> Result<SecretKey> {
+        (**self).secret_key(password)
+    }
+}
+
+/// Software encryption with ChaCha20-Poly1305 under an Argon2id password key
+pub struct SoftwareBackend {
+    key: Zeroizing<[u8; 32]>,
+}
+
+impl SoftwareBackend {
+    /// Derive the encryption key from a password, salt and costs
+    pub fn new(password: &str, salt: &[u8], kdf: &KdfConfig) -> Result<Self> {
+        Ok(Self {
+            key: Keystore::derive_key(password, salt, kdf)?,
+        })
+    }
+}
+
+impl std::fmt::Debug for SoftwareBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("SoftwareBackend").finish_non_exhaustive()
+    }
+}
+
+impl KeystoreBackend for SoftwareBackend {
+    fn id(&self) -> &str {
+        SOFTWARE_BACKEND_ID
+    }
+    
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        Keystore::encrypt(plaintext, &self.key)
+    }
+    
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        Keystore::decrypt(ciphertext, &self.key)
+    }
+}
+
+/// Attached hardware backend (never serialized)
+#[derive(Clone, Default)]
+struct BackendSlot(Option<Arc<dyn KeystoreBackend>>);
+
+impl std::fmt::Debug for BackendSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.0 {
+            Some(backend) => write!(f, "BackendSlot({})", backend.id()),
+            None => f.write_str("BackendSlot(None)"),
+        }
+    }
+}
+
+/// Argon2id cost parameters for password key derivation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfConfig {
+    /// Memory cost in KiB
+    pub m_cost: u32,
+    
+    /// Number of passes
+    pub t_cost: u32,
+    
+    /// Degree of parallelism
+    pub p_cost: u32,
+}
+
+impl Default for KdfConfig {
+    /// Argon2's recommended defaults (19 MiB, 2 passes, 1 lane)
+    fn default() -> Self {
+        use argon2::Params;
+        
+        Self {
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl KdfConfig {
+    /// Check the costs are accepted by Argon2
+    pub fn validate(&self) -> Result<()> {
+        self.params().map(|_| ())
+    }
+    
+    /// Argon2 parameters producing a 32-byte key
+    fn params(&self) -> Result<argon2::Params> {
+        argon2::Params::new(self.m_cost, self.t_cost, self.p_cost, Some(32))
+            .map_err(|_| MobileError::KeystoreError("Invalid key derivation parameters".to_string()))
+    }
+}
+
+/// Imported private key, encrypted on its own under a salt separate from the keystore's
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedKey {
+    /// Salt
+    salt: Vec<u8>,
+    
+    /// Key derivation costs used with `salt`
+    #[serde(default)]
+    kdf: KdfConfig,
+    
+    /// Encrypted hex of the private key
+    encrypted_key: Vec<u8>,
+}
+
+/// Builder for a keystore with a generated or supplied mnemonic
+#[derive(Clone)]
+pub struct KeystoreBuilder {
+    word_count: usize,
+    mnemonic: Option<Zeroizing<String>>,
+    passphrase: Zeroizing<String>,
+    kdf: KdfConfig,
+    backend: BackendSlot,
+    allow_weak_mnemonic: bool,
+}
 
 /// Keystore
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,117 +183,681 @@ pub struct Keystore {
     /// Salt
     salt: Vec<u8>,
     
-    /// Master key
-    master_key: Vec<u8>,
+    /// Key derivation costs used with `salt` (Argon2 defaults for older keystores)
+    #[serde(default)]
+    kdf: KdfConfig,
+    
+    /// Encrypted BIP39 passphrase (absent when the seed uses no passphrase)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encrypted_passphrase: Option<Vec<u8>>,
+    
+    /// Encrypted imported private keys, addressed by slot
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    encrypted_keys: Vec<Vec<u8>>,
+    
+    /// Hardware backend the secrets are sealed with (software when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    backend_id: Option<String>,
+    
+    /// `PASSWORD_CHECK` sealed under the password key, for hardware-backed keystores
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password_check: Option<Vec<u8>>,
+    
+    /// Hardware backend, attached at runtime
+    #[serde(skip)]
+    backend: BackendSlot,
 }
 
-impl Keystore {
-    /// Create a new keystore
-    pub fn new(password: &str) -> Result<Self> {
+/// Portable keystore document (version 1)
+#[derive(Serialize, Deserialize)]
+struct KeystoreDocument {
+    version: u32,
+    network: Network,
+    kdf: KdfParams,
+    cipher: String,
+    salt: String,
+    mnemonic: CipherText,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    passphrase: Option<CipherText>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    imported_keys: Vec<CipherText>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    keys: Vec<ImportedKeyDocument>,
+}
+
+/// Independently encrypted imported key in a portable document
+#[derive(Serialize, Deserialize)]
+struct ImportedKeyDocument {
+    kdf: KdfParams,
+    salt: String,
+    key: CipherText,
+}
+
+/// Password key derivation parameters
+#[derive(Serialize, Deserialize, PartialEq, Eq)]
+struct KdfParams {
+    algorithm: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+/// Hex-encoded nonce and ciphertext (with tag)
+#[derive(Serialize, Deserialize)]
+struct CipherText {
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Just enough of a document to check its version before parsing the rest
+#[derive(Deserialize)]
+struct VersionProbe {
+    version: u32,
+}
+
+impl KdfParams {
+    /// Document form of a keystore's costs
+    fn from_config(config: &KdfConfig) -> Self {
+        Self {
+            algorithm: "argon2id".to_string(),
+            m_cost: config.m_cost,
+            t_cost: config.t_cost,
+            p_cost: config.p_cost,
+        }
+    }
+    
+    /// Costs described by a document, rejecting other algorithms and costs over the maximums
+    fn to_config(&self) -> Result<KdfConfig> {
+        if self.algorithm != "argon2id" {
+            return Err(MobileError::KeystoreError("Unsupported key derivation parameters".to_string()));
+        }
+        if self.m_cost > MAX_KDF_M_COST || self.t_cost > MAX_KDF_T_COST || self.p_cost > MAX_KDF_P_COST {
+            return Err(MobileError::KeystoreError("Key derivation costs too high".to_string()));
+        }
+        
+        let config = KdfConfig {
+            m_cost: self.m_cost,
+            t_cost: self.t_cost,
+            p_cost: self.p_cost,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl Default for KeystoreBuilder {
+    fn default() -> Self {
+        Self {
+            word_count: DEFAULT_WORD_COUNT,
+            mnemonic: None,
+            passphrase: Zeroizing::new(String::new()),
+            kdf: KdfConfig::default(),
+            backend: BackendSlot::default(),
+            allow_weak_mnemonic: false,
+        }
+    }
+}
+
+impl std::fmt::Debug for KeystoreBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("KeystoreBuilder")
+            .field("word_count", &self.word_count)
+            .field("kdf", &self.kdf)
+            .field("backend", &self.backend)
+            .field("allow_weak_mnemonic", &self.allow_weak_mnemonic)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeystoreBuilder {
+    /// Word count of a generated mnemonic
+    pub fn word_count(mut self, word_count: usize) -> Self {
+        self.word_count = word_count;
+        self
+    }
+    
+    /// Use an existing mnemonic instead of generating one
+    pub fn mnemonic(mut self, mnemonic: &str) -> Self {
+        self.mnemonic = Some(Zeroizing::new(mnemonic.to_string()));
+        self
+    }
+    
+    /// BIP39 passphrase (the "25th word")
+    pub fn passphrase(mut self, passphrase: &str) -> Self {
+        self.passphrase = Zeroizing::new(passphrase.to_string());
+        self
+    }
+    
+    /// Key derivation costs
+    pub fn kdf(mut self, kdf: KdfConfig) -> Self {
+        self.kdf = kdf;
+        self
+    }
+    
+    /// Seal the secrets with a hardware backend instead of in software
+    pub fn backend(mut self, backend: Arc<dyn KeystoreBackend>) -> Self {
+        self.backend = BackendSlot(Some(backend));
+        self
+    }
+    
+    /// Accept a mnemonic on the known-weak blocklist (for deliberate test use)
+    pub fn allow_weak_mnemonic(mut self) -> Self {
+        self.allow_weak_mnemonic = true;
+        self
+    }
+    
+    /// Build the keystore, encrypting it under `password`
+    pub fn build(self, password: &str) -> Result<Keystore> {
         use rand::Rng;
         
+        self.kdf.validate()?;
+        
+        // Normalize and validate a supplied mnemonic, so the stored phrase and seed
+        // don't depend on how it was pasted, or generate one
+        let mnemonic = match self.mnemonic {
+            Some(mnemonic) => {
+                let mnemonic = mnemonic::normalize(&mnemonic);
+                mnemonic::validate(&mnemonic)?;
+                if !self.allow_weak_mnemonic && mnemonic::is_weak(&mnemonic) {
+                    return Err(MobileError::WeakMnemonic);
+                }
+                mnemonic
+            }
+            None => mnemonic::generate(self.word_count)?,
+        };
+        
         // Generate random salt
         let mut rng = rand::thread_rng();
-        let salt: Vec<u8> = (0..16).map(|_| rng.gen()).collect();
+        let salt: Vec<u8> = (0..SALT_LEN).map(|_| rng.gen()).collect();
         
         // Derive master key from password
-        let master_key = Self::derive_key(password, &salt)?;
+        let master_key = Keystore::derive_key(password, &salt, &self.kdf)?;
         
-        // Generate mnemonic
-        let mnemonic = Self::generate_mnemonic()?;
+        // A hardware backend seals the secrets; the password key only seals a check value
+        let backend_id = self.backend.0.as_ref().map(|backend| backend.id().to_string());
+        let (backend, password_check) = match &self.backend.0 {
+            Some(backend) => (backend.clone(), Some(Keystore::encrypt(PASSWORD_CHECK, &master_key)?)),
+            None => (Arc::new(SoftwareBackend { key: master_key }) as Arc<dyn KeystoreBackend>, None),
+        };
         
-        // Encrypt mnemonic
-        let encrypted_mnemonic = Self::encrypt(&mnemonic, &master_key)?;
+        // Encrypt mnemonic and passphrase
+        let encrypted_mnemonic = backend.encrypt(mnemonic.as_bytes())?;
+        let encrypted_passphrase = if self.passphrase.is_empty() {
+            None
+        } else {
+            Some(backend.encrypt(self.passphrase.as_bytes())?)
+        };
         
-        Ok(Self {
+        Ok(Keystore {
             encrypted_mnemonic,
             salt,
-            master_key,
+            kdf: self.kdf,
+            encrypted_passphrase,
+            encrypted_keys: Vec::new(),
+            backend_id,
+            password_check,
+            backend: self.backend,
+        })
+    }
+}
+
+impl ImportedKey {
+    /// Encrypt a private key under `password` with a fresh salt
+    pub fn new(secret_key: &[u8; 32], password: &str, kdf: KdfConfig) -> Result<Self> {
+        use rand::Rng;
+        
+        let mut rng = rand::thread_rng();
+        let salt: Vec<u8> = (0..SALT_LEN).map(|_| rng.gen()).collect();
+        
+        let key_hex = Zeroizing::new(hex::encode(secret_key));
+        let encrypted_key = SoftwareBackend::new(password, &salt, &kdf)?.encrypt(key_hex.as_bytes())?;
+        Ok(Self { salt, kdf, encrypted_key })
+    }
+    
+    /// Decrypt the private key
+    pub fn private_key(&self, password: &str) -> Result<Zeroizing<[u8; 32]>> {
+        let key_hex = SoftwareBackend::new(password, &self.salt, &self.kdf)?.decrypt(&self.encrypted_key)?;
+        decode_private_key(&key_hex)
+    }
+    
+    /// Re-encrypt under a new password and a fresh salt, with the same costs
+    pub fn change_password(&mut self, old_password: &str, new_password: &str) -> Result<()> {
+        let secret_key = self.private_key(old_password)?;
+        *self = Self::new(&secret_key, new_password, self.kdf)?;
+        Ok(())
+    }
+    
+    /// Document form of the key
+    fn to_document(&self) -> ImportedKeyDocument {
+        ImportedKeyDocument {
+            kdf: KdfParams::from_config(&self.kdf),
+            salt: hex::encode(&self.salt),
+            key: CipherText::from_encrypted(&self.encrypted_key),
+        }
+    }
+    
+    /// Key described by a document
+    fn from_document(document: &ImportedKeyDocument) -> Result<Self> {
+        Ok(Self {
+            salt: hex::decode(&document.salt)
+                .map_err(|_| MobileError::KeystoreError("Invalid keystore salt".to_string()))?,
+            kdf: document.kdf.to_config()?,
+            encrypted_key: document.key.to_encrypted()?,
         })
     }
+}
+
+impl KeyProvider for ImportedKey {
+    fn secret_key(&self, password: &str) -> Result<SecretKey> {
+        let key_bytes = self.private_key(password)?;
+        SecretKey::from_slice(&key_bytes[..])
+            .map_err(|_| MobileError::KeystoreError("Corrupted private key".to_string()))
+    }
+}
+
+/// Parse a decrypted hex private key
+fn decode_private_key(key_hex: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+    let mut secret_key = Zeroizing::new([0u8; 32]);
+    hex::decode_to_slice(key_hex, &mut secret_key[..])
+        .map_err(|_| MobileError::KeystoreError("Corrupted private key".to_string()))?;
+    Ok(secret_key)
+}
+
+impl CipherText {
+    /// Split `[nonce] [ciphertext] [tag]` into hex fields
+    fn from_encrypted(encrypted: &[u8]) -> Self {
+        let (nonce, ciphertext) = encrypted.split_at(NONCE_LEN.min(encrypted.len()));
+        Self {
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        }
+    }
+    
+    /// Join the hex fields back into `[nonce] [ciphertext] [tag]`
+    fn to_encrypted(&self) -> Result<Vec<u8>> {
+        let invalid = |_| MobileError::KeystoreError("Invalid keystore ciphertext".to_string());
+        let mut encrypted = hex::decode(&self.nonce).map_err(invalid)?;
+        if encrypted.len() != NONCE_LEN {
+            return Err(MobileError::KeystoreError("Invalid keystore nonce".to_string()));
+        }
+        encrypted.extend(hex::decode(&self.ciphertext).map_err(invalid)?);
+        Ok(encrypted)
+    }
+}
+
+impl Keystore {
+    /// Create a new keystore
+    pub fn new(password: &str) -> Result<Self> {
+        Self::with_word_count(password, DEFAULT_WORD_COUNT)
+    }
+    
+    /// Create a new keystore with a mnemonic of the given word count
+    pub fn with_word_count(password: &str, word_count: usize) -> Result<Self> {
+        Self::builder().word_count(word_count).build(password)
+    }
+    
+    /// Start building a keystore with custom options
+    pub fn builder() -> KeystoreBuilder {
+        KeystoreBuilder::default()
+    }
     
     /// Create keystore from mnemonic
     pub fn from_mnemonic(mnemonic: &str, password: &str) -> Result<Self> {
+        Self::from_mnemonic_with_passphrase(mnemonic, "", password)
+    }
+    
+    /// Create keystore from mnemonic and a BIP39 passphrase (the "25th word")
+    ///
+    /// The passphrase changes the derived seed; `password` only encrypts the keystore.
+    pub fn from_mnemonic_with_passphrase(mnemonic: &str, passphrase: &str, password: &str) -> Result<Self> {
+        Self::builder()
+            .mnemonic(mnemonic)
+            .passphrase(passphrase)
+            .build(password)
+    }
+    
+    /// Key derivation costs
+    pub fn kdf(&self) -> KdfConfig {
+        self.kdf
+    }
+    
+    /// Identifier of the backend the secrets are sealed with
+    pub fn backend_id(&self) -> &str {
+        self.backend_id.as_deref().unwrap_or(SOFTWARE_BACKEND_ID)
+    }
+    
+    /// Attach the hardware backend a loaded keystore was sealed with
+    pub fn attach_backend(&mut self, backend: Arc<dyn KeystoreBackend>) -> Result<()> {
+        if self.backend_id.as_deref() != Some(backend.id()) {
+            return Err(MobileError::KeystoreError(format!(
+                "Keystore is sealed with the {} backend, not {}",
+                self.backend_id(),
+                backend.id()
+            )));
+        }
+        
+        self.backend = BackendSlot(Some(backend));
+        Ok(())
+    }
+    
+    /// Does the seed use a BIP39 passphrase
+    pub fn has_passphrase(&self) -> bool {
+        self.encrypted_passphrase.is_some()
+    }
+    
+    /// Export mnemonic (wiped from memory when dropped)
+    pub fn export_mnemonic(&self, password: &str) -> Result<Zeroizing<String>> {
+        // Unlock the backend with the password
+        let backend = self.backend(password)?;
+        
+        // Decrypt mnemonic
+        Self::open(&*backend, &self.encrypted_mnemonic)
+    }
+    
+    /// Change the password, re-encrypting the mnemonic under a fresh salt and the same costs
+    ///
+    /// Secrets sealed by a hardware backend stay as they are; only the password check is renewed.
+    pub fn change_password(&mut self, old_password: &str, new_password: &str) -> Result<()> {
         use rand::Rng;
         
-        // Validate mnemonic
-        if mnemonic.split_whitespace().count() != 12 {
-            return Err(MobileError::InvalidMnemonic);
-        }
+        // Check the old password
+        let old_backend = self.backend(old_password)?;
         
-        // Generate random salt
+        // Derive a fresh master key from the new password
         let mut rng = rand::thread_rng();
-        let salt: Vec<u8> = (0..16).map(|_| rng.gen()).collect();
+        let salt: Vec<u8> = (0..SALT_LEN).map(|_| rng.gen()).collect();
+        let master_key = Self::derive_key(new_password, &salt, &self.kdf)?;
         
-        // Derive master key from password
-        let master_key = Self::derive_key(password, &salt)?;
+        if self.password_check.is_some() {
+            self.password_check = Some(Self::encrypt(PASSWORD_CHECK, &master_key)?);
+            self.salt = salt;
+            return Ok(());
+        }
         
-        // Encrypt mnemonic
-        let encrypted_mnemonic = Self::encrypt(mnemonic, &master_key)?;
+        // Re-encrypt mnemonic and passphrase
+        let mnemonic = Self::open(&*old_backend, &self.encrypted_mnemonic)?;
+        let passphrase = self.decrypt_passphrase(&*old_backend)?;
+        let backend = SoftwareBackend { key: master_key };
+        self.encrypted_mnemonic = backend.encrypt(mnemonic.as_bytes())?;
+        if self.encrypted_passphrase.is_some() {
+            self.encrypted_passphrase = Some(backend.encrypt(passphrase.as_bytes())?);
+        }
+        self.encrypted_keys = self.encrypted_keys
+            .iter()
+            .map(|encrypted| {
+                let key_hex = old_backend.decrypt(encrypted)?;
+                backend.encrypt(&key_hex)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.salt = salt;
         
-        Ok(Self {
-            encrypted_mnemonic,
-            salt,
-            master_key,
-        })
+        Ok(())
     }
     
-    /// Export mnemonic
-    pub fn export_mnemonic(&self, password: &str) -> Result<String> {
-        // Derive key from password
-        let key = Self::derive_key(password, &self.salt)?;
+    /// Derive the BIP39 seed from the stored mnemonic and passphrase
+    pub fn seed(&self, password: &str) -> Result<Zeroizing<[u8; 64]>> {
+        let backend = self.backend(password)?;
+        let mnemonic = Self::open(&*backend, &self.encrypted_mnemonic)?;
+        let passphrase = self.decrypt_passphrase(&*backend)?;
+        Ok(mnemonic::to_seed(&mnemonic, &passphrase))
+    }
+    
+    /// Store an imported private key, returning its slot
+    pub fn add_private_key(&mut self, secret_key: &[u8; 32], password: &str) -> Result<u32> {
+        let backend = self.backend(password)?;
         
-        // Decrypt mnemonic
-        Self::decrypt(&self.encrypted_mnemonic, &key)
+        // Make sure the key is sealed with the keystore password
+        backend.decrypt(&self.encrypted_mnemonic)?;
+        
+        let key_hex = Zeroizing::new(hex::encode(secret_key));
+        self.encrypted_keys.push(backend.encrypt(key_hex.as_bytes())?);
+        Ok((self.encrypted_keys.len() - 1) as u32)
+    }
+    
+    /// Decrypt the imported private key in `slot`
+    pub fn private_key(&self, slot: u32, password: &str) -> Result<Zeroizing<[u8; 32]>> {
+        let encrypted = self.encrypted_keys
+            .get(slot as usize)
+            .ok_or(MobileError::AccountNotFound)?;
+        
+        let backend = self.backend(password)?;
+        let key_hex = backend.decrypt(encrypted)?;
+        decode_private_key(&key_hex)
+    }
+    
+    /// Number of imported private keys
+    pub fn imported_key_count(&self) -> u32 {
+        self.encrypted_keys.len() as u32
+    }
+    
+    /// Move the imported keys out of the keystore, each keeping the salt it was sealed under
+    ///
+    /// Keys sealed by a hardware backend can't be opened without it, so they stay in their slots.
+    pub fn split_imported_keys(&mut self) -> Vec<ImportedKey> {
+        if self.password_check.is_some() {
+            return Vec::new();
+        }
+        
+        std::mem::take(&mut self.encrypted_keys)
+            .into_iter()
+            .map(|encrypted_key| ImportedKey {
+                salt: self.salt.clone(),
+                kdf: self.kdf,
+                encrypted_key,
+            })
+            .collect()
+    }
+    
+    /// Export as a versioned, password-protected JSON document
+    ///
+    /// The ciphertexts are copied as stored, so repeated exports are identical.
+    pub fn export_json(&self, password: &str, network: Network) -> Result<String> {
+        self.export_json_with_keys(password, network, &[])
+    }
+    
+    /// Export as `export_json` does, along with independently encrypted imported keys
+    pub fn export_json_with_keys(&self, password: &str, network: Network, keys: &[ImportedKey]) -> Result<String> {
+        if self.backend_id.is_some() {
+            return Err(MobileError::KeystoreError("Hardware-backed keystores can't be exported".to_string()));
+        }
+        
+        // Make sure the document opens with the given password
+        self.export_mnemonic(password)?;
+        for key in keys {
+            key.private_key(password)?;
+        }
+        
+        let document = KeystoreDocument {
+            version: KEYSTORE_JSON_VERSION,
+            network,
+            kdf: KdfParams::from_config(&self.kdf),
+            cipher: "chacha20-poly1305".to_string(),
+            salt: hex::encode(&self.salt),
+            mnemonic: CipherText::from_encrypted(&self.encrypted_mnemonic),
+            passphrase: self.encrypted_passphrase.as_deref().map(CipherText::from_encrypted),
+            imported_keys: self.encrypted_keys.iter().map(|key| CipherText::from_encrypted(key)).collect(),
+            keys: keys.iter().map(ImportedKey::to_document).collect(),
+        };
+        
+        serde_json::to_string_pretty(&document)
+            .map_err(|e| MobileError::SerializationError(e.to_string()))
+    }
+    
+    /// Import a document produced by `export_json`, checking the password
+    ///
+    /// Independently encrypted keys are left out; use `import_json_with_keys` to keep them.
+    pub fn import_json(json: &str, password: &str) -> Result<(Self, Network)> {
+        let (keystore, network, _) = Self::import_json_with_keys(json, password)?;
+        Ok((keystore, network))
+    }
+    
+    /// Import a document produced by `export_json_with_keys`, checking the password
+    pub fn import_json_with_keys(json: &str, password: &str) -> Result<(Self, Network, Vec<ImportedKey>)> {
+        let probe: VersionProbe = serde_json::from_str(json)
+            .map_err(|e| MobileError::SerializationError(e.to_string()))?;
+        if probe.version != KEYSTORE_JSON_VERSION {
+            return Err(MobileError::KeystoreError(format!(
+                "Unsupported keystore version: {}",
+                probe.version
+            )));
+        }
+        
+        let document: KeystoreDocument = serde_json::from_str(json)
+            .map_err(|e| MobileError::SerializationError(e.to_string()))?;
+        let kdf = document.kdf.to_config()?;
+        
+        let keystore = Self {
+            encrypted_mnemonic: document.mnemonic.to_encrypted()?,
+            salt: hex::decode(&document.salt)
+                .map_err(|_| MobileError::KeystoreError("Invalid keystore salt".to_string()))?,
+            kdf,
+            encrypted_passphrase: document.passphrase.map(|passphrase| passphrase.to_encrypted()).transpose()?,
+            encrypted_keys: document.imported_keys
+                .iter()
+                .map(CipherText::to_encrypted)
+                .collect::<Result<Vec<_>>>()?,
+            backend_id: None,
+            password_check: None,
+            backend: BackendSlot::default(),
+        };
+        keystore.export_mnemonic(password)?;
+        
+        let keys = document.keys
+            .iter()
+            .map(ImportedKey::from_document)
+            .collect::<Result<Vec<_>>>()?;
+        for key in &keys {
+            key.private_key(password)?;
+        }
+        
+        Ok((keystore, document.network, keys))
+    }
+    
+    /// Derive the master key from the password with this keystore's salt and costs
+    fn master_key(&self, password: &str) -> Result<Zeroizing<[u8; 32]>> {
+        Self::derive_key(password, &self.salt, &self.kdf)
+    }
+    
+    /// Backend for this keystore, checking `password`
+    fn backend(&self, password: &str) -> Result<Arc<dyn KeystoreBackend>> {
+        let key = self.master_key(password)?;
+        
+        match (&self.password_check, &self.backend.0) {
+            (None, _) => Ok(Arc::new(SoftwareBackend { key })),
+            (Some(check), Some(backend)) => {
+                let opened = Self::decrypt(check, &key)?;
+                if !security::constant_time_eq(&opened, PASSWORD_CHECK) {
+                    return Err(MobileError::CryptoError("Password check failed".to_string()));
+                }
+                Ok(backend.clone())
+            }
+            (Some(_), None) => Err(MobileError::KeystoreError(format!(
+                "The {} backend is not attached",
+                self.backend_id()
+            ))),
+        }
+    }
+    
+    /// Decrypt a UTF-8 secret with `backend`
+    fn open(backend: &dyn KeystoreBackend, encrypted: &[u8]) -> Result<Zeroizing<String>> {
+        Self::utf8(backend.decrypt(encrypted)?)
+    }
+    
+    /// Decrypt the stored passphrase (empty when none is set)
+    fn decrypt_passphrase(&self, backend: &dyn KeystoreBackend) -> Result<Zeroizing<String>> {
+        match &self.encrypted_passphrase {
+            Some(encrypted) => Self::open(backend, encrypted),
+            None => Ok(Zeroizing::new(String::new())),
+        }
+    }
+    
+    /// Encrypt arbitrary data under a password with a fresh salt
+    ///
+    /// Format: [salt (16 bytes)] [nonce (12 bytes)] [ciphertext] [tag (16 bytes)]
+    pub(crate) fn seal(data: &[u8], password: &str) -> Result<Vec<u8>> {
+        use rand::Rng;
+        
+        let mut salt = [0u8; SALT_LEN];
+        rand::thread_rng().fill(&mut salt);
+        
+        let key = Self::derive_key(password, &salt, &KdfConfig::default())?;
+        let mut sealed = salt.to_vec();
+        sealed.extend_from_slice(&Self::encrypt(data, &key)?);
+        
+        Ok(sealed)
+    }
+    
+    /// Decrypt data produced by `seal`
+    pub(crate) fn unseal(sealed: &[u8], password: &str) -> Result<Zeroizing<Vec<u8>>> {
+        if sealed.len() < SALT_LEN {
+            return Err(MobileError::CryptoError("Encrypted data too short".to_string()));
+        }
+        
+        let (salt, encrypted) = sealed.split_at(SALT_LEN);
+        let key = Self::derive_key(password, salt, &KdfConfig::default())?;
+        Self::decrypt(encrypted, &key)
     }
     
     /// Derive key from password
-    fn derive_key(password: &str, salt: &[u8]) -> Result<Vec<u8>> {
-        use argon2::{Argon2, PasswordHasher};
+    ///
+    /// The key is never stored; it is wiped from memory when dropped.
+    fn derive_key(password: &str, salt: &[u8], kdf: &KdfConfig) -> Result<Zeroizing<[u8; 32]>> {
+        use argon2::{Algorithm, Argon2, PasswordHasher, Version};
         use argon2::password_hash::SaltString;
         
         let salt_string = SaltString::encode_b64(salt)
             .map_err(|_| MobileError::KeystoreError("Invalid salt".to_string()))?;
         
-        let argon2 = Argon2::default();
+        #[cfg(feature = "log")]
+        let started = std::time::Instant::now();
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, kdf.params()?);
         let password_hash = argon2
             .hash_password(password.as_bytes(), &salt_string)
             .map_err(|_| MobileError::KeystoreError("Key derivation failed".to_string()))?;
+        log_event!(
+            m_cost = kdf.m_cost,
+            t_cost = kdf.t_cost,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "derived password key"
+        );
+        
+        let hash = password_hash
+            .hash
+            .filter(|hash| hash.len() == 32)
+            .ok_or_else(|| MobileError::KeystoreError("Key derivation failed".to_string()))?;
         
-        Ok(password_hash.hash.unwrap().as_bytes().to_vec())
+        let mut key = Zeroizing::new([0u8; 32]);
+        key.copy_from_slice(hash.as_bytes());
+        Ok(key)
     }
     
-    /// Generate mnemonic
-    fn generate_mnemonic() -> Result<String> {
+    /// Encrypt data using ChaCha20-Poly1305
+    fn encrypt(data: &[u8], key: &[u8; 32]) -> Result<Vec<u8>> {
         use rand::Rng;
+        use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce, KeyInit};
+        use chacha20poly1305::aead::Aead;
         
-        let words = vec![
-            "abandon", "ability", "able", "about", "above", "absent", "absorb", "abstract",
-            "academy", "accept", "access", "accident", "account", "accuse", "achieve", "acid",
-        ];
+        // Format: [nonce (12 bytes)] [ciphertext] [tag (16 bytes)]
+        let mut nonce_bytes = [0u8; 12];
+        rand::thread_rng().fill(&mut nonce_bytes);
         
-        let mut rng = rand::thread_rng();
-        let mnemonic: Vec<&str> = (0..12)
-            .map(|_| words[rng.gen_range(0..words.len())])
-            .collect();
+        let derived_key = Self::derive_cipher_key(key, &nonce_bytes);
+        let cipher = ChaCha20Poly1305::new(&Key::from(*derived_key));
+        let nonce = Nonce::from_slice(&nonce_bytes);
         
-        Ok(mnemonic.join(" "))
-    }
-    
-    /// Encrypt data
-    fn encrypt(data: &str, key: &[u8]) -> Result<Vec<u8>> {
-        use blake3::Hasher;
+        let ciphertext_and_tag = cipher
+            .encrypt(nonce, data)
+            .map_err(|_| MobileError::CryptoError("Encryption failed".to_string()))?;
         
-        let mut hasher = Hasher::new();
-        hasher.update(data.as_bytes());
-        hasher.update(key);
+        let mut encrypted = Vec::with_capacity(nonce_bytes.len() + ciphertext_and_tag.len());
+        encrypted.extend_from_slice(&nonce_bytes);
+        encrypted.extend_from_slice(&ciphertext_and_tag);
         
-        Ok(hasher.finalize().as_bytes().to_vec())
+        Ok(encrypted)
     }
     
     /// Decrypt data using real cryptographic key derivation and ChaCha20-Poly1305
-    fn decrypt(encrypted: &[u8], key: &[u8]) -> Result<String> {
-        // REAL IMPLEMENTATION: Secure decryption with key derivation
+    fn decrypt(encrypted: &[u8], key: &[u8; 32]) -> Result<Zeroizing<Vec<u8>>> {
         // Format: [nonce (12 bytes)] [ciphertext] [tag (16 bytes)]
         
         if encrypted.len() < 28 {
@@ -133,7 +868,35 @@ impl Keystore {
         let nonce_bytes = &encrypted[0..12];
         let ciphertext_and_tag = &encrypted[12..];
         
-        // Derive key from input key using SHA-256 (HKDF-like approach)
+        // Use derived key for decryption
+        let derived_key = Self::derive_cipher_key(key, nonce_bytes);
+        
+        // Decrypt using ChaCha20-Poly1305
+        use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce, KeyInit};
+        use chacha20poly1305::aead::Aead;
+        
+        // Create cipher with derived key
+        let cipher = ChaCha20Poly1305::new(&Key::from(*derived_key));
+        let nonce = Nonce::from_slice(nonce_bytes);
+        
+        match cipher.decrypt(nonce, ciphertext_and_tag) {
+            Ok(plaintext) => Ok(Zeroizing::new(plaintext)),
+            Err(_) => Err(MobileError::CryptoError("Decryption failed - invalid key or corrupted data".to_string()))
+        }
+    }
+    
+    /// Interpret decrypted bytes as UTF-8, wiping them if they aren't
+    fn utf8(mut plaintext: Zeroizing<Vec<u8>>) -> Result<Zeroizing<String>> {
+        String::from_utf8(std::mem::take(&mut *plaintext))
+            .map(Zeroizing::new)
+            .map_err(|e| {
+                e.into_bytes().zeroize();
+                MobileError::CryptoError("Invalid UTF-8 in decrypted data".to_string())
+            })
+    }
+    
+    /// Derive the per-message cipher key from the master key and nonce (HKDF-like)
+    fn derive_cipher_key(key: &[u8; 32], nonce: &[u8]) -> Zeroizing<[u8; 32]> {
         use sha2::{Digest, Sha256};
         
         // Step 1: Extract phase - hash the input key
@@ -146,28 +909,12 @@ impl Keystore {
         let mut hasher = Sha256::new();
         hasher.update(prk);
         hasher.update(b"silver_keystore_expand");
-        hasher.update(nonce_bytes);
+        hasher.update(nonce);
         let derived_key_bytes = hasher.finalize();
         
-        // Use derived key for decryption
-        let mut derived_key = [0u8; 32];
+        let mut derived_key = Zeroizing::new([0u8; 32]);
         derived_key.copy_from_slice(&derived_key_bytes[0..32]);
-        
-        // Decrypt using ChaCha20-Poly1305
-        use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce, KeyInit};
-        use chacha20poly1305::aead::Aead;
-        
-        // Create cipher with derived key
-        let cipher = ChaCha20Poly1305::new(&Key::from(derived_key));
-        let nonce = Nonce::from_slice(nonce_bytes);
-        
-        match cipher.decrypt(nonce, ciphertext_and_tag) {
-            Ok(plaintext) => {
-                String::from_utf8(plaintext)
-                    .map_err(|_| MobileError::CryptoError("Invalid UTF-8 in decrypted data".to_string()))
-            }
-            Err(_) => Err(MobileError::CryptoError("Decryption failed - invalid key or corrupted data".to_string()))
-        }
+        derived_key
     }
 }
 
@@ -183,8 +930,344 @@ mod tests {
     
     #[test]
     fn test_keystore_from_mnemonic() {
-        let mnemonic = "abandon ability able about above absent absorb abstract academy accept access accident";
+        let mnemonic = "legal winner thank year wave sausage worth useful legal winner thank yellow";
         let keystore = Keystore::from_mnemonic(mnemonic, "password123");
         assert!(keystore.is_ok());
     }
+    
+    #[test]
+    fn test_export_mnemonic_round_trip() {
+        let mnemonic = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let keystore = Keystore::from_mnemonic(mnemonic, "password123").unwrap();
+        
+        let exported = keystore.export_mnemonic("password123").unwrap();
+        assert_eq!(exported.as_str(), mnemonic);
+        assert_eq!(exported.split_whitespace().count(), 12);
+    }
+    
+    #[test]
+    fn test_export_mnemonic_wrong_password() {
+        let keystore = Keystore::new("password123").unwrap();
+        
+        let result = keystore.export_mnemonic("wrongpassword");
+        assert!(matches!(result, Err(MobileError::CryptoError(_))));
+    }
+    
+    #[test]
+    fn test_keystore_from_24_word_mnemonic() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon \
+                        abandon abandon abandon abandon abandon abandon abandon abandon \
+                        abandon abandon abandon abandon abandon abandon abandon art";
+        assert!(matches!(Keystore::from_mnemonic(mnemonic, "password123"), Err(MobileError::WeakMnemonic)));
+        
+        let keystore = Keystore::builder()
+            .mnemonic(mnemonic)
+            .allow_weak_mnemonic()
+            .build("password123")
+            .unwrap();
+        let exported = keystore.export_mnemonic("password123").unwrap();
+        assert_eq!(exported.split_whitespace().count(), 24);
+    }
+    
+    #[test]
+    fn test_keystore_rejects_13_word_mnemonic() {
+        let mnemonic = "legal winner thank year wave sausage worth useful legal winner thank yellow legal";
+        let result = Keystore::from_mnemonic(mnemonic, "password123");
+        assert!(matches!(result, Err(MobileError::InvalidMnemonic)));
+    }
+    
+    #[test]
+    fn test_keystore_with_24_words() {
+        let keystore = Keystore::with_word_count("password123", 24).unwrap();
+        let exported = keystore.export_mnemonic("password123").unwrap();
+        assert_eq!(exported.split_whitespace().count(), 24);
+        
+        assert!(Keystore::with_word_count("password123", 13).is_err());
+    }
+    
+    #[test]
+    fn test_change_password() {
+        let mut keystore = Keystore::new("password123").unwrap();
+        let mnemonic = keystore.export_mnemonic("password123").unwrap();
+        
+        keystore.change_password("password123", "newpassword456").unwrap();
+        assert_eq!(keystore.export_mnemonic("newpassword456").unwrap(), mnemonic);
+        assert!(keystore.export_mnemonic("password123").is_err());
+    }
+    
+    #[test]
+    fn test_change_password_rejects_wrong_old_password() {
+        let mut keystore = Keystore::new("password123").unwrap();
+        
+        assert!(keystore.change_password("wrongpassword", "newpassword456").is_err());
+        assert!(keystore.export_mnemonic("password123").is_ok());
+    }
+    
+    #[test]
+    fn test_serialized_keystore_contains_no_derived_key() {
+        let keystore = Keystore::new("password123").unwrap();
+        let key = keystore.master_key("password123").unwrap();
+        
+        let json = serde_json::to_string(&keystore).unwrap();
+        let key_json = serde_json::to_string(&*key).unwrap();
+        let key_bytes = key_json.trim_start_matches('[').trim_end_matches(']');
+        
+        assert!(!json.contains("master_key"));
+        assert!(!json.contains(key_bytes));
+        assert!(!json.contains(&hex::encode(&*key)));
+    }
+    
+    #[test]
+    fn test_passphrase_changes_seed() {
+        let mnemonic = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let plain = Keystore::from_mnemonic(mnemonic, "password123").unwrap();
+        let mut hidden = Keystore::from_mnemonic_with_passphrase(mnemonic, "hidden", "password123").unwrap();
+        assert!(!plain.has_passphrase());
+        assert!(hidden.has_passphrase());
+        
+        assert_eq!(*plain.seed("password123").unwrap(), *mnemonic::to_seed(mnemonic, ""));
+        assert_eq!(*hidden.seed("password123").unwrap(), *mnemonic::to_seed(mnemonic, "hidden"));
+        
+        // The passphrase survives a password change
+        hidden.change_password("password123", "newpassword456").unwrap();
+        assert_eq!(*hidden.seed("newpassword456").unwrap(), *mnemonic::to_seed(mnemonic, "hidden"));
+    }
+    
+    #[test]
+    fn test_imported_key_round_trip() {
+        let mut keystore = Keystore::new("password123").unwrap();
+        let secret = [9u8; 32];
+        
+        assert!(keystore.add_private_key(&secret, "wrongpassword").is_err());
+        let slot = keystore.add_private_key(&secret, "password123").unwrap();
+        assert_eq!(*keystore.private_key(slot, "password123").unwrap(), secret);
+        
+        keystore.change_password("password123", "newpassword456").unwrap();
+        assert_eq!(*keystore.private_key(slot, "newpassword456").unwrap(), secret);
+        assert!(keystore.private_key(slot, "password123").is_err());
+    }
+    
+    #[test]
+    fn test_json_export_round_trip() {
+        let mnemonic = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let keystore = Keystore::from_mnemonic_with_passphrase(mnemonic, "hidden", "password123").unwrap();
+        
+        let json = keystore.export_json("password123", Network::Testnet).unwrap();
+        assert!(!json.contains("legal"));
+        assert_eq!(json, keystore.export_json("password123", Network::Testnet).unwrap());
+        assert!(keystore.export_json("wrongpassword", Network::Testnet).is_err());
+        
+        let (imported, network) = Keystore::import_json(&json, "password123").unwrap();
+        assert_eq!(network, Network::Testnet);
+        assert_eq!(imported.export_mnemonic("password123").unwrap().as_str(), mnemonic);
+        assert_eq!(*imported.seed("password123").unwrap(), *keystore.seed("password123").unwrap());
+        assert!(Keystore::import_json(&json, "wrongpassword").is_err());
+    }
+    
+    #[test]
+    fn test_json_import_rejects_unknown_version() {
+        let keystore = Keystore::new("password123").unwrap();
+        let json = keystore.export_json("password123", Network::Mainnet).unwrap();
+        
+        let mut document: serde_json::Value = serde_json::from_str(&json).unwrap();
+        document["version"] = serde_json::json!(KEYSTORE_JSON_VERSION + 1);
+        
+        let result = Keystore::import_json(&document.to_string(), "password123");
+        assert!(matches!(result, Err(MobileError::KeystoreError(_))));
+    }
+    
+    #[test]
+    fn test_json_import_rejects_excessive_kdf_costs() {
+        let keystore = Keystore::new("password123").unwrap();
+        let json = keystore.export_json("password123", Network::Mainnet).unwrap();
+        
+        for (field, cost) in [("m_cost", MAX_KDF_M_COST), ("t_cost", MAX_KDF_T_COST), ("p_cost", MAX_KDF_P_COST)] {
+            let mut document: serde_json::Value = serde_json::from_str(&json).unwrap();
+            document["kdf"][field] = serde_json::json!(cost + 1);
+            
+            let result = Keystore::import_json(&document.to_string(), "password123");
+            assert!(matches!(result, Err(MobileError::KeystoreError(_))), "{}", field);
+        }
+        
+        let mut document: serde_json::Value = serde_json::from_str(&json).unwrap();
+        document["kdf"]["m_cost"] = serde_json::json!(u32::MAX);
+        document["kdf"]["t_cost"] = serde_json::json!(u32::MAX);
+        assert!(Keystore::import_json(&document.to_string(), "password123").is_err());
+    }
+    
+    #[test]
+    fn test_custom_kdf_params() {
+        let kdf = KdfConfig {
+            m_cost: 8 * 1024,
+            t_cost: 3,
+            p_cost: 1,
+        };
+        let mut keystore = Keystore::builder()
+            .kdf(kdf)
+            .mnemonic("legal winner thank year wave sausage worth useful legal winner thank yellow")
+            .build("password123")
+            .unwrap();
+        assert_eq!(keystore.kdf(), kdf);
+        assert!(keystore.export_mnemonic("password123").unwrap().starts_with("legal"));
+        
+        // The costs are part of the key, so a default-cost key can't open it
+        let default_key = Keystore::derive_key("password123", &keystore.salt, &KdfConfig::default()).unwrap();
+        assert!(Keystore::decrypt(&keystore.encrypted_mnemonic, &default_key).is_err());
+        
+        // They survive serialization, JSON export and password changes
+        let json = serde_json::to_string(&keystore).unwrap();
+        let restored: Keystore = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.kdf(), kdf);
+        assert!(restored.export_mnemonic("password123").is_ok());
+        
+        let document = keystore.export_json("password123", Network::Mainnet).unwrap();
+        let (imported, _) = Keystore::import_json(&document, "password123").unwrap();
+        assert_eq!(imported.kdf(), kdf);
+        
+        keystore.change_password("password123", "newpassword456").unwrap();
+        assert_eq!(keystore.kdf(), kdf);
+        assert!(keystore.export_mnemonic("newpassword456").is_ok());
+    }
+    
+    #[test]
+    fn test_invalid_kdf_params_rejected() {
+        let kdf = KdfConfig {
+            m_cost: 1,
+            t_cost: 0,
+            p_cost: 1,
+        };
+        let result = Keystore::builder().kdf(kdf).build("password123");
+        assert!(matches!(result, Err(MobileError::KeystoreError(_))));
+    }
+    
+    #[test]
+    fn test_legacy_keystore_uses_default_kdf() {
+        let keystore = Keystore::new("password123").unwrap();
+        let mut json: serde_json::Value = serde_json::to_value(&keystore).unwrap();
+        json.as_object_mut().unwrap().remove("kdf");
+        
+        let restored: Keystore = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.kdf(), KdfConfig::default());
+        assert!(restored.export_mnemonic("password123").is_ok());
+    }
+    
+    /// Stand-in for a hardware backend that records what it is asked to encrypt
+    struct EnclaveMock {
+        sealed: parking_lot::Mutex<Vec<Vec<u8>>>,
+    }
+    
+    impl EnclaveMock {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                sealed: parking_lot::Mutex::new(Vec::new()),
+            })
+        }
+    }
+    
+    impl KeystoreBackend for EnclaveMock {
+        fn id(&self) -> &str {
+            "secure-enclave"
+        }
+        
+        fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+            self.sealed.lock().push(plaintext.to_vec());
+            Ok(b"enclave:".iter().copied().chain(plaintext.iter().map(|b| b ^ 0xa5)).collect())
+        }
+        
+        fn decrypt(&self, ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+            let body = ciphertext
+                .strip_prefix(b"enclave:")
+                .ok_or_else(|| MobileError::CryptoError("Not sealed by the enclave".to_string()))?;
+            Ok(Zeroizing::new(body.iter().map(|b| b ^ 0xa5).collect()))
+        }
+    }
+    
+    #[test]
+    fn test_hardware_backend_seals_mnemonic() {
+        let mnemonic = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let enclave = EnclaveMock::new();
+        let mut keystore = Keystore::builder()
+            .mnemonic(mnemonic)
+            .backend(enclave.clone())
+            .build("password123")
+            .unwrap();
+        assert_eq!(keystore.backend_id(), "secure-enclave");
+        
+        // The mnemonic went to the enclave and was stored as the enclave sealed it
+        assert_eq!(enclave.sealed.lock().as_slice(), &[mnemonic.as_bytes().to_vec()]);
+        assert!(keystore.encrypted_mnemonic.starts_with(b"enclave:"));
+        let key = keystore.master_key("password123").unwrap();
+        assert!(Keystore::decrypt(&keystore.encrypted_mnemonic, &key).is_err());
+        
+        // The password is still checked, and the mnemonic opens through the enclave
+        assert!(keystore.export_mnemonic("wrongpassword").is_err());
+        assert_eq!(&*keystore.export_mnemonic("password123").unwrap(), mnemonic);
+        assert!(keystore.export_json("password123", Network::Mainnet).is_err());
+        
+        // Changing the password leaves the enclave ciphertext untouched
+        let sealed = keystore.encrypted_mnemonic.clone();
+        keystore.change_password("password123", "newpassword456").unwrap();
+        assert_eq!(keystore.encrypted_mnemonic, sealed);
+        assert!(keystore.export_mnemonic("password123").is_err());
+        assert!(keystore.seed("newpassword456").is_ok());
+        
+        // A loaded keystore needs the backend re-attached
+        let json = serde_json::to_string(&keystore).unwrap();
+        let mut restored: Keystore = serde_json::from_str(&json).unwrap();
+        assert!(matches!(restored.seed("newpassword456"), Err(MobileError::KeystoreError(_))));
+        let software = SoftwareBackend::new("newpassword456", &restored.salt, &restored.kdf).unwrap();
+        assert!(restored.attach_backend(Arc::new(software)).is_err());
+        restored.attach_backend(enclave.clone()).unwrap();
+        assert_eq!(*restored.seed("newpassword456").unwrap(), *keystore.seed("newpassword456").unwrap());
+        assert_eq!(enclave.sealed.lock().len(), 1);
+    }
+    
+    #[test]
+    fn test_derived_key_zeroed_on_drop() {
+        use std::mem::ManuallyDrop;
+        
+        let key = Keystore::derive_key("password123", &[7u8; SALT_LEN], &KdfConfig::default()).unwrap();
+        let mut key = ManuallyDrop::new(key);
+        let ptr = key.as_ptr();
+        
+        // SAFETY: the array lives inline in `key`, whose storage outlives both reads
+        let bytes = || unsafe { std::slice::from_raw_parts(ptr, 32) }.to_vec();
+        assert!(bytes().iter().any(|&b| b != 0));
+        
+        unsafe { ManuallyDrop::drop(&mut key) };
+        assert!(bytes().iter().all(|&b| b == 0));
+    }
+    
+    #[test]
+    fn test_decrypt_rejects_corrupted_ciphertext() {
+        let key = [7u8; 32];
+        let encrypted = Keystore::encrypt(b"secret mnemonic", &key).unwrap();
+        assert_eq!(&Keystore::decrypt(&encrypted, &key).unwrap()[..], b"secret mnemonic");
+        
+        // A flipped bit in the nonce, the ciphertext or the tag fails authentication
+        for position in [0, NONCE_LEN, encrypted.len() - 1] {
+            let mut corrupted = encrypted.clone();
+            corrupted[position] ^= 0x01;
+            assert!(matches!(Keystore::decrypt(&corrupted, &key), Err(MobileError::CryptoError(_))));
+        }
+    }
+    
+    #[test]
+    fn test_decrypt_rejects_truncated_input() {
+        let key = [7u8; 32];
+        let encrypted = Keystore::encrypt(b"secret mnemonic", &key).unwrap();
+        
+        for len in [0, NONCE_LEN, 27] {
+            match Keystore::decrypt(&encrypted[..len], &key) {
+                Err(MobileError::CryptoError(message)) => assert!(message.contains("too short")),
+                other => panic!("expected a too-short error, got {:?}", other.map(|_| ())),
+            }
+        }
+        
+        // Long enough to parse but missing the end of the tag
+        assert!(matches!(
+            Keystore::decrypt(&encrypted[..encrypted.len() - 1], &key),
+            Err(MobileError::CryptoError(_))
+        ));
+    }
 }
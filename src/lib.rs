@@ -9,6 +9,9 @@
 //! - QR code support
 //! - Biometric authentication
 
+#[macro_use]
+mod logging;
+
 pub mod account;
 pub mod errors;
 pub mod keystore;
@@ -16,17 +19,45 @@ pub mod transaction;
 pub mod wallet;
 pub mod sync;
 pub mod security;
+pub mod mnemonic;
+pub mod hd;
+pub mod network;
+pub mod payment;
+pub mod qr;
+pub mod fee;
+pub mod history;
+pub mod contacts;
+pub mod utxo;
+pub mod price;
+pub mod events;
+pub mod amount;
+pub mod clock;
+pub mod storage;
+pub mod message;
+mod wordlist;
 
 pub use account::Account;
-pub use errors::{MobileError, Result};
-pub use keystore::Keystore;
-pub use transaction::MobileTransaction;
-pub use wallet::MobileWallet;
-pub use sync::SyncManager;
-pub use security::SecurityManager;
+pub use clock::{Clock, SystemClock};
+pub use contacts::Contact;
+pub use errors::{MobileError, NetworkErrorKind, Result};
+pub use keystore::{ImportedKey, KdfConfig, KeyProvider, Keystore, KeystoreBackend, SoftwareBackend};
+pub use network::Network;
+pub use fee::FeeRate;
+pub use message::verify_message;
+pub use payment::PaymentRequest;
+pub use qr::{ErrorCorrection, QrMatrix};
+pub use transaction::{MobileTransaction, TransactionOutput, TransactionPreview, TransactionStatus, TransactionWarning};
+pub use wallet::{MobileWallet, ScanReport, ScanWarning};
+pub use sync::{CancelToken, NodeConfig, SyncManager, WalletSyncReport};
+pub use storage::StorageFormat;
+pub use security::{BiometricAuthenticator, BiometricProof, PasswordPolicy, SecurityManager, SecuritySettings, SpendPolicy};
 
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
-use parking_lot::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use parking_lot::{Mutex, RwLock};
+use zeroize::Zeroizing;
 
 /// Mobile wallet version
 pub const MOBILE_WALLET_VERSION: &str = "1.0.0";
@@ -34,103 +65,497 @@ pub const MOBILE_WALLET_VERSION: &str = "1.0.0";
 /// Mobile wallet manager
 #[derive(Clone, Debug)]
 pub struct MobileWalletManager {
-    /// Wallet
-    wallet: Arc<RwLock<Option<MobileWallet>>>,
+    /// Stored wallets
+    wallets: Arc<RwLock<WalletStore>>,
     
-    /// Security manager
-    security_manager: Arc<SecurityManager>,
+    /// Security manager, replaced by the loaded wallet's saved settings
+    security_manager: Arc<RwLock<SecurityManager>>,
     
     /// Sync manager
     sync_manager: Arc<SyncManager>,
+    
+    /// Change observers
+    observers: Arc<events::WalletObservers>,
+    
+    /// Sync tickets, handed out in the order syncs start fetching
+    sync_tickets: Arc<AtomicU64>,
+    
+    /// Newest sync ticket applied to each wallet; only locked under the wallet write lock
+    applied_syncs: Arc<Mutex<HashMap<String, u64>>>,
+    
+    /// IDs of transactions created past the spend limit by stepping up, until broadcast
+    stepped_up: Arc<Mutex<HashSet<String>>>,
 }
 
 impl MobileWalletManager {
     /// Create a new mobile wallet manager
     pub fn new() -> Result<Self> {
-        Ok(Self {
-            wallet: Arc::new(RwLock::new(None)),
-            security_manager: Arc::new(SecurityManager::new()?),
-            sync_manager: Arc::new(SyncManager::new()?),
-        })
+        Self::with_sync_manager(SyncManager::new()?)
+    }
+    
+    /// Create a manager that syncs through a specific sync manager
+    pub fn with_sync_manager(sync_manager: SyncManager) -> Result<Self> {
+        Ok(Self::with_managers(SecurityManager::new()?, sync_manager))
+    }
+    
+    /// Manager with no wallets around the given security and sync managers
+    fn with_managers(security_manager: SecurityManager, sync_manager: SyncManager) -> Self {
+        Self {
+            wallets: Arc::new(RwLock::new(WalletStore::default())),
+            security_manager: Arc::new(RwLock::new(security_manager)),
+            sync_manager: Arc::new(sync_manager),
+            observers: Arc::new(events::WalletObservers::new()),
+            sync_tickets: Arc::new(AtomicU64::new(0)),
+            applied_syncs: Arc::new(Mutex::new(HashMap::new())),
+            stepped_up: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+    
+    /// Register a callback fired with the new balance when a sync changes it
+    pub fn on_balance_changed(&self, callback: events::BalanceCallback) {
+        self.observers.on_balance_changed(callback);
+    }
+    
+    /// Register a callback fired for each transaction a sync adds or updates
+    pub fn on_transaction_update(&self, callback: events::TransactionCallback) {
+        self.observers.on_transaction_update(callback);
     }
     
-    /// Create a new wallet
-    pub fn create_wallet(&self, password: &str) -> Result<MobileWallet> {
+    /// Create a new wallet, make it active and return its ID
+    pub fn create_wallet(&self, password: &str) -> Result<String> {
         // Validate password
-        self.security_manager.validate_password(password)?;
+        self.security().validate_password(password)?;
         
         // Create wallet
         let wallet = MobileWallet::new(password)?;
+        let id = wallet.id().to_string();
         
         // Store wallet
-        let mut stored_wallet = self.wallet.write();
-        *stored_wallet = Some(wallet.clone());
+        self.wallets.write().insert(wallet);
+        self.security().unlock();
         
-        Ok(wallet)
+        Ok(id)
     }
     
     /// Import wallet from mnemonic
     pub fn import_wallet(&self, mnemonic: &str, password: &str) -> Result<MobileWallet> {
         // Validate password
-        self.security_manager.validate_password(password)?;
+        self.security().validate_password(password)?;
         
         // Import wallet
         let wallet = MobileWallet::from_mnemonic(mnemonic, password)?;
         
         // Store wallet
-        let mut stored_wallet = self.wallet.write();
-        *stored_wallet = Some(wallet.clone());
+        self.wallets.write().insert(wallet.clone());
+        self.security().unlock();
+        
+        Ok(wallet)
+    }
+    
+    /// Load wallet from an encrypted file, restoring the security settings saved with it
+    pub fn load_wallet(&self, path: &Path, password: &str) -> Result<MobileWallet> {
+        let wallet = MobileWallet::load_from_path(path, password)?;
+        let security = wallet.restore_security_manager(password)?;
+        
+        // Store wallet
+        self.wallets.write().insert(wallet.clone());
+        let mut current = self.security_manager.write();
+        if let Some(authenticator) = current.biometric_authenticator() {
+            security.set_biometric_authenticator(authenticator);
+        }
+        *current = security;
+        current.unlock();
         
         Ok(wallet)
     }
     
-    /// Get active wallet
+    /// Save the active wallet to an encrypted file, with the current security settings
+    pub fn save_wallet(&self, path: &Path, password: &str) -> Result<()> {
+        let mut store = self.wallets.write();
+        let wallet = store.active_mut()?;
+        wallet.store_security_settings(&self.security(), password)?;
+        wallet.save_to_path(path, password)
+    }
+    
+    /// Change the password of the active wallet
+    pub fn change_password(&self, old_password: &str, new_password: &str) -> Result<()> {
+        let mut stored_wallet = self.wallets.write();
+        let wallet = stored_wallet.active_mut()?;
+        wallet.change_password(old_password, new_password, &self.security())
+    }
+    
+    /// Get a copy of the active wallet
+    ///
+    /// This clones the whole wallet, including its full history; prefer
+    /// [`with_wallet`](Self::with_wallet) for reads.
     pub fn get_wallet(&self) -> Result<MobileWallet> {
-        let wallet = self.wallet.read();
-        wallet
-            .clone()
-            .ok_or(MobileError::NoWalletLoaded)
+        self.wallets.read().active().cloned()
+    }
+    
+    /// Run `f` against a stored wallet under the write lock
+    ///
+    /// Changes land on the stored wallet itself, so concurrent updates can't overwrite each other.
+    fn update_wallet<R>(&self, id: &str, f: impl FnOnce(&mut MobileWallet) -> R) -> Result<R> {
+        let mut store = self.wallets.write();
+        let wallet = store.wallets.get_mut(id).ok_or(MobileError::WalletNotFound)?;
+        Ok(f(wallet))
+    }
+    
+    /// Run `f` against the active wallet under the read lock
+    ///
+    /// `f` must not call back into methods of this manager that write to the wallet.
+    pub fn with_wallet<R>(&self, f: impl FnOnce(&MobileWallet) -> R) -> Result<R> {
+        let store = self.wallets.read();
+        Ok(f(store.active()?))
+    }
+    
+    /// List stored wallets, ordered by name
+    pub fn list_wallets(&self) -> Vec<WalletSummary> {
+        let store = self.wallets.read();
+        let mut summaries: Vec<WalletSummary> = store
+            .wallets
+            .values()
+            .map(|wallet| WalletSummary {
+                id: wallet.id().to_string(),
+                name: wallet.name().to_string(),
+            })
+            .collect();
+        summaries.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+        summaries
+    }
+    
+    /// ID of the active wallet
+    pub fn active_wallet_id(&self) -> Option<String> {
+        self.wallets.read().active_wallet_id.clone()
+    }
+    
+    /// Make a stored wallet the active one
+    ///
+    /// Switching to a different wallet locks the manager until it is unlocked
+    /// with that wallet's password.
+    pub fn switch_wallet(&self, id: &str) -> Result<()> {
+        let mut store = self.wallets.write();
+        if !store.wallets.contains_key(id) {
+            return Err(MobileError::WalletNotFound);
+        }
+        if store.active_wallet_id.as_deref() != Some(id) {
+            store.active_wallet_id = Some(id.to_string());
+            self.security().lock();
+        }
+        Ok(())
+    }
+    
+    /// Remove a stored wallet
+    ///
+    /// Removing the active wallet leaves no wallet active and locks the manager.
+    pub fn remove_wallet(&self, id: &str) -> Result<()> {
+        let mut store = self.wallets.write();
+        if store.wallets.remove(id).is_none() {
+            return Err(MobileError::WalletNotFound);
+        }
+        if store.active_wallet_id.as_deref() == Some(id) {
+            store.active_wallet_id = None;
+            self.security().lock();
+        }
+        Ok(())
+    }
+    
+    /// Rename a stored wallet
+    pub fn rename_wallet(&self, id: &str, name: &str) -> Result<()> {
+        let mut store = self.wallets.write();
+        let wallet = store
+            .wallets
+            .get_mut(id)
+            .ok_or(MobileError::WalletNotFound)?;
+        wallet.set_name(name)
     }
     
     /// Create a transaction
+    ///
+    /// `biometric` is required once biometric authentication is enabled. Fails with
+    /// `SpendLimitExceeded` when the amount would go over the daily spend limit; it only
+    /// counts against the limit once `broadcast_transaction` sends it.
     pub fn create_transaction(
         &self,
         recipient: &str,
         amount: u64,
         fee: u64,
+        biometric: Option<&BiometricProof>,
     ) -> Result<MobileTransaction> {
-        let wallet = self.get_wallet()?;
-        wallet.create_transaction(recipient, amount, fee)
+        self.ensure_authorized(biometric)?;
+        self.security().check_spend(amount, sync::now_secs(), false)?;
+        self.wallets.write().active_mut()?.create_transaction(recipient, amount, fee)
+    }
+    
+    /// Create a transaction past the daily spend limit by re-entering the wallet password
+    pub fn create_transaction_with_step_up(
+        &self,
+        recipient: &str,
+        amount: u64,
+        fee: u64,
+        password: &str,
+        biometric: Option<&BiometricProof>,
+    ) -> Result<MobileTransaction> {
+        self.ensure_authorized(biometric)?;
+        let transaction = {
+            let mut store = self.wallets.write();
+            let wallet = store.active_mut()?;
+            wallet.export_mnemonic(password).map_err(|_| MobileError::InvalidPassword)?;
+            wallet.create_transaction(recipient, amount, fee)?
+        };
+        self.stepped_up.lock().insert(transaction.id.clone());
+        Ok(transaction)
+    }
+    
+    /// Cap what can be sent per day without stepping up; needs a recent unlock
+    pub fn set_spend_policy(&self, policy: SpendPolicy, biometric: Option<&BiometricProof>) -> Result<()> {
+        self.ensure_authorized(biometric)?;
+        self.security().require_recent_auth(sync::now_secs())?;
+        self.security().set_spend_policy(policy);
+        Ok(())
+    }
+    
+    /// Export the active wallet's mnemonic
+    ///
+    /// `biometric` is required once biometric authentication is enabled. Either
+    /// way, the last unlock or biometric check must be within `REAUTH_WINDOW_SECS`.
+    pub fn export_mnemonic(&self, password: &str, biometric: Option<&BiometricProof>) -> Result<Zeroizing<String>> {
+        self.ensure_authorized(biometric)?;
+        self.security().require_recent_auth(sync::now_secs())?;
+        self.with_wallet(|wallet| wallet.export_mnemonic(password))?
+    }
+    
+    /// Install the platform biometric check
+    pub fn set_biometric_authenticator(&self, authenticator: Arc<dyn BiometricAuthenticator>) {
+        self.security().set_biometric_authenticator(authenticator);
+    }
+    
+    /// Require a biometric proof for sensitive calls
+    pub fn enable_biometric(&self) -> Result<()> {
+        self.security().enable_biometric()
+    }
+    
+    /// Stop requiring a biometric proof
+    pub fn disable_biometric(&self) {
+        self.security().disable_biometric();
+    }
+    
+    /// Security manager of the loaded wallet
+    fn security(&self) -> parking_lot::RwLockReadGuard<'_, SecurityManager> {
+        self.security_manager.read()
+    }
+    
+    /// Lock the wallet immediately
+    pub fn lock(&self) {
+        self.security().lock();
+    }
+    
+    /// Is the wallet locked
+    pub fn is_locked(&self) -> bool {
+        self.security().is_locked(sync::now_secs())
+    }
+    
+    /// Unlock the wallet with its password
+    pub fn unlock(&self, password: &str) -> Result<()> {
+        self.with_wallet(|wallet| wallet.export_mnemonic(password))?
+            .map_err(|_| MobileError::InvalidPassword)?;
+        self.security().unlock();
+        Ok(())
+    }
+    
+    /// Fail while locked or, with biometrics enabled, without an accepted proof,
+    /// otherwise count the call as activity
+    fn ensure_authorized(&self, biometric: Option<&BiometricProof>) -> Result<()> {
+        if self.is_locked() {
+            return Err(MobileError::WalletLocked);
+        }
+        if self.security().is_biometric_enabled() {
+            self.security().authenticate_biometric(biometric)?;
+        }
+        self.security().record_activity();
+        Ok(())
     }
     
     /// Get balance
     pub fn get_balance(&self) -> Result<u64> {
-        let wallet = self.get_wallet()?;
-        Ok(wallet.balance())
+        self.with_wallet(|wallet| wallet.balance())
     }
     
     /// Sync wallet
+    ///
+    /// Concurrent syncs all record their transactions, but balances only move forward:
+    /// a sync that started fetching before one already applied keeps the newer balances.
     pub fn sync(&self) -> Result<()> {
-        let wallet = self.get_wallet()?;
-        self.sync_manager.sync(&wallet)
+        // Fetch without holding the lock, then apply the result in one step
+        let (wallet_id, addresses): (String, Vec<String>) = {
+            let stored_wallet = self.wallets.read();
+            let wallet = stored_wallet.active()?;
+            let addresses = wallet
+                .accounts()
+                .iter()
+                .map(|account| account.address().to_string())
+                .collect();
+            (wallet.id().to_string(), addresses)
+        };
+        let ticket = self.sync_tickets.fetch_add(1, Ordering::SeqCst) + 1;
+        let update = self.sync_manager.fetch(&addresses)?;
+        
+        // Apply to the wallet that was fetched, even if another was selected meanwhile
+        let (old_balance, new_balance, changed) = self.update_wallet(&wallet_id, |wallet| {
+            let old_balance = wallet.balance();
+            let before: HashMap<String, (TransactionStatus, u32)> = wallet
+                .transaction_history()
+                .into_iter()
+                .map(|tx| (tx.id, (tx.status, tx.confirmations)))
+                .collect();
+            
+            let mut applied_syncs = self.applied_syncs.lock();
+            let applied = applied_syncs.entry(wallet_id.clone()).or_default();
+            if ticket > *applied {
+                *applied = ticket;
+                update.apply(wallet);
+            } else {
+                update.apply_transactions(wallet);
+            }
+            
+            let changed: Vec<MobileTransaction> = wallet
+                .transaction_history()
+                .into_iter()
+                .filter(|tx| before.get(&tx.id) != Some(&(tx.status, tx.confirmations)))
+                .collect();
+            (old_balance, wallet.balance(), changed)
+        })?;
+        
+        // Fire callbacks after releasing the lock so they can call back into the manager
+        if new_balance != old_balance {
+            self.observers.notify_balance(new_balance);
+        }
+        for transaction in &changed {
+            self.observers.notify_transaction(transaction);
+        }
+        
+        Ok(())
+    }
+    
+    /// Broadcast a signed transaction and record it as pending under the node's txid
+    ///
+    /// Retrying after a timeout is safe: the node recognises the transaction and it is recorded once.
+    /// The first successful broadcast counts the amount against the daily spend limit; unless the
+    /// transaction was stepped up, it fails with `SpendLimitExceeded` before sending if over the limit.
+    pub fn broadcast_transaction(&self, mut transaction: MobileTransaction) -> Result<String> {
+        let stepped_up = self.stepped_up.lock().contains(&transaction.id);
+        if self.find_transaction(&transaction.id)?.is_none() {
+            self.security().check_spend(transaction.amount, sync::now_secs(), stepped_up)?;
+        }
+        
+        let created_id = transaction.id.clone();
+        let txid = self.sync_manager.broadcast(&transaction)?;
+        
+        transaction.id = txid.clone();
+        transaction.set_status(TransactionStatus::Pending);
+        transaction.broadcast = true;
+        
+        let mut stored_wallet = self.wallets.write();
+        let wallet = stored_wallet.active_mut()?;
+        if !wallet.mark_broadcast(&txid) {
+            let amount = transaction.amount;
+            wallet.add_transaction(transaction);
+            // Already sent, so counted even if a concurrent broadcast used up the limit meanwhile
+            self.security().record_spend(amount, sync::now_secs(), true)?;
+            self.stepped_up.lock().remove(&created_id);
+        }
+        
+        Ok(txid)
+    }
+    
+    /// Poll the node for a transaction's status and record it in the history
+    pub fn refresh_transaction_status(&self, txid: &str) -> Result<TransactionStatus> {
+        let state = self.sync_manager.poll_status(txid)?;
+        
+        let updated = {
+            let mut stored_wallet = self.wallets.write();
+            let wallet = stored_wallet.active_mut()?;
+            
+            let previous = wallet
+                .transaction_history()
+                .into_iter()
+                .find(|tx| tx.id == txid);
+            wallet.set_transaction_status(txid, state.status);
+            wallet.set_transaction_confirmations(txid, state.confirmations);
+            previous
+                .filter(|tx| tx.status != state.status || tx.confirmations != state.confirmations)
+                .map(|mut tx| {
+                    tx.status = state.status;
+                    tx.confirmations = state.confirmations;
+                    tx
+                })
+        };
+        
+        if let Some(transaction) = &updated {
+            self.observers.notify_transaction(transaction);
+        }
+        
+        Ok(state.status)
     }
     
     /// Get transaction history
     pub fn get_transaction_history(&self) -> Result<Vec<MobileTransaction>> {
-        let wallet = self.get_wallet()?;
-        Ok(wallet.transaction_history())
+        self.with_wallet(|wallet| wallet.transaction_history())
+    }
+    
+    /// Look up a transaction of the active wallet by ID
+    pub fn find_transaction(&self, id: &str) -> Result<Option<MobileTransaction>> {
+        self.with_wallet(|wallet| wallet.find_transaction(id).cloned())
+    }
+}
+
+/// Identifies a stored wallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletSummary {
+    /// Wallet ID
+    pub id: String,
+    
+    /// Wallet name
+    pub name: String,
+}
+
+/// Wallets held by the manager and which one is active
+#[derive(Debug, Default)]
+struct WalletStore {
+    /// Wallets keyed by ID
+    wallets: HashMap<String, MobileWallet>,
+    
+    /// Active wallet ID
+    active_wallet_id: Option<String>,
+}
+
+impl WalletStore {
+    /// Store a wallet and make it active
+    fn insert(&mut self, wallet: MobileWallet) {
+        let id = wallet.id().to_string();
+        self.wallets.insert(id.clone(), wallet);
+        self.active_wallet_id = Some(id);
+    }
+    
+    /// Active wallet
+    fn active(&self) -> Result<&MobileWallet> {
+        self.active_wallet_id
+            .as_ref()
+            .and_then(|id| self.wallets.get(id))
+            .ok_or(MobileError::NoWalletLoaded)
+    }
+    
+    /// Active wallet, mutably
+    fn active_mut(&mut self) -> Result<&mut MobileWallet> {
+        let id = self.active_wallet_id.as_ref().ok_or(MobileError::NoWalletLoaded)?;
+        self.wallets.get_mut(id).ok_or(MobileError::NoWalletLoaded)
     }
 }
 
 impl Default for MobileWalletManager {
+    /// Manager with default settings; unlike `new`, this can't fail
     fn default() -> Self {
-        Self::new().unwrap_or_else(|_| {
-            Self {
-                wallet: Arc::new(RwLock::new(None)),
-                security_manager: Arc::new(SecurityManager::new().unwrap_or_default()),
-                sync_manager: Arc::new(SyncManager::new().unwrap_or_default()),
-            }
-        })
+        Self::with_managers(SecurityManager::default(), SyncManager::default())
     }
 }
 
@@ -143,4 +568,452 @@ mod tests {
         let manager = MobileWalletManager::new();
         assert!(manager.is_ok());
     }
+    
+    #[test]
+    fn test_load_wallet() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.dat");
+        
+        let wallet = MobileWallet::new("ValidPass123").unwrap();
+        wallet.save_to_path(&path, "ValidPass123").unwrap();
+        
+        let manager = MobileWalletManager::new().unwrap();
+        manager.load_wallet(&path, "ValidPass123").unwrap();
+        assert_eq!(manager.get_wallet().unwrap().id(), wallet.id());
+    }
+    
+    #[test]
+    fn test_security_settings_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.dat");
+        let policy = SpendPolicy { daily_limit: 5000 };
+        
+        let manager = MobileWalletManager::new().unwrap();
+        manager.create_wallet("ValidPass123").unwrap();
+        manager.set_spend_policy(policy, None).unwrap();
+        manager.save_wallet(&path, "ValidPass123").unwrap();
+        
+        let restarted = MobileWalletManager::new().unwrap();
+        assert_eq!(restarted.security().spend_policy(), SpendPolicy::default());
+        restarted.load_wallet(&path, "ValidPass123").unwrap();
+        assert_eq!(restarted.security().spend_policy(), policy);
+        assert!(!restarted.is_locked());
+    }
+    
+    #[test]
+    fn test_locked_wallet_refuses_sensitive_calls() {
+        let manager = MobileWalletManager::new().unwrap();
+        manager.create_wallet("ValidPass123").unwrap();
+        assert!(manager.export_mnemonic("ValidPass123", None).is_ok());
+        
+        manager.lock();
+        assert!(matches!(manager.export_mnemonic("ValidPass123", None), Err(MobileError::WalletLocked)));
+        assert!(matches!(
+            manager.create_transaction("silver_recipient", 1000, 10, None),
+            Err(MobileError::WalletLocked)
+        ));
+        
+        assert!(matches!(manager.unlock("WrongPass123"), Err(MobileError::InvalidPassword)));
+        assert!(manager.is_locked());
+        
+        manager.unlock("ValidPass123").unwrap();
+        assert!(!manager.is_locked());
+        assert!(manager.export_mnemonic("ValidPass123", None).is_ok());
+    }
+    
+    /// Accepts exactly one proof
+    struct MockAuthenticator(Vec<u8>);
+    
+    impl BiometricAuthenticator for MockAuthenticator {
+        fn verify(&self, proof: &BiometricProof) -> bool {
+            proof.0 == self.0
+        }
+    }
+    
+    #[test]
+    fn test_biometric_gates_sensitive_calls() {
+        let manager = MobileWalletManager::new().unwrap();
+        manager.create_wallet("ValidPass123").unwrap();
+        let recipient = Account::address_from_public_key(&[2u8; 33], Network::Mainnet);
+        let accepted = BiometricProof(b"face-ok".to_vec());
+        let rejected = BiometricProof(b"face-bad".to_vec());
+        
+        manager.set_biometric_authenticator(Arc::new(MockAuthenticator(b"face-ok".to_vec())));
+        manager.enable_biometric().unwrap();
+        
+        for proof in [None, Some(&rejected)] {
+            assert!(matches!(
+                manager.export_mnemonic("ValidPass123", proof),
+                Err(MobileError::BiometricAuthFailed)
+            ));
+            assert!(matches!(
+                manager.create_transaction(&recipient, 1000, 10, proof),
+                Err(MobileError::BiometricAuthFailed)
+            ));
+        }
+        
+        assert!(manager.export_mnemonic("ValidPass123", Some(&accepted)).is_ok());
+        // Past the biometric gate, the wallet's own checks apply
+        assert!(matches!(
+            manager.create_transaction(&recipient, 1000, 10, Some(&accepted)),
+            Err(MobileError::InsufficientBalance)
+        ));
+        
+        manager.disable_biometric();
+        assert!(manager.export_mnemonic("ValidPass123", None).is_ok());
+    }
+    
+    #[test]
+    fn test_with_wallet_reads_in_place() {
+        let manager = MobileWalletManager::new().unwrap();
+        assert!(matches!(manager.with_wallet(|wallet| wallet.balance()), Err(MobileError::NoWalletLoaded)));
+        
+        let created = manager.create_wallet("ValidPass123").unwrap();
+        let (id, account_count) = manager
+            .with_wallet(|wallet| (wallet.id().to_string(), wallet.accounts().len()))
+            .unwrap();
+        assert_eq!(id, created);
+        assert_eq!(account_count, 1);
+        
+        // The closure sees the stored wallet itself, not a copy
+        let stored: *const MobileWallet = manager.with_wallet(|wallet| wallet as *const _).unwrap();
+        let again: *const MobileWallet = manager.with_wallet(|wallet| wallet as *const _).unwrap();
+        assert_eq!(stored, again);
+        assert_eq!(manager.get_balance().unwrap(), 0);
+    }
+    
+    #[test]
+    fn test_find_transaction_in_active_wallet() {
+        let manager = MobileWalletManager::new().unwrap();
+        manager.create_wallet("ValidPass123").unwrap();
+        
+        let from = manager.with_wallet(|wallet| wallet.active_account().unwrap().address().to_string()).unwrap();
+        let tx = MobileTransaction::new(from.clone(), from, 1000, 10).unwrap();
+        let txid = tx.id.clone();
+        manager.wallets.write().active_mut().unwrap().add_transaction(tx);
+        
+        assert_eq!(manager.find_transaction(&txid).unwrap().unwrap().id, txid);
+        assert!(manager.find_transaction("tx_missing").unwrap().is_none());
+    }
+    
+    #[test]
+    fn test_import_wallet_validates_mnemonic() {
+        let manager = MobileWalletManager::new().unwrap();
+        let valid = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        assert!(manager.import_wallet(valid, "ValidPass123").is_ok());
+        
+        // A publicly known test phrase
+        let weak = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        assert!(matches!(manager.import_wallet(weak, "ValidPass123"), Err(MobileError::WeakMnemonic)));
+        
+        // A typo'd word that isn't in the wordlist
+        let typo = "abandon abandon abandon abandon abandon abandonn abandon abandon abandon abandon abandon about";
+        assert!(matches!(manager.import_wallet(typo, "ValidPass123"), Err(MobileError::InvalidMnemonic)));
+        
+        // Every word is valid but the checksum isn't
+        let bad_checksum = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+        assert!(matches!(
+            manager.import_wallet(bad_checksum, "ValidPass123"),
+            Err(MobileError::InvalidMnemonic)
+        ));
+        assert_eq!(manager.list_wallets().len(), 1);
+    }
+    
+    #[test]
+    fn test_switch_between_named_wallets() {
+        let manager = MobileWalletManager::new().unwrap();
+        let first = manager.create_wallet("ValidPass123").unwrap();
+        let second = manager.create_wallet("OtherPass456").unwrap();
+        manager.rename_wallet(&first, "Savings").unwrap();
+        
+        let names: Vec<String> = manager.list_wallets().into_iter().map(|w| w.name).collect();
+        assert_eq!(names, vec!["Main Wallet", "Savings"]);
+        assert_eq!(manager.get_wallet().unwrap().id(), second);
+        
+        manager.switch_wallet(&first).unwrap();
+        assert_eq!(manager.get_wallet().unwrap().id(), first);
+        assert_eq!(manager.get_wallet().unwrap().name(), "Savings");
+        
+        // The new selection needs its own password
+        assert!(manager.is_locked());
+        assert!(matches!(manager.unlock("OtherPass456"), Err(MobileError::InvalidPassword)));
+        manager.unlock("ValidPass123").unwrap();
+        
+        assert!(matches!(manager.switch_wallet("missing"), Err(MobileError::WalletNotFound)));
+    }
+    
+    #[test]
+    fn test_balance_follows_active_wallet() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = MobileWalletManager::new().unwrap();
+        let mut ids = Vec::new();
+        for (file, balance) in [("spending.dat", 1500), ("savings.dat", 90_000)] {
+            let mut wallet = MobileWallet::new("ValidPass123").unwrap();
+            wallet.set_balance(balance);
+            let path = dir.path().join(file);
+            wallet.save_to_path(&path, "ValidPass123").unwrap();
+            ids.push(manager.load_wallet(&path, "ValidPass123").unwrap().id().to_string());
+        }
+        assert_eq!(manager.active_wallet_id().as_deref(), Some(ids[1].as_str()));
+        assert_eq!(manager.get_balance().unwrap(), 90_000);
+        
+        manager.switch_wallet(&ids[0]).unwrap();
+        manager.unlock("ValidPass123").unwrap();
+        assert_eq!(manager.get_balance().unwrap(), 1500);
+        
+        // Removing the active wallet leaves none selected
+        manager.remove_wallet(&ids[0]).unwrap();
+        assert!(manager.active_wallet_id().is_none());
+        assert!(manager.is_locked());
+        assert!(matches!(manager.get_balance(), Err(MobileError::NoWalletLoaded)));
+        assert!(matches!(manager.remove_wallet(&ids[0]), Err(MobileError::WalletNotFound)));
+        
+        manager.switch_wallet(&ids[1]).unwrap();
+        assert_eq!(manager.get_balance().unwrap(), 90_000);
+    }
+    
+    #[test]
+    fn test_spend_limit_requires_step_up() {
+        let mut server = mockito::Server::new();
+        let manager = MobileWalletManager::with_sync_manager(SyncManager::with_node_url(&server.url()).unwrap()).unwrap();
+        manager.create_wallet("ValidPass123").unwrap();
+        manager.wallets.write().active_mut().unwrap().set_balance(100_000);
+        manager.set_spend_policy(SpendPolicy { daily_limit: 5000 }, None).unwrap();
+        let recipient = Account::address_from_public_key(&[2u8; 33], Network::Mainnet);
+        let mut accept = |transaction: &MobileTransaction| {
+            server
+                .mock("POST", "/transactions")
+                .match_header(sync::IDEMPOTENCY_KEY_HEADER, transaction.id.as_str())
+                .with_status(200)
+                .with_body(format!(r#"{{"txid": "{}"}}"#, transaction.id))
+                .create()
+        };
+        
+        // Only what is broadcast counts against the limit
+        let first = manager.create_transaction(&recipient, 3000, 10, None).unwrap();
+        let second = manager.create_transaction(&recipient, 2000, 10, None).unwrap();
+        let third = manager.create_transaction(&recipient, 1000, 10, None).unwrap();
+        assert_eq!(manager.security().spent_in_window(sync::now_secs()), 0);
+        
+        let _first_mock = accept(&first);
+        let _second_mock = accept(&second);
+        manager.broadcast_transaction(first).unwrap();
+        manager.broadcast_transaction(second).unwrap();
+        assert_eq!(manager.security().spent_in_window(sync::now_secs()), 5000);
+        
+        // Over the limit is refused before anything is sent
+        let third_mock = accept(&third);
+        assert!(matches!(manager.broadcast_transaction(third), Err(MobileError::SpendLimitExceeded)));
+        assert!(!third_mock.matched());
+        assert!(matches!(
+            manager.create_transaction(&recipient, 1000, 10, None),
+            Err(MobileError::SpendLimitExceeded)
+        ));
+        
+        assert!(matches!(
+            manager.create_transaction_with_step_up(&recipient, 1000, 10, "WrongPass123", None),
+            Err(MobileError::InvalidPassword)
+        ));
+        let stepped_up = manager.create_transaction_with_step_up(&recipient, 1000, 10, "ValidPass123", None).unwrap();
+        let _stepped_up_mock = accept(&stepped_up);
+        manager.broadcast_transaction(stepped_up).unwrap();
+        assert_eq!(manager.security().spent_in_window(sync::now_secs()), 6000);
+    }
+    
+    #[test]
+    fn test_recent_spends_survive_restart() {
+        let mut server = mockito::Server::new();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.dat");
+        let manager = MobileWalletManager::with_sync_manager(SyncManager::with_node_url(&server.url()).unwrap()).unwrap();
+        manager.create_wallet("ValidPass123").unwrap();
+        manager.wallets.write().active_mut().unwrap().set_balance(100_000);
+        manager.set_spend_policy(SpendPolicy { daily_limit: 5000 }, None).unwrap();
+        let recipient = Account::address_from_public_key(&[2u8; 33], Network::Mainnet);
+        
+        let transaction = manager.create_transaction(&recipient, 4000, 10, None).unwrap();
+        server
+            .mock("POST", "/transactions")
+            .with_status(200)
+            .with_body(format!(r#"{{"txid": "{}"}}"#, transaction.id))
+            .create();
+        manager.broadcast_transaction(transaction).unwrap();
+        manager.save_wallet(&path, "ValidPass123").unwrap();
+        
+        let restarted = MobileWalletManager::new().unwrap();
+        restarted.load_wallet(&path, "ValidPass123").unwrap();
+        assert_eq!(restarted.security().spent_in_window(sync::now_secs()), 4000);
+        assert!(matches!(
+            restarted.create_transaction(&recipient, 2000, 10, None),
+            Err(MobileError::SpendLimitExceeded)
+        ));
+    }
+    
+    #[test]
+    fn test_broadcast_retry_after_timeout_records_once() {
+        use std::io::Write;
+        
+        let mut server = mockito::Server::new();
+        let sync_manager = SyncManager::with_node_url(&server.url())
+            .unwrap()
+            .with_timeout(std::time::Duration::from_millis(200));
+        let manager = MobileWalletManager::with_sync_manager(sync_manager).unwrap();
+        manager.create_wallet("ValidPass123").unwrap();
+        manager.wallets.write().active_mut().unwrap().set_balance(10_000);
+        let recipient = Account::address_from_public_key(&[2u8; 33], Network::Mainnet);
+        let transaction = manager.create_transaction(&recipient, 1000, 10, None).unwrap();
+        
+        // The node accepts the first attempt but answers too late
+        let slow = server
+            .mock("POST", "/transactions")
+            .match_header(sync::IDEMPOTENCY_KEY_HEADER, transaction.id.as_str())
+            .with_status(200)
+            .with_chunked_body(|body| {
+                std::thread::sleep(std::time::Duration::from_millis(600));
+                body.write_all(br#"{"txid": "ignored"}"#)
+            })
+            .create();
+        assert!(manager.broadcast_transaction(transaction.clone()).is_err());
+        slow.remove();
+        
+        // Retries are recognised by their key and recorded once
+        server
+            .mock("POST", "/transactions")
+            .match_header(sync::IDEMPOTENCY_KEY_HEADER, transaction.id.as_str())
+            .with_status(409)
+            .with_body(format!(r#"{{"txid": "{}"}}"#, transaction.id))
+            .expect(2)
+            .create();
+        assert_eq!(manager.broadcast_transaction(transaction.clone()).unwrap(), transaction.id);
+        assert_eq!(manager.broadcast_transaction(transaction.clone()).unwrap(), transaction.id);
+        
+        let history = manager.get_transaction_history().unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].id, transaction.id);
+        assert!(history[0].broadcast);
+    }
+    
+    #[test]
+    fn test_concurrent_syncs_keep_newest_balance() {
+        use std::io::Write;
+        
+        let mut server = mockito::Server::new();
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        let manager = MobileWalletManager::with_sync_manager(sync_manager).unwrap();
+        manager.create_wallet("ValidPass123").unwrap();
+        let address = manager
+            .with_wallet(|wallet| wallet.active_account().unwrap().address().to_string())
+            .unwrap();
+        let balance_path = format!("/accounts/{}/balance", address);
+        let history_path = format!("/accounts/{}/transactions", address);
+        let history = |id: &str| {
+            format!(
+                r#"{{"transactions": [{{"id": "{}", "from": "silver_sender", "to": "{}",
+                    "amount": 1000, "fee": 10, "status": "Confirmed", "timestamp": 1700000000}}]}}"#,
+                id, address
+            )
+        };
+        
+        // The first sync's balance arrives late, after the second sync has applied a newer one
+        server
+            .mock("GET", balance_path.as_str())
+            .with_status(200)
+            .with_chunked_body(|body| {
+                std::thread::sleep(std::time::Duration::from_millis(400));
+                body.write_all(br#"{"balance": 1000}"#)
+            })
+            .expect(1)
+            .create();
+        server
+            .mock("GET", balance_path.as_str())
+            .with_status(200)
+            .with_body(r#"{"balance": 2000}"#)
+            .expect(1)
+            .create();
+        server
+            .mock("GET", history_path.as_str())
+            .with_status(200)
+            .with_body(history("tx_second"))
+            .expect(1)
+            .create();
+        server
+            .mock("GET", history_path.as_str())
+            .with_status(200)
+            .with_body(history("tx_first"))
+            .expect(1)
+            .create();
+        
+        let first = {
+            let manager = manager.clone();
+            std::thread::spawn(move || manager.sync())
+        };
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        let second = {
+            let manager = manager.clone();
+            std::thread::spawn(move || manager.sync())
+        };
+        second.join().unwrap().unwrap();
+        first.join().unwrap().unwrap();
+        
+        // Both syncs' transactions are kept, and the stale balance didn't overwrite the newer one
+        assert_eq!(manager.get_balance().unwrap(), 2000);
+        let mut ids: Vec<String> = manager
+            .get_transaction_history()
+            .unwrap()
+            .into_iter()
+            .map(|tx| tx.id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["tx_first", "tx_second"]);
+    }
+    
+    #[test]
+    fn test_sync_fires_observers() {
+        use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+        
+        let mut server = mockito::Server::new();
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        let manager = MobileWalletManager::with_sync_manager(sync_manager).unwrap();
+        manager.create_wallet("ValidPass123").unwrap();
+        let address = manager
+            .with_wallet(|wallet| wallet.active_account().unwrap().address().to_string())
+            .unwrap();
+        
+        server
+            .mock("GET", format!("/accounts/{}/balance", address).as_str())
+            .with_status(200)
+            .with_body(r#"{"balance": 4200}"#)
+            .create();
+        server
+            .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+            .with_status(200)
+            .with_body(format!(
+                r#"{{"transactions": [{{"id": "tx_1", "from": "silver_sender", "to": "{}",
+                    "amount": 4200, "fee": 10, "status": "Confirmed", "timestamp": 1700000000}}]}}"#,
+                address
+            ))
+            .create();
+        
+        let balance = Arc::new(AtomicU64::new(0));
+        let updates = Arc::new(AtomicUsize::new(0));
+        let balance_sink = balance.clone();
+        let updates_sink = updates.clone();
+        let reentrant = manager.clone();
+        manager.on_balance_changed(Box::new(move |new_balance| {
+            // Reading through the manager from a callback must not deadlock
+            assert_eq!(reentrant.get_balance().unwrap(), new_balance);
+            balance_sink.store(new_balance, Ordering::SeqCst);
+        }));
+        manager.on_transaction_update(Box::new(move |_| {
+            updates_sink.fetch_add(1, Ordering::SeqCst);
+        }));
+        
+        manager.sync().unwrap();
+        assert_eq!(balance.load(Ordering::SeqCst), 4200);
+        assert_eq!(updates.load(Ordering::SeqCst), 1);
+        
+        // Nothing changed, so nothing fires
+        manager.sync().unwrap();
+        assert_eq!(updates.load(Ordering::SeqCst), 1);
+    }
 }
@@ -0,0 +1,38 @@
+//! Diagnostics emitted through `tracing` when the `log` feature is enabled
+//!
+//! Without the feature the macros expand to nothing, so the default build pays nothing.
+//! Never pass passwords, mnemonics, seeds or key bytes to them.
+
+/// Emit a debug-level `tracing` event
+#[cfg(feature = "log")]
+macro_rules! log_event {
+    ($($arg:tt)+) => {
+        tracing::debug!($($arg)+)
+    };
+}
+
+/// Emit a debug-level `tracing` event
+#[cfg(not(feature = "log"))]
+macro_rules! log_event {
+    ($($arg:tt)+) => {};
+}
+
+/// Enter a debug-level `tracing` span until the returned guard drops
+#[cfg(feature = "log")]
+macro_rules! log_span {
+    ($($arg:tt)+) => {
+        tracing::debug_span!($($arg)+).entered()
+    };
+}
+
+/// Enter a debug-level `tracing` span until the returned guard drops
+#[cfg(not(feature = "log"))]
+macro_rules! log_span {
+    ($($arg:tt)+) => {
+        $crate::logging::NoSpan
+    };
+}
+
+/// Stand-in span guard when logging is compiled out
+#[cfg(not(feature = "log"))]
+pub(crate) struct NoSpan;
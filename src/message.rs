@@ -0,0 +1,67 @@
+//! Signing free-form messages, e.g. to prove ownership of an address
+
+use secp256k1::{ecdsa::Signature, Message, PublicKey, Secp256k1, SecretKey};
+use sha2::{Digest, Sha256};
+
+/// Domain separator hashed ahead of every message
+///
+/// Keeps a message signature from ever matching a transaction's signing hash.
+pub const MESSAGE_PREFIX: &[u8] = b"SilverBitcoin Signed Message:\n";
+
+/// Hash that is actually signed for `message`
+pub fn message_hash(message: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(MESSAGE_PREFIX);
+    hasher.update((message.len() as u64).to_le_bytes());
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+/// Sign `message` with a secret key, returning a 64-byte compact signature
+pub fn sign_message(secret_key: &SecretKey, message: &[u8]) -> Vec<u8> {
+    let secp = Secp256k1::signing_only();
+    let digest = Message::from_digest(message_hash(message));
+    secp.sign_ecdsa(&digest, secret_key).serialize_compact().to_vec()
+}
+
+/// Verify a message signature against a compressed public key
+pub fn verify_message(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let secp = Secp256k1::verification_only();
+    
+    let public_key = match PublicKey::from_slice(public_key) {
+        Ok(public_key) => public_key,
+        Err(_) => return false,
+    };
+    let signature = match Signature::from_compact(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    
+    let digest = Message::from_digest(message_hash(message));
+    secp.verify_ecdsa(&digest, &signature, &public_key).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_sign_and_verify_message() {
+        let secret_key = SecretKey::from_slice(&[0x11; 32]).unwrap();
+        let public_key = PublicKey::from_secret_key(&Secp256k1::new(), &secret_key).serialize();
+        
+        let signature = sign_message(&secret_key, b"log in to example.com");
+        assert_eq!(signature.len(), 64);
+        assert!(verify_message(&public_key, b"log in to example.com", &signature));
+        
+        assert!(!verify_message(&public_key, b"log in to example.org", &signature));
+        assert!(!verify_message(&public_key, b"log in to example.com", &signature[..63]));
+        assert!(!verify_message(&[0x02; 33], b"log in to example.com", &signature));
+        
+        // Signing the same digest without the prefix gives a signature that does not verify
+        let bare = Secp256k1::new()
+            .sign_ecdsa(&Message::from_digest(Sha256::digest(b"log in to example.com").into()), &secret_key)
+            .serialize_compact();
+        assert!(!verify_message(&public_key, b"log in to example.com", &bare));
+    }
+}
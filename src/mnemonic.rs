@@ -0,0 +1,253 @@
+//! BIP39 mnemonic generation, validation and seed derivation
+
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+use crate::errors::{MobileError, Result};
+use crate::wordlist::ENGLISH;
+
+/// Mnemonic word counts accepted by BIP39
+pub const VALID_WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// Default mnemonic word count for new keystores
+pub const DEFAULT_WORD_COUNT: usize = 12;
+
+/// PBKDF2 iteration count mandated by BIP39
+const SEED_ROUNDS: u32 = 2048;
+
+/// Published test phrases, as a word repeated up to a final checksum word
+///
+/// Funds sent to these are swept by bots within minutes.
+const WEAK_MNEMONICS: [(&str, &str); 7] = [
+    ("abandon", "about"),
+    ("abandon", "agent"),
+    ("abandon", "art"),
+    ("zoo", "wrong"),
+    ("zoo", "when"),
+    ("zoo", "vote"),
+    ("test", "junk"),
+];
+
+/// Generate a new random mnemonic with the given word count
+pub fn generate(word_count: usize) -> Result<Zeroizing<String>> {
+    use rand::Rng;
+    
+    if !VALID_WORD_COUNTS.contains(&word_count) {
+        return Err(MobileError::InvalidMnemonic);
+    }
+    
+    // Every 3 words carry 32 bits of entropy (plus 1 checksum bit)
+    let mut entropy = Zeroizing::new(vec![0u8; word_count / 3 * 4]);
+    rand::thread_rng().fill(&mut entropy[..]);
+    
+    from_entropy(&entropy)
+}
+
+/// Encode entropy (16 to 32 bytes, multiple of 4) as a mnemonic
+pub fn from_entropy(entropy: &[u8]) -> Result<Zeroizing<String>> {
+    let entropy_bits = entropy.len() * 8;
+    if !(128..=256).contains(&entropy_bits) || !entropy_bits.is_multiple_of(32) {
+        return Err(MobileError::InvalidMnemonic);
+    }
+    
+    let checksum_bits = entropy_bits / 32;
+    let hash = Sha256::digest(entropy);
+    
+    let mut bits = Vec::with_capacity(entropy_bits + checksum_bits);
+    for byte in entropy {
+        for i in (0..8).rev() {
+            bits.push((byte >> i) & 1 == 1);
+        }
+    }
+    for i in 0..checksum_bits {
+        bits.push(hash_bit(&hash, i));
+    }
+    
+    // Map each 11-bit group to a word
+    let words: Vec<&str> = bits
+        .chunks(11)
+        .map(|chunk| {
+            let index = chunk.iter().fold(0usize, |acc, &bit| (acc << 1) | bit as usize);
+            ENGLISH[index]
+        })
+        .collect();
+    
+    Ok(Zeroizing::new(words.join(" ")))
+}
+
+/// Decode a mnemonic back to its entropy, verifying words and checksum
+pub fn to_entropy(mnemonic: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let words: Vec<&str> = mnemonic.split_whitespace().collect();
+    if !VALID_WORD_COUNTS.contains(&words.len()) {
+        return Err(MobileError::InvalidMnemonic);
+    }
+    
+    let mut bits = Vec::with_capacity(words.len() * 11);
+    for word in &words {
+        let index = ENGLISH
+            .binary_search(word)
+            .map_err(|_| MobileError::InvalidMnemonic)?;
+        for i in (0..11).rev() {
+            bits.push((index >> i) & 1 == 1);
+        }
+    }
+    
+    let checksum_bits = bits.len() / 33;
+    let entropy_bits = bits.len() - checksum_bits;
+    
+    let entropy: Zeroizing<Vec<u8>> = Zeroizing::new(
+        bits[..entropy_bits]
+            .chunks(8)
+            .map(|chunk| chunk.iter().fold(0u8, |acc, &bit| (acc << 1) | bit as u8))
+            .collect(),
+    );
+    
+    // Verify checksum
+    let hash = Sha256::digest(&*entropy);
+    for i in 0..checksum_bits {
+        if bits[entropy_bits + i] != hash_bit(&hash, i) {
+            return Err(MobileError::InvalidMnemonic);
+        }
+    }
+    
+    Ok(entropy)
+}
+
+/// Validate a mnemonic against the wordlist and checksum
+pub fn validate(mnemonic: &str) -> Result<()> {
+    to_entropy(mnemonic).map(|_| ())
+}
+
+/// Is a mnemonic one of the publicly known test phrases
+pub fn is_weak(mnemonic: &str) -> bool {
+    let normalized = normalize(mnemonic);
+    let words: Vec<&str> = normalized.split(' ').collect();
+    let Some((last, repeated)) = words.split_last() else {
+        return false;
+    };
+    
+    WEAK_MNEMONICS.iter().any(|(word, checksum_word)| {
+        last == checksum_word && !repeated.is_empty() && repeated.iter().all(|w| w == word)
+    })
+}
+
+/// Clean up a pasted mnemonic: trim, collapse whitespace runs to single spaces and lowercase
+pub fn normalize(mnemonic: &str) -> Zeroizing<String> {
+    let mut normalized = Zeroizing::new(String::with_capacity(mnemonic.len()));
+    for word in mnemonic.split_whitespace() {
+        if !normalized.is_empty() {
+            normalized.push(' ');
+        }
+        normalized.extend(word.chars().flat_map(char::to_lowercase));
+    }
+    normalized
+}
+
+/// Pick `count` distinct word positions (0-based, ascending) of a `word_count`-word mnemonic to quiz
+pub fn challenge_positions(word_count: usize, count: usize) -> Vec<usize> {
+    let mut positions = rand::seq::index::sample(&mut rand::thread_rng(), word_count, count.min(word_count)).into_vec();
+    positions.sort_unstable();
+    positions
+}
+
+/// Derive the 64-byte BIP39 seed from a mnemonic and optional passphrase
+///
+/// The mnemonic and passphrase are used as-is; callers are expected to pass
+/// NFKD-normalized input (always true for the English wordlist).
+pub fn to_seed(mnemonic: &str, passphrase: &str) -> Zeroizing<[u8; 64]> {
+    use sha2::Sha512;
+    
+    let salt = Zeroizing::new(format!("mnemonic{}", passphrase));
+    let mut seed = Zeroizing::new([0u8; 64]);
+    pbkdf2::pbkdf2_hmac::<Sha512>(mnemonic.as_bytes(), salt.as_bytes(), SEED_ROUNDS, &mut *seed);
+    seed
+}
+
+/// Get bit `i` (most significant first) of a hash
+fn hash_bit(hash: &[u8], i: usize) -> bool {
+    (hash[i / 8] >> (7 - i % 8)) & 1 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_from_entropy_vectors() {
+        assert_eq!(
+            from_entropy(&[0u8; 16]).unwrap().as_str(),
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+        );
+        assert_eq!(
+            from_entropy(&[0x7f; 16]).unwrap().as_str(),
+            "legal winner thank year wave sausage worth useful legal winner thank yellow"
+        );
+        assert_eq!(
+            from_entropy(&[0xff; 16]).unwrap().as_str(),
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong"
+        );
+    }
+    
+    #[test]
+    fn test_seed_vector() {
+        let seed = to_seed(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            "TREZOR",
+        );
+        assert_eq!(
+            hex::encode(*seed),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+    }
+    
+    #[test]
+    fn test_generated_mnemonic_round_trip() {
+        for &word_count in VALID_WORD_COUNTS.iter() {
+            let mnemonic = generate(word_count).unwrap();
+            assert_eq!(mnemonic.split_whitespace().count(), word_count);
+            
+            let entropy = to_entropy(&mnemonic).unwrap();
+            assert_eq!(entropy.len(), word_count / 3 * 4);
+            assert_eq!(from_entropy(&entropy).unwrap(), mnemonic);
+        }
+    }
+    
+    #[test]
+    fn test_validate_rejects_unknown_word() {
+        let mnemonic = "legal winner thank year wave sausage worth useful legal winner thank yelow";
+        assert!(matches!(validate(mnemonic), Err(MobileError::InvalidMnemonic)));
+    }
+    
+    #[test]
+    fn test_is_weak() {
+        assert!(is_weak("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"));
+        assert!(is_weak(" Zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo WRONG "));
+        assert!(!is_weak("legal winner thank year wave sausage worth useful legal winner thank yellow"));
+        assert!(!is_weak(&generate(12).unwrap()));
+        assert!(!is_weak(""));
+    }
+    
+    #[test]
+    fn test_challenge_positions() {
+        let positions = challenge_positions(12, 3);
+        assert_eq!(positions.len(), 3);
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(positions.iter().all(|&position| position < 12));
+        assert_eq!(challenge_positions(12, 20), (0..12).collect::<Vec<_>>());
+    }
+    
+    #[test]
+    fn test_normalize() {
+        let messy = "  Legal winner\tthank  year\nwave sausage worth useful legal winner thank YELLOW \n";
+        assert_eq!(
+            &*normalize(messy),
+            "legal winner thank year wave sausage worth useful legal winner thank yellow"
+        );
+        assert!(normalize(" \n ").is_empty());
+    }
+    
+    #[test]
+    fn test_validate_rejects_bad_checksum() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+        assert!(matches!(validate(mnemonic), Err(MobileError::InvalidMnemonic)));
+    }
+}
@@ -0,0 +1,52 @@
+//! Network selection for mobile wallet
+
+use serde::{Deserialize, Serialize};
+use crate::hd::COIN_TYPE;
+
+/// Network a wallet operates on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Network {
+    /// Mainnet
+    #[default]
+    Mainnet,
+    
+    /// Testnet
+    Testnet,
+}
+
+impl Network {
+    /// Address prefix for this network
+    pub fn address_prefix(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "silver_",
+            Network::Testnet => "tsilver_",
+        }
+    }
+    
+    /// BIP44 coin type for this network (testnets share coin type 1)
+    pub fn coin_type(&self) -> u32 {
+        match self {
+            Network::Mainnet => COIN_TYPE,
+            Network::Testnet => 1,
+        }
+    }
+    
+    /// Detect the network of an address from its prefix
+    pub fn from_address(address: &str) -> Option<Self> {
+        [Network::Mainnet, Network::Testnet]
+            .into_iter()
+            .find(|network| address.starts_with(network.address_prefix()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_network_from_address() {
+        assert_eq!(Network::from_address("silver_abc123"), Some(Network::Mainnet));
+        assert_eq!(Network::from_address("tsilver_abc123"), Some(Network::Testnet));
+        assert_eq!(Network::from_address("bitcoin_abc123"), None);
+    }
+}
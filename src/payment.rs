@@ -0,0 +1,242 @@
+//! Payment requests encoded as `silver:` URIs
+
+use serde::{Deserialize, Serialize};
+use crate::account::Account;
+use crate::errors::{MobileError, Result};
+use crate::transaction::{MobileTransaction, TransactionStatus};
+
+/// URI scheme for SilverBitcoin payment requests
+pub const URI_SCHEME: &str = "silver:";
+
+/// Payment request encoded as a `silver:address?amount=X&label=Y&message=Z&expires=T` URI
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentRequest {
+    /// Recipient address
+    pub address: String,
+    
+    /// Requested amount in base units
+    pub amount: Option<u64>,
+    
+    /// Recipient label
+    pub label: Option<String>,
+    
+    /// Payment message
+    pub message: Option<String>,
+    
+    /// Time after which the request should no longer be paid (seconds since the Unix epoch)
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    
+    /// ID of the incoming transaction that paid the request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fulfilled_by: Option<String>,
+}
+
+impl PaymentRequest {
+    /// Parse a payment URI
+    pub fn from_uri(uri: &str) -> Result<Self> {
+        // The scheme is case-insensitive
+        let scheme = uri.get(..URI_SCHEME.len()).ok_or(MobileError::InvalidTransaction)?;
+        if !scheme.eq_ignore_ascii_case(URI_SCHEME) {
+            return Err(MobileError::InvalidTransaction);
+        }
+        let rest = &uri[URI_SCHEME.len()..];
+        
+        let (address, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let address = percent_decode(address)?;
+        if !Account::validate_address(&address) {
+            return Err(MobileError::InvalidAddress);
+        }
+        
+        let mut request = Self {
+            address,
+            amount: None,
+            label: None,
+            message: None,
+            expires_at: None,
+            fulfilled_by: None,
+        };
+        
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value)?;
+            
+            match key {
+                "amount" => {
+                    let amount = value.parse().map_err(|_| MobileError::InvalidTransaction)?;
+                    request.amount = Some(amount);
+                }
+                "label" => request.label = Some(value),
+                "message" => request.message = Some(value),
+                "expires" => {
+                    let expires_at = value.parse().map_err(|_| MobileError::InvalidTransaction)?;
+                    request.expires_at = Some(expires_at);
+                }
+                // Unknown required parameters must not be ignored
+                key if key.starts_with("req-") => return Err(MobileError::InvalidTransaction),
+                _ => {}
+            }
+        }
+        
+        Ok(request)
+    }
+    
+    /// Encode the request as a payment URI, for display as a QR code
+    pub fn to_uri(&self) -> String {
+        let mut query = Vec::new();
+        if let Some(amount) = self.amount {
+            query.push(format!("amount={}", amount));
+        }
+        if let Some(label) = &self.label {
+            query.push(format!("label={}", percent_encode(label)));
+        }
+        if let Some(message) = &self.message {
+            query.push(format!("message={}", percent_encode(message)));
+        }
+        if let Some(expires_at) = self.expires_at {
+            query.push(format!("expires={}", expires_at));
+        }
+        
+        let mut uri = format!("{}{}", URI_SCHEME, self.address);
+        if !query.is_empty() {
+            uri.push('?');
+            uri.push_str(&query.join("&"));
+        }
+        uri
+    }
+    
+    /// Has the request expired at `now` (seconds since the Unix epoch)
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+    
+    /// Is the request still waiting for payment at `now`
+    pub fn is_open(&self, now: u64) -> bool {
+        self.fulfilled_by.is_none() && !self.is_expired(now)
+    }
+    
+    /// Does an incoming transaction pay this open request
+    ///
+    /// It must reach the address before the expiry with at least the requested amount, summed
+    /// over every output to the address, and must not have failed, been withdrawn or replaced.
+    /// Whether the transaction is incoming is up to the caller.
+    pub fn is_paid_by(&self, transaction: &MobileTransaction) -> bool {
+        let live = !matches!(
+            transaction.status,
+            TransactionStatus::Failed
+                | TransactionStatus::Cancelled
+                | TransactionStatus::Expired
+                | TransactionStatus::Replaced
+        );
+        let outputs: Vec<u64> = transaction
+            .recipients()
+            .into_iter()
+            .filter(|output| output.address == self.address)
+            .map(|output| output.amount)
+            .collect();
+        let paid = outputs.iter().fold(0u64, |total, amount| total.saturating_add(*amount));
+        
+        live && self.is_open(transaction.timestamp)
+            && !outputs.is_empty()
+            && self.amount.is_none_or(|amount| paid >= amount)
+    }
+}
+
+/// Percent-encode a URI component, keeping only unreserved characters
+pub(crate) fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Decode `%XX` escapes in a URI component
+fn percent_decode(input: &str) -> Result<String> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let escape = input.get(i + 1..i + 3).ok_or(MobileError::InvalidTransaction)?;
+            let byte = hex::decode(escape).map_err(|_| MobileError::InvalidTransaction)?;
+            decoded.extend_from_slice(&byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    
+    String::from_utf8(decoded).map_err(|_| MobileError::InvalidTransaction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    
+    fn address() -> String {
+        Account::address_from_public_key(&[2u8; 33], Network::Mainnet)
+    }
+    
+    #[test]
+    fn test_parse_full_uri() {
+        let uri = format!("silver:{}?amount=1500&label=Alice%20Smith&message=Rent%20%26%20bills", address());
+        let request = PaymentRequest::from_uri(&uri).unwrap();
+        
+        assert_eq!(request.address, address());
+        assert_eq!(request.amount, Some(1500));
+        assert_eq!(request.label.as_deref(), Some("Alice Smith"));
+        assert_eq!(request.message.as_deref(), Some("Rent & bills"));
+    }
+    
+    #[test]
+    fn test_parse_bare_address() {
+        let request = PaymentRequest::from_uri(&format!("silver:{}", address())).unwrap();
+        
+        assert_eq!(request.address, address());
+        assert_eq!(request.amount, None);
+        assert_eq!(request.label, None);
+        assert_eq!(request.message, None);
+    }
+    
+    #[test]
+    fn test_parse_rejects_malformed_uri() {
+        let bitcoin = format!("bitcoin:{}", address());
+        assert!(matches!(PaymentRequest::from_uri(&bitcoin), Err(MobileError::InvalidTransaction)));
+        
+        let bad_amount = format!("silver:{}?amount=abc", address());
+        assert!(matches!(PaymentRequest::from_uri(&bad_amount), Err(MobileError::InvalidTransaction)));
+        
+        let bad_escape = format!("silver:{}?label=%zz", address());
+        assert!(matches!(PaymentRequest::from_uri(&bad_escape), Err(MobileError::InvalidTransaction)));
+        
+        let required = format!("silver:{}?req-unknown=1", address());
+        assert!(matches!(PaymentRequest::from_uri(&required), Err(MobileError::InvalidTransaction)));
+        
+        assert!(matches!(PaymentRequest::from_uri("silver:not_an_address"), Err(MobileError::InvalidAddress)));
+    }
+    
+    #[test]
+    fn test_uri_round_trip_with_expiry() {
+        let request = PaymentRequest {
+            address: address(),
+            amount: Some(2500),
+            label: Some("Coffee & cake".to_string()),
+            message: None,
+            expires_at: Some(1_700_000_600),
+            fulfilled_by: None,
+        };
+        let uri = request.to_uri();
+        assert_eq!(uri, format!("silver:{}?amount=2500&label=Coffee%20%26%20cake&expires=1700000600", address()));
+        assert_eq!(PaymentRequest::from_uri(&uri).unwrap(), request);
+        
+        assert!(!request.is_expired(1_700_000_599));
+        assert!(request.is_expired(1_700_000_600));
+        assert!(!request.is_open(1_700_000_600));
+    }
+}
@@ -0,0 +1,210 @@
+//! Fiat price conversion
+
+use std::collections::HashMap;
+use std::time::Duration;
+use serde::Deserialize;
+use crate::amount;
+use crate::errors::{MobileError, NetworkErrorKind, Result};
+
+/// Base units per whole coin
+pub const UNITS_PER_COIN: u64 = 10u64.pow(amount::DECIMALS as u32);
+
+/// Timeout applied to price requests
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Source of coin-to-fiat exchange rates
+pub trait PriceProvider {
+    /// Price of one coin in `fiat` (ISO 4217 code such as `USD`)
+    fn rate(&self, fiat: &str) -> Result<f64>;
+}
+
+/// Provider backed by a fixed rate table
+#[derive(Debug, Clone, Default)]
+pub struct StaticPriceProvider {
+    /// Rates keyed by upper-case currency code
+    rates: HashMap<String, f64>,
+}
+
+impl StaticPriceProvider {
+    /// Create an empty provider
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Add a rate for a currency
+    pub fn with_rate(mut self, fiat: &str, rate: f64) -> Self {
+        self.rates.insert(fiat.to_ascii_uppercase(), rate);
+        self
+    }
+}
+
+impl PriceProvider for StaticPriceProvider {
+    fn rate(&self, fiat: &str) -> Result<f64> {
+        self.rates
+            .get(&fiat.to_ascii_uppercase())
+            .copied()
+            .ok_or_else(|| MobileError::UnsupportedCurrency(fiat.to_string()))
+    }
+}
+
+/// Provider querying `GET {base_url}/rates/{CURRENCY}` for `{"rate": <f64>}`
+#[derive(Debug, Clone)]
+pub struct HttpPriceProvider {
+    /// Price service URL
+    base_url: String,
+}
+
+/// Price service response
+#[derive(Deserialize)]
+struct RateResponse {
+    rate: f64,
+}
+
+impl HttpPriceProvider {
+    /// Create a provider for a price service
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl PriceProvider for HttpPriceProvider {
+    fn rate(&self, fiat: &str) -> Result<f64> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(MobileError::from)?;
+        
+        let response = client
+            .get(format!("{}/rates/{}", self.base_url, fiat.to_ascii_uppercase()))
+            .send()
+            .map_err(MobileError::from)?;
+        
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(MobileError::UnsupportedCurrency(fiat.to_string()));
+        }
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            return Err(MobileError::network(
+                NetworkErrorKind::Http(status),
+                format!("Price service returned HTTP {}", status),
+            ));
+        }
+        
+        let response: RateResponse = response
+            .json()
+            .map_err(MobileError::from)?;
+        Ok(response.rate)
+    }
+}
+
+/// Convert base units to fiat at `rate`
+///
+/// Whole coins and the fractional remainder are converted separately so large
+/// balances keep their precision, and the result is rounded to the nearest
+/// cent (half away from zero). Treat the value as display-only.
+pub fn to_fiat(amount: u64, rate: f64) -> Result<f64> {
+    if !rate.is_finite() || rate < 0.0 {
+        return Err(MobileError::network(NetworkErrorKind::Decode, format!("Invalid exchange rate: {}", rate)));
+    }
+    
+    let whole = (amount / UNITS_PER_COIN) as f64;
+    let fraction = (amount % UNITS_PER_COIN) as f64 / UNITS_PER_COIN as f64;
+    let value = whole * rate + fraction * rate;
+    
+    Ok((value * 100.0).round() / 100.0)
+}
+
+/// Decimal places of an ISO 4217 currency: 0 for JPY and other unsubdivided
+/// currencies, 3 for the dinars, and 2 for everything else
+pub fn fiat_decimals(currency: &str) -> usize {
+    match currency.to_ascii_uppercase().as_str() {
+        "JPY" | "KRW" | "VND" | "CLP" | "ISK" | "IDR" | "PYG" | "UGX" | "XAF" | "XOF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        _ => 2,
+    }
+}
+
+/// Format a fiat value for display, e.g. `1,234,567.50` for USD or `1,234,568` for JPY
+///
+/// Rounds to the currency's decimal places and groups thousands with commas. The digits
+/// come from the exact decimal expansion of the float, so large values never switch to
+/// exponent notation. The currency symbol is left to the caller.
+pub fn format_fiat(value: f64, currency: &str) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    
+    let digits = format!("{:.*}", fiat_decimals(currency), value.abs());
+    let (whole, fraction) = match digits.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (digits.as_str(), None),
+    };
+    
+    // A negative value that rounds to zero is shown without its sign
+    let mut formatted = String::new();
+    if value < 0.0 && digits.bytes().any(|digit| digit.is_ascii_digit() && digit != b'0') {
+        formatted.push('-');
+    }
+    for (index, digit) in whole.chars().enumerate() {
+        if index > 0 && (whole.len() - index) % 3 == 0 {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        formatted.push('.');
+        formatted.push_str(fraction);
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_to_fiat_rounds_to_cents() {
+        assert_eq!(to_fiat(150_000_000, 20_000.0).unwrap(), 30_000.0);
+        assert_eq!(to_fiat(1, 20_000.0).unwrap(), 0.0);
+        assert_eq!(to_fiat(123_456, 1000.0).unwrap(), 1.23);
+        assert!(to_fiat(1, f64::NAN).is_err());
+    }
+    
+    #[test]
+    fn test_format_fiat() {
+        assert_eq!(format_fiat(1_234_567.5, "USD"), "1,234,567.50");
+        assert_eq!(format_fiat(1_234_567.5, "jpy"), "1,234,568");
+        assert_eq!(format_fiat(999.0, "JPY"), "999");
+        assert_eq!(format_fiat(1.2345, "KWD"), "1.234");
+        assert_eq!(format_fiat(-1500.25, "EUR"), "-1,500.25");
+        assert_eq!(format_fiat(-0.001, "USD"), "0.00");
+        assert_eq!(format_fiat(1e20, "USD"), "100,000,000,000,000,000,000.00");
+    }
+    
+    #[test]
+    fn test_static_provider() {
+        let provider = StaticPriceProvider::new().with_rate("usd", 2.5);
+        assert_eq!(provider.rate("USD").unwrap(), 2.5);
+        assert!(matches!(provider.rate("XYZ"), Err(MobileError::UnsupportedCurrency(_))));
+    }
+    
+    #[test]
+    fn test_http_provider() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/rates/EUR")
+            .with_status(200)
+            .with_body(r#"{"rate": 18500.5}"#)
+            .create();
+        server
+            .mock("GET", "/rates/XYZ")
+            .with_status(404)
+            .create();
+        
+        let provider = HttpPriceProvider::new(&server.url());
+        assert_eq!(provider.rate("eur").unwrap(), 18500.5);
+        assert!(matches!(provider.rate("xyz"), Err(MobileError::UnsupportedCurrency(_))));
+    }
+}
@@ -0,0 +1,320 @@
+//! QR symbols, and multi-part QR frames for moving large payloads between air-gapped devices
+
+use std::collections::BTreeMap;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use qrcode::{Color, EcLevel, QrCode, Version};
+use serde::{Deserialize, Serialize};
+use crate::errors::{MobileError, Result};
+
+/// Prefix of every frame
+pub const FRAME_PREFIX: &str = "silver-tx:";
+
+/// Hex digits of the payload ID carried by every frame
+pub const PAYLOAD_ID_LEN: usize = 8;
+
+/// Most frames a payload may be split into; larger part counts in a frame are rejected
+pub const MAX_QR_PARTS: usize = 1024;
+
+/// Largest QR version
+pub const MAX_VERSION: u8 = 40;
+
+/// How much of a symbol can be damaged and still decode
+///
+/// Higher levels recover more but need a larger symbol for the same payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ErrorCorrection {
+    /// Recovers about 7% damage
+    L,
+    
+    /// Recovers about 15% damage
+    #[default]
+    M,
+    
+    /// Recovers about 25% damage
+    Q,
+    
+    /// Recovers about 30% damage
+    H,
+}
+
+impl ErrorCorrection {
+    fn ec_level(self) -> EcLevel {
+        match self {
+            ErrorCorrection::L => EcLevel::L,
+            ErrorCorrection::M => EcLevel::M,
+            ErrorCorrection::Q => EcLevel::Q,
+            ErrorCorrection::H => EcLevel::H,
+        }
+    }
+}
+
+/// A generated QR symbol, without the quiet zone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrMatrix {
+    version: u8,
+    level: ErrorCorrection,
+    width: usize,
+    modules: Vec<bool>,
+}
+
+impl QrMatrix {
+    /// QR version, 1 to 40
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+    
+    /// Error-correction level the symbol was generated at
+    pub fn level(&self) -> ErrorCorrection {
+        self.level
+    }
+    
+    /// Modules along each side
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    
+    /// Total number of modules
+    pub fn module_count(&self) -> usize {
+        self.modules.len()
+    }
+    
+    /// Whether the module at column `x`, row `y` is dark; false outside the symbol
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.width && self.modules[y * self.width + x]
+    }
+}
+
+/// Generate the smallest QR symbol holding `data` at `level`
+pub fn generate(data: &str, level: ErrorCorrection) -> Result<QrMatrix> {
+    generate_within(data, level, MAX_VERSION)
+}
+
+/// Like [`generate`], but reject payloads needing a version above `max_version`
+///
+/// Useful when the symbol must stay scannable on a small screen.
+pub fn generate_within(data: &str, level: ErrorCorrection, max_version: u8) -> Result<QrMatrix> {
+    let max_version = max_version.clamp(1, MAX_VERSION);
+    let too_large = || MobileError::QrPayloadTooLarge {
+        len: data.len(),
+        level,
+        max_version,
+    };
+    
+    let code = QrCode::with_error_correction_level(data, level.ec_level()).map_err(|_| too_large())?;
+    let version = match code.version() {
+        Version::Normal(version) => version as u8,
+        Version::Micro(_) => return Err(too_large()),
+    };
+    if version > max_version {
+        return Err(too_large());
+    }
+    
+    Ok(QrMatrix {
+        version,
+        level,
+        width: code.width(),
+        modules: code.to_colors().into_iter().map(|color| color == Color::Dark).collect(),
+    })
+}
+
+/// Split `data` into `silver-tx:id:part/total:base64` frames of at most `max_chunk` payload bytes each
+///
+/// Parts are numbered from 1, and `id` is the payload's ID (see [`payload_id`]). Empty data still
+/// yields a single frame. Choose `max_chunk` so there are at most `MAX_QR_PARTS` frames, or
+/// [`decode_chunks`] rejects them.
+pub fn encode_chunks(data: &[u8], max_chunk: usize) -> Vec<String> {
+    let id = payload_id(data);
+    let chunks: Vec<&[u8]> = if data.is_empty() {
+        vec![data]
+    } else {
+        data.chunks(max_chunk.max(1)).collect()
+    };
+    let total = chunks.len();
+    
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, chunk)| format!("{}{}:{}/{}:{}", FRAME_PREFIX, id, index + 1, total, STANDARD.encode(chunk)))
+        .collect()
+}
+
+/// ID of a payload: the first 4 bytes of its BLAKE3 hash, in hex
+pub fn payload_id(data: &[u8]) -> String {
+    hex::encode(&blake3::hash(data).as_bytes()[..PAYLOAD_ID_LEN / 2])
+}
+
+/// Reassemble frames from [`encode_chunks`], in any order
+///
+/// Scanning the same frame twice is harmless. Frames from different payloads are rejected by
+/// their payload ID, and the reassembled data must match that ID.
+pub fn decode_chunks<S: AsRef<str>>(frames: &[S]) -> Result<Vec<u8>> {
+    let mut id = None;
+    let mut total = None;
+    let mut parts: BTreeMap<usize, Vec<u8>> = BTreeMap::new();
+    
+    for frame in frames {
+        let (frame_id, part, frame_total, chunk) = parse_frame(frame.as_ref())?;
+        if *id.get_or_insert_with(|| frame_id.clone()) != frame_id {
+            return Err(MobileError::InvalidQrFrame("frames come from different payloads".to_string()));
+        }
+        if *total.get_or_insert(frame_total) != frame_total {
+            return Err(MobileError::InvalidQrFrame("frames disagree on the part count".to_string()));
+        }
+        if parts.get(&part).is_some_and(|existing| *existing != chunk) {
+            return Err(MobileError::InvalidQrFrame(format!("conflicting copies of part {}", part)));
+        }
+        parts.insert(part, chunk);
+    }
+    
+    let total = total.ok_or_else(|| MobileError::InvalidQrFrame("no frames".to_string()))?;
+    let missing: Vec<usize> = (1..=total).filter(|part| !parts.contains_key(part)).collect();
+    if !missing.is_empty() {
+        return Err(MobileError::MissingQrFrames(missing));
+    }
+    
+    let data: Vec<u8> = parts.into_values().flatten().collect();
+    if id.as_deref() != Some(payload_id(&data).as_str()) {
+        return Err(MobileError::InvalidQrFrame("reassembled payload does not match its ID".to_string()));
+    }
+    Ok(data)
+}
+
+/// Split a frame into its payload ID, part number, part count and decoded payload
+fn parse_frame(frame: &str) -> Result<(String, usize, usize, Vec<u8>)> {
+    let invalid = || MobileError::InvalidQrFrame(frame.chars().take(32).collect());
+    
+    let body = frame.trim().strip_prefix(FRAME_PREFIX).ok_or_else(invalid)?;
+    let (id, body) = body.split_once(':').ok_or_else(invalid)?;
+    if id.len() != PAYLOAD_ID_LEN || !id.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let (sequence, payload) = body.split_once(':').ok_or_else(invalid)?;
+    let (part, total) = sequence.split_once('/').ok_or_else(invalid)?;
+    let part: usize = part.parse().map_err(|_| invalid())?;
+    let total: usize = total.parse().map_err(|_| invalid())?;
+    if part == 0 || part > total || total > MAX_QR_PARTS {
+        return Err(invalid());
+    }
+    
+    let chunk = STANDARD.decode(payload).map_err(|_| invalid())?;
+    Ok((id.to_ascii_lowercase(), part, total, chunk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::seq::SliceRandom;
+    
+    #[test]
+    fn test_chunks_reassemble_out_of_order() {
+        let data: Vec<u8> = (0..2048u32).map(|i| (i * 7 % 256) as u8).collect();
+        
+        let mut frames = encode_chunks(&data, 300);
+        assert_eq!(frames.len(), 7);
+        assert!(frames[0].starts_with(&format!("silver-tx:{}:1/7:", payload_id(&data))));
+        
+        frames.shuffle(&mut rand::thread_rng());
+        frames.push(frames[0].clone());
+        assert_eq!(decode_chunks(&frames).unwrap(), data);
+        
+        assert_eq!(decode_chunks(&encode_chunks(&[], 300)).unwrap(), Vec::<u8>::new());
+    }
+    
+    /// Render `matrix` with a quiet zone and read it back
+    fn scan(matrix: &QrMatrix) -> String {
+        const SCALE: usize = 4;
+        const QUIET: usize = 4;
+        let side = (matrix.width() + 2 * QUIET) * SCALE;
+        let mut image = rqrr::PreparedImage::prepare_from_greyscale(side, side, |x, y| {
+            let (x, y) = (x / SCALE, y / SCALE);
+            let dark = x >= QUIET && y >= QUIET && matrix.is_dark(x - QUIET, y - QUIET);
+            if dark { 0 } else { 255 }
+        });
+        let grids = image.detect_grids();
+        assert_eq!(grids.len(), 1);
+        grids[0].decode().unwrap().1
+    }
+    
+    #[test]
+    fn test_generate_at_low_and_high_correction() {
+        let address = "silver_28038096b936b2336596e0219cff864b6985c3c47c08dbcd";
+        
+        let low = generate(address, ErrorCorrection::L).unwrap();
+        let high = generate(address, ErrorCorrection::H).unwrap();
+        assert_eq!(low.level(), ErrorCorrection::L);
+        assert!(high.version() > low.version());
+        assert!(high.module_count() > low.module_count());
+        assert_eq!(low.width(), 17 + 4 * low.version() as usize);
+        
+        assert_eq!(scan(&low), address);
+        assert_eq!(scan(&high), address);
+        
+        assert_eq!(ErrorCorrection::default(), ErrorCorrection::M);
+        let default = generate(address, ErrorCorrection::default()).unwrap();
+        assert!(default.version() >= low.version() && default.version() <= high.version());
+    }
+    
+    #[test]
+    fn test_generate_rejects_oversized_payload() {
+        let address = "silver_28038096b936b2336596e0219cff864b6985c3c47c08dbcd";
+        let version = generate(address, ErrorCorrection::H).unwrap().version();
+        assert!(generate_within(address, ErrorCorrection::H, version).is_ok());
+        assert!(matches!(
+            generate_within(address, ErrorCorrection::H, version - 1),
+            Err(MobileError::QrPayloadTooLarge { len: 55, level: ErrorCorrection::H, .. })
+        ));
+        
+        let huge = "x".repeat(2000);
+        assert!(generate(&huge, ErrorCorrection::L).is_ok());
+        assert!(matches!(
+            generate(&huge, ErrorCorrection::H),
+            Err(MobileError::QrPayloadTooLarge { max_version: 40, .. })
+        ));
+    }
+    
+    #[test]
+    fn test_missing_and_foreign_frames_fail() {
+        let data = vec![42u8; 2048];
+        let mut frames = encode_chunks(&data, 500);
+        frames.remove(2);
+        assert!(matches!(decode_chunks(&frames), Err(MobileError::MissingQrFrames(missing)) if missing == vec![3]));
+        
+        let mut mixed = encode_chunks(&data, 500);
+        mixed.push(encode_chunks(&data, 1000)[0].clone());
+        assert!(matches!(decode_chunks(&mixed), Err(MobileError::InvalidQrFrame(_))));
+        
+        assert!(matches!(decode_chunks(&["silver-tx:00000000:0/1:AA=="]), Err(MobileError::InvalidQrFrame(_))));
+        assert!(matches!(decode_chunks(&["silver-tx:1/1:AA=="]), Err(MobileError::InvalidQrFrame(_))));
+        
+        // A forged part count would otherwise size the missing-part list
+        let forged = format!("silver-tx:00000000:1/{}:AA==", usize::MAX);
+        assert!(matches!(decode_chunks(&[forged]), Err(MobileError::InvalidQrFrame(_))));
+        let over_cap = format!("silver-tx:00000000:1/{}:AA==", MAX_QR_PARTS + 1);
+        assert!(matches!(decode_chunks(&[over_cap]), Err(MobileError::InvalidQrFrame(_))));
+        assert!(matches!(
+            decode_chunks(&[format!("silver-tx:00000000:1/{}:AA==", MAX_QR_PARTS)]),
+            Err(MobileError::MissingQrFrames(missing)) if missing.len() == MAX_QR_PARTS - 1
+        ));
+        assert!(matches!(decode_chunks::<&str>(&[]), Err(MobileError::InvalidQrFrame(_))));
+    }
+    
+    #[test]
+    fn test_frames_from_same_length_payloads_fail() {
+        let first = vec![1u8; 1500];
+        let second = vec![2u8; 1500];
+        
+        // Same part count, complementary parts: only the payload ID tells them apart
+        let first_frames = encode_chunks(&first, 500);
+        let second_frames = encode_chunks(&second, 500);
+        let mixed = [first_frames[0].clone(), second_frames[1].clone(), first_frames[2].clone()];
+        assert!(matches!(decode_chunks(&mixed), Err(MobileError::InvalidQrFrame(_))));
+        
+        // Frames relabelled with the other payload's ID fail the checksum
+        let relabelled = second_frames[1].replace(&payload_id(&second), &payload_id(&first));
+        let forged = [first_frames[0].clone(), relabelled, first_frames[2].clone()];
+        assert!(matches!(decode_chunks(&forged), Err(MobileError::InvalidQrFrame(_))));
+        
+        assert_eq!(decode_chunks(&first_frames).unwrap(), first);
+    }
+}
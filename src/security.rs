@@ -1,78 +1,637 @@
 //! Security features for mobile wallet
 
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use parking_lot::{Mutex, RwLock};
 use crate::errors::{MobileError, Result};
+use crate::keystore::Keystore;
+use crate::sync::now_secs;
 use serde::{Deserialize, Serialize};
 
+/// Minimum password length
+pub const MIN_PASSWORD_LEN: usize = 8;
+
+/// Minimum estimated password entropy of the default policy, in bits
+pub const MIN_PASSWORD_ENTROPY_BITS: u32 = 40;
+
+/// Minimum PIN length
+pub const MIN_PIN_LEN: usize = 4;
+
+/// Default inactivity timeout before the wallet locks (5 minutes)
+pub const DEFAULT_AUTO_LOCK_SECS: u64 = 300;
+
+/// How long a password or biometric check allows revealing the mnemonic (1 minute)
+pub const REAUTH_WINDOW_SECS: u64 = 60;
+
+/// Rolling window the spend limit applies to (24 hours)
+pub const SPEND_WINDOW_SECS: u64 = 86_400;
+
+/// Password rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PasswordRequirement {
+    /// At least the policy's minimum number of characters
+    MinLength,
+    
+    /// An uppercase letter
+    Uppercase,
+    
+    /// A lowercase letter
+    Lowercase,
+    
+    /// A digit
+    Digit,
+    
+    /// A symbol
+    Symbol,
+    
+    /// At least the policy's minimum estimated entropy
+    Entropy,
+}
+
+/// Rules `validate_password` enforces, configurable per deployment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasswordPolicy {
+    /// Minimum number of characters
+    pub min_length: usize,
+    
+    /// Require an uppercase letter
+    pub require_upper: bool,
+    
+    /// Require a lowercase letter
+    pub require_lower: bool,
+    
+    /// Require a digit
+    pub require_digit: bool,
+    
+    /// Require a symbol
+    pub require_symbol: bool,
+    
+    /// Minimum estimated entropy in bits (0 disables the check)
+    pub min_entropy_bits: u32,
+}
+
+impl PasswordPolicy {
+    /// Is the rule enforced by this policy
+    pub fn requires(&self, requirement: PasswordRequirement) -> bool {
+        match requirement {
+            PasswordRequirement::MinLength => self.min_length > 0,
+            PasswordRequirement::Uppercase => self.require_upper,
+            PasswordRequirement::Lowercase => self.require_lower,
+            PasswordRequirement::Digit => self.require_digit,
+            PasswordRequirement::Symbol => self.require_symbol,
+            PasswordRequirement::Entropy => self.min_entropy_bits > 0,
+        }
+    }
+}
+
+impl Default for PasswordPolicy {
+    /// `MIN_PASSWORD_LEN` characters, mixed case, a digit and `MIN_PASSWORD_ENTROPY_BITS`; symbols recommended
+    fn default() -> Self {
+        Self {
+            min_length: MIN_PASSWORD_LEN,
+            require_upper: true,
+            require_lower: true,
+            require_digit: true,
+            require_symbol: false,
+            min_entropy_bits: MIN_PASSWORD_ENTROPY_BITS,
+        }
+    }
+}
+
+/// Cap on what can be sent without stepping up authentication
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendPolicy {
+    /// Most that can be sent in any `SPEND_WINDOW_SECS`, in base units (0 disables the cap)
+    pub daily_limit: u64,
+}
+
+/// User preferences of a `SecurityManager`, saved with the wallet
+///
+/// Runtime state (lock, activity, the authenticator) is not included, and the PIN hash
+/// is sealed under the wallet password rather than stored as is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecuritySettings {
+    /// Biometric authentication enabled
+    pub biometric_enabled: bool,
+    
+    /// Inactivity timeout in seconds (0 disables auto-lock)
+    #[serde(default = "default_auto_lock_secs")]
+    pub auto_lock_secs: u64,
+    
+    /// Rules new passwords must meet
+    #[serde(default)]
+    pub password_policy: PasswordPolicy,
+    
+    /// Daily spend cap
+    #[serde(default)]
+    pub spend_policy: SpendPolicy,
+    
+    /// PIN hash sealed under the wallet password (None when no PIN is set)
+    #[serde(default)]
+    pub sealed_pin_hash: Option<Vec<u8>>,
+    
+    /// Recent sends as (timestamp, amount), so a restart does not reset the daily limit
+    #[serde(default)]
+    pub recent_spends: Vec<(u64, u64)>,
+}
+
+/// Password strength feedback
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasswordStrength {
+    /// Number of rules met (0 to 6)
+    pub score: u8,
+    
+    /// Rules the password does not meet, required or not
+    pub missing: Vec<PasswordRequirement>,
+    
+    /// Does the password meet every rule the policy requires
+    pub acceptable: bool,
+}
+
+impl SecuritySettings {
+    /// Copy with the PIN hash sealed under `new_password` instead of `old_password`
+    pub fn reseal(&self, old_password: &str, new_password: &str) -> Result<Self> {
+        let sealed_pin_hash = self
+            .sealed_pin_hash
+            .as_ref()
+            .map(|sealed| Keystore::seal(&Keystore::unseal(sealed, old_password)?, new_password))
+            .transpose()?;
+        
+        Ok(Self {
+            sealed_pin_hash,
+            ..self.clone()
+        })
+    }
+}
+
+impl PasswordStrength {
+    /// Does the password meet every required rule
+    pub fn is_acceptable(&self) -> bool {
+        self.acceptable
+    }
+}
+
+/// Evidence from the platform that a biometric check just succeeded (e.g. a signed assertion)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BiometricProof(pub Vec<u8>);
+
+/// Platform biometric check (Face ID, fingerprint, ...)
+pub trait BiometricAuthenticator: Send + Sync {
+    /// Does the proof come from a successful check
+    fn verify(&self, proof: &BiometricProof) -> bool;
+}
+
+/// Installed biometric authenticator, if any
+#[derive(Default)]
+struct AuthenticatorSlot(RwLock<Option<Arc<dyn BiometricAuthenticator>>>);
+
+impl std::fmt::Debug for AuthenticatorSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let installed = self.0.read().is_some();
+        f.debug_tuple("AuthenticatorSlot").field(&installed).finish()
+    }
+}
+
 /// Security manager
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SecurityManager {
     /// Biometric enabled
-    biometric_enabled: bool,
+    biometric_enabled: AtomicBool,
+    
+    /// Platform biometric check
+    #[serde(skip)]
+    authenticator: AuthenticatorSlot,
+    
+    /// Argon2 PHC hash of the PIN (None when no PIN is set)
+    #[serde(default)]
+    pin_hash: Option<String>,
     
-    /// PIN enabled
-    pin_enabled: bool,
+    /// Inactivity timeout in seconds (0 disables auto-lock)
+    #[serde(default = "default_auto_lock_secs")]
+    auto_lock_secs: u64,
+    
+    /// Timestamp of the last recorded activity
+    #[serde(skip)]
+    last_activity: AtomicU64,
+    
+    /// Explicitly locked until the next unlock
+    #[serde(skip)]
+    locked: AtomicBool,
+    
+    /// Timestamp of the last successful password or biometric check (0 when none)
+    #[serde(skip)]
+    last_auth: AtomicU64,
+    
+    /// Rules new passwords must meet
+    #[serde(default)]
+    password_policy: PasswordPolicy,
+    
+    /// Spend policy's daily limit (0 when uncapped)
+    #[serde(default)]
+    daily_spend_limit: AtomicU64,
+    
+    /// Recent sends as (timestamp, amount), oldest first
+    #[serde(skip)]
+    spends: Mutex<Vec<(u64, u64)>>,
 }
 
 impl SecurityManager {
     /// Create a new security manager
     pub fn new() -> Result<Self> {
         Ok(Self {
-            biometric_enabled: false,
-            pin_enabled: false,
+            biometric_enabled: AtomicBool::new(false),
+            authenticator: AuthenticatorSlot::default(),
+            pin_hash: None,
+            auto_lock_secs: DEFAULT_AUTO_LOCK_SECS,
+            last_activity: AtomicU64::new(now_secs()),
+            locked: AtomicBool::new(false),
+            last_auth: AtomicU64::new(0),
+            password_policy: PasswordPolicy::default(),
+            daily_spend_limit: AtomicU64::new(0),
+            spends: Mutex::new(Vec::new()),
         })
     }
     
-    /// Validate password
-    pub fn validate_password(&self, password: &str) -> Result<()> {
-        if password.len() < 8 {
-            return Err(MobileError::InvalidPassword);
-        }
+    /// Snapshot the user's preferences for saving, sealing the PIN hash under `password`
+    pub fn export_settings(&self, password: &str) -> Result<SecuritySettings> {
+        let sealed_pin_hash = self
+            .pin_hash
+            .as_ref()
+            .map(|pin_hash| Keystore::seal(pin_hash.as_bytes(), password))
+            .transpose()?;
         
-        // Check for complexity
-        let has_upper = password.chars().any(|c| c.is_uppercase());
-        let has_lower = password.chars().any(|c| c.is_lowercase());
-        let has_digit = password.chars().any(|c| c.is_numeric());
+        Ok(SecuritySettings {
+            biometric_enabled: self.is_biometric_enabled(),
+            auto_lock_secs: self.auto_lock_secs,
+            password_policy: self.password_policy.clone(),
+            spend_policy: self.spend_policy(),
+            sealed_pin_hash,
+            recent_spends: self.spends.lock().clone(),
+        })
+    }
+    
+    /// Security manager with saved preferences, unsealing the PIN hash with `password`
+    pub fn from_settings(settings: &SecuritySettings, password: &str) -> Result<Self> {
+        let pin_hash = settings
+            .sealed_pin_hash
+            .as_ref()
+            .map(|sealed| {
+                let pin_hash = Keystore::unseal(sealed, password)?;
+                String::from_utf8(pin_hash.to_vec())
+                    .map_err(|_| MobileError::CryptoError("Corrupted PIN hash".to_string()))
+            })
+            .transpose()?;
         
-        if !has_upper || !has_lower || !has_digit {
+        let manager = Self {
+            pin_hash,
+            auto_lock_secs: settings.auto_lock_secs,
+            password_policy: settings.password_policy.clone(),
+            spends: Mutex::new(settings.recent_spends.clone()),
+            ..Self::default()
+        };
+        manager.biometric_enabled.store(settings.biometric_enabled, Ordering::SeqCst);
+        manager.set_spend_policy(settings.spend_policy);
+        Ok(manager)
+    }
+    
+    /// Get the password policy
+    pub fn password_policy(&self) -> &PasswordPolicy {
+        &self.password_policy
+    }
+    
+    /// Set the rules `validate_password` enforces
+    pub fn set_password_policy(&mut self, policy: PasswordPolicy) {
+        self.password_policy = policy;
+    }
+    
+    /// Validate a password against the policy
+    pub fn validate_password(&self, password: &str) -> Result<()> {
+        if !self.password_strength(password).is_acceptable() {
             return Err(MobileError::InvalidPassword);
         }
         
         Ok(())
     }
     
+    /// Rate a password and list the rules it does not meet
+    pub fn password_strength(&self, password: &str) -> PasswordStrength {
+        let policy = &self.password_policy;
+        let checks = [
+            (PasswordRequirement::MinLength, password.chars().count() >= policy.min_length),
+            (PasswordRequirement::Uppercase, password.chars().any(|c| c.is_uppercase())),
+            (PasswordRequirement::Lowercase, password.chars().any(|c| c.is_lowercase())),
+            (PasswordRequirement::Digit, password.chars().any(|c| c.is_numeric())),
+            (PasswordRequirement::Symbol, password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace())),
+            (PasswordRequirement::Entropy, estimate_entropy_bits(password) >= policy.min_entropy_bits as f64),
+        ];
+        
+        let missing: Vec<PasswordRequirement> = checks
+            .iter()
+            .filter(|(_, met)| !met)
+            .map(|(requirement, _)| *requirement)
+            .collect();
+        
+        PasswordStrength {
+            score: (checks.len() - missing.len()) as u8,
+            acceptable: missing.iter().all(|requirement| !policy.requires(*requirement)),
+            missing,
+        }
+    }
+    
     /// Enable biometric authentication
-    pub fn enable_biometric(&mut self) -> Result<()> {
-        self.biometric_enabled = true;
+    pub fn enable_biometric(&self) -> Result<()> {
+        self.biometric_enabled.store(true, Ordering::SeqCst);
         Ok(())
     }
     
     /// Disable biometric authentication
-    pub fn disable_biometric(&mut self) {
-        self.biometric_enabled = false;
+    pub fn disable_biometric(&self) {
+        self.biometric_enabled.store(false, Ordering::SeqCst);
     }
     
     /// Is biometric enabled
     pub fn is_biometric_enabled(&self) -> bool {
-        self.biometric_enabled
+        self.biometric_enabled.load(Ordering::SeqCst)
+    }
+    
+    /// Install the platform biometric check
+    pub fn set_biometric_authenticator(&self, authenticator: Arc<dyn BiometricAuthenticator>) {
+        *self.authenticator.0.write() = Some(authenticator);
+    }
+    
+    /// Installed biometric check, if any
+    pub(crate) fn biometric_authenticator(&self) -> Option<Arc<dyn BiometricAuthenticator>> {
+        self.authenticator.0.read().clone()
+    }
+    
+    /// Check a biometric proof with the installed authenticator
+    ///
+    /// Fails when no authenticator is installed or the proof is missing or rejected.
+    pub fn authenticate_biometric(&self, proof: Option<&BiometricProof>) -> Result<()> {
+        let authenticator = self.authenticator.0.read().clone();
+        match (authenticator, proof) {
+            (Some(authenticator), Some(proof)) if authenticator.verify(proof) => {
+                self.last_auth.store(now_secs(), Ordering::SeqCst);
+                Ok(())
+            }
+            _ => Err(MobileError::BiometricAuthFailed),
+        }
     }
     
-    /// Enable PIN
-    pub fn enable_pin(&mut self) -> Result<()> {
-        self.pin_enabled = true;
+    /// Set the PIN, storing only a salted Argon2 hash
+    pub fn set_pin(&mut self, pin: &str) -> Result<()> {
+        use argon2::{Argon2, PasswordHasher};
+        use argon2::password_hash::SaltString;
+        use rand::Rng;
+        
+        if pin.len() < MIN_PIN_LEN || !pin.bytes().all(|b| b.is_ascii_digit()) || is_trivial_pin(pin) {
+            return Err(MobileError::InvalidPin);
+        }
+        
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill(&mut salt);
+        let salt_string = SaltString::encode_b64(&salt)
+            .map_err(|_| MobileError::CryptoError("Invalid salt".to_string()))?;
+        
+        let pin_hash = Argon2::default()
+            .hash_password(pin.as_bytes(), &salt_string)
+            .map_err(|_| MobileError::CryptoError("PIN hashing failed".to_string()))?;
+        
+        self.pin_hash = Some(pin_hash.to_string());
         Ok(())
     }
     
+    /// Check a PIN against the stored hash (false when no PIN is set)
+    ///
+    /// The PIN is rehashed with the stored salt and costs, and the hashes compared in constant time.
+    pub fn verify_pin(&self, pin: &str) -> Result<bool> {
+        use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordHasher, Version};
+        
+        let Some(stored) = &self.pin_hash else {
+            return Ok(false);
+        };
+        
+        let corrupted = || MobileError::CryptoError("Corrupted PIN hash".to_string());
+        let parsed = PasswordHash::new(stored).map_err(|_| corrupted())?;
+        let (Some(salt), Some(expected)) = (parsed.salt, parsed.hash) else {
+            return Err(corrupted());
+        };
+        let algorithm = Algorithm::try_from(parsed.algorithm).map_err(|_| corrupted())?;
+        let version = parsed
+            .version
+            .map(Version::try_from)
+            .transpose()
+            .map_err(|_| corrupted())?
+            .unwrap_or_default();
+        let params = Params::try_from(&parsed).map_err(|_| corrupted())?;
+        
+        let computed = Argon2::new(algorithm, version, params)
+            .hash_password(pin.as_bytes(), salt)
+            .map_err(|_| MobileError::CryptoError("PIN verification failed".to_string()))?;
+        let computed = computed.hash.ok_or_else(|| MobileError::CryptoError("PIN verification failed".to_string()))?;
+        Ok(constant_time_eq(computed.as_bytes(), expected.as_bytes()))
+    }
+    
     /// Disable PIN
     pub fn disable_pin(&mut self) {
-        self.pin_enabled = false;
+        self.pin_hash = None;
     }
     
     /// Is PIN enabled
     pub fn is_pin_enabled(&self) -> bool {
-        self.pin_enabled
+        self.pin_hash.is_some()
+    }
+    
+    /// Get auto-lock timeout in seconds
+    pub fn auto_lock_secs(&self) -> u64 {
+        self.auto_lock_secs
+    }
+    
+    /// Set auto-lock timeout in seconds (0 disables auto-lock)
+    pub fn set_auto_lock_secs(&mut self, secs: u64) {
+        self.auto_lock_secs = secs;
+    }
+    
+    /// Record user activity, postponing the auto-lock
+    pub fn record_activity(&self) {
+        self.last_activity.store(now_secs(), Ordering::SeqCst);
+    }
+    
+    /// Is the wallet locked at time `now` (seconds since the Unix epoch)
+    pub fn is_locked(&self, now: u64) -> bool {
+        if self.locked.load(Ordering::SeqCst) {
+            return true;
+        }
+        
+        self.auto_lock_secs > 0
+            && now.saturating_sub(self.last_activity.load(Ordering::SeqCst)) >= self.auto_lock_secs
+    }
+    
+    /// Lock immediately
+    pub fn lock(&self) {
+        self.locked.store(true, Ordering::SeqCst);
+    }
+    
+    /// Clear the lock once the caller has re-authenticated
+    pub(crate) fn unlock(&self) {
+        self.locked.store(false, Ordering::SeqCst);
+        self.record_activity();
+        self.last_auth.store(now_secs(), Ordering::SeqCst);
+    }
+    
+    /// Did a password or biometric check succeed within `REAUTH_WINDOW_SECS` before `now`
+    pub fn is_recently_authenticated(&self, now: u64) -> bool {
+        let last_auth = self.last_auth.load(Ordering::SeqCst);
+        last_auth > 0 && now.saturating_sub(last_auth) < REAUTH_WINDOW_SECS
+    }
+    
+    /// Get the spend policy
+    pub fn spend_policy(&self) -> SpendPolicy {
+        SpendPolicy {
+            daily_limit: self.daily_spend_limit.load(Ordering::SeqCst),
+        }
+    }
+    
+    /// Set the cap `record_spend` enforces
+    pub fn set_spend_policy(&self, policy: SpendPolicy) {
+        self.daily_spend_limit.store(policy.daily_limit, Ordering::SeqCst);
+    }
+    
+    /// Total sent in the `SPEND_WINDOW_SECS` before `now`
+    pub fn spent_in_window(&self, now: u64) -> u64 {
+        let mut spends = self.spends.lock();
+        prune_spends(&mut spends, now);
+        spends.iter().map(|(_, amount)| amount).sum()
+    }
+    
+    /// Fail with `SpendLimitExceeded` if a send of `amount` at `now` would go over the daily limit
+    ///
+    /// Nothing is recorded; a `stepped_up` send always passes.
+    pub fn check_spend(&self, amount: u64, now: u64, stepped_up: bool) -> Result<()> {
+        let limit = self.daily_spend_limit.load(Ordering::SeqCst);
+        if limit > 0 && !stepped_up && self.spent_in_window(now).saturating_add(amount) > limit {
+            return Err(MobileError::SpendLimitExceeded);
+        }
+        Ok(())
+    }
+    
+    /// Count a send of `amount` at `now` against the daily limit
+    ///
+    /// Fails with `SpendLimitExceeded`, recording nothing, when the send would take the window
+    /// over the limit. A `stepped_up` send has passed extra authentication and is recorded anyway.
+    pub fn record_spend(&self, amount: u64, now: u64, stepped_up: bool) -> Result<()> {
+        let limit = self.daily_spend_limit.load(Ordering::SeqCst);
+        let mut spends = self.spends.lock();
+        prune_spends(&mut spends, now);
+        
+        let spent: u64 = spends.iter().map(|(_, amount)| amount).sum();
+        if limit > 0 && !stepped_up && spent.saturating_add(amount) > limit {
+            return Err(MobileError::SpendLimitExceeded);
+        }
+        spends.push((now, amount));
+        Ok(())
+    }
+    
+    /// Fail unless authenticated recently at `now`
+    ///
+    /// The error asks for a biometric check when biometrics are enabled, otherwise a password unlock.
+    pub fn require_recent_auth(&self, now: u64) -> Result<()> {
+        if self.is_recently_authenticated(now) {
+            return Ok(());
+        }
+        
+        if self.is_biometric_enabled() {
+            Err(MobileError::BiometricAuthFailed)
+        } else {
+            Err(MobileError::WalletLocked)
+        }
     }
 }
 
+impl Clone for SecurityManager {
+    fn clone(&self) -> Self {
+        Self {
+            biometric_enabled: AtomicBool::new(self.biometric_enabled.load(Ordering::SeqCst)),
+            authenticator: AuthenticatorSlot(RwLock::new(self.authenticator.0.read().clone())),
+            pin_hash: self.pin_hash.clone(),
+            auto_lock_secs: self.auto_lock_secs,
+            last_activity: AtomicU64::new(self.last_activity.load(Ordering::SeqCst)),
+            locked: AtomicBool::new(self.locked.load(Ordering::SeqCst)),
+            last_auth: AtomicU64::new(self.last_auth.load(Ordering::SeqCst)),
+            password_policy: self.password_policy.clone(),
+            daily_spend_limit: AtomicU64::new(self.daily_spend_limit.load(Ordering::SeqCst)),
+            spends: Mutex::new(self.spends.lock().clone()),
+        }
+    }
+}
+
+impl Default for SecurityManager {
+    fn default() -> Self {
+        Self {
+            biometric_enabled: AtomicBool::new(false),
+            authenticator: AuthenticatorSlot::default(),
+            pin_hash: None,
+            auto_lock_secs: DEFAULT_AUTO_LOCK_SECS,
+            last_activity: AtomicU64::new(now_secs()),
+            locked: AtomicBool::new(false),
+            last_auth: AtomicU64::new(0),
+            password_policy: PasswordPolicy::default(),
+            daily_spend_limit: AtomicU64::new(0),
+            spends: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+/// Rough entropy of a password: its length times log2 of the character classes it draws from
+fn estimate_entropy_bits(password: &str) -> f64 {
+    let classes: [(fn(char) -> bool, u32); 4] = [
+        (|c| c.is_lowercase(), 26),
+        (|c| c.is_uppercase(), 26),
+        (|c| c.is_numeric(), 10),
+        (|c| !c.is_alphanumeric(), 33),
+    ];
+    let pool: u32 = classes
+        .iter()
+        .filter(|(in_class, _)| password.chars().any(in_class))
+        .map(|(_, size)| size)
+        .sum();
+    if pool == 0 {
+        return 0.0;
+    }
+    
+    password.chars().count() as f64 * f64::from(pool).log2()
+}
+
+/// Compare secrets (hashes, derived keys, check values) without leaking where they differ
+///
+/// Unequal lengths compare unequal; the lengths themselves are not treated as secret.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    use subtle::ConstantTimeEq;
+    
+    a.ct_eq(b).into()
+}
+
+/// Drop sends that have left the window ending at `now`
+fn prune_spends(spends: &mut Vec<(u64, u64)>, now: u64) {
+    spends.retain(|(timestamp, _)| now.saturating_sub(*timestamp) < SPEND_WINDOW_SECS);
+}
+
+/// Is a PIN a single repeated digit or an ascending/descending run
+fn is_trivial_pin(pin: &str) -> bool {
+    let digits = pin.as_bytes();
+    let steps: Vec<i16> = digits
+        .windows(2)
+        .map(|pair| pair[1] as i16 - pair[0] as i16)
+        .collect();
+    
+    [0, 1, -1].iter().any(|&step| steps.iter().all(|&s| s == step))
+}
+
+/// Serde default for settings saved before auto-lock existed
+fn default_auto_lock_secs() -> u64 {
+    DEFAULT_AUTO_LOCK_SECS
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,4 +652,232 @@ mod tests {
         // Invalid password (too short)
         assert!(manager.validate_password("short").is_err());
     }
+    
+    #[test]
+    fn test_password_strength_feedback() {
+        let manager = SecurityManager::new().unwrap();
+        
+        let weak = manager.password_strength("abc");
+        assert_eq!(weak.score, 1);
+        assert_eq!(
+            weak.missing,
+            vec![
+                PasswordRequirement::MinLength,
+                PasswordRequirement::Uppercase,
+                PasswordRequirement::Digit,
+                PasswordRequirement::Symbol,
+                PasswordRequirement::Entropy,
+            ]
+        );
+        assert!(!weak.is_acceptable());
+        
+        // A missing symbol lowers the score but is not required
+        let good = manager.password_strength("ValidPass123");
+        assert_eq!(good.score, 5);
+        assert_eq!(good.missing, vec![PasswordRequirement::Symbol]);
+        assert!(good.is_acceptable());
+        
+        let strong = manager.password_strength("ValidPass123!");
+        assert_eq!(strong.score, 6);
+        assert!(strong.missing.is_empty());
+    }
+    
+    #[test]
+    fn test_lenient_password_policy() {
+        let mut manager = SecurityManager::new().unwrap();
+        assert!(manager.validate_password("correct horse battery").is_err());
+        
+        manager.set_password_policy(PasswordPolicy {
+            min_length: 12,
+            require_upper: false,
+            require_lower: true,
+            require_digit: false,
+            require_symbol: false,
+            min_entropy_bits: 60,
+        });
+        assert!(manager.validate_password("correct horse battery").is_ok());
+        assert!(manager.validate_password("horse").is_err());
+    }
+    
+    #[test]
+    fn test_strict_password_policy_requires_symbol() {
+        let mut manager = SecurityManager::new().unwrap();
+        manager.set_password_policy(PasswordPolicy {
+            require_symbol: true,
+            ..PasswordPolicy::default()
+        });
+        
+        assert!(matches!(manager.validate_password("ValidPass123"), Err(MobileError::InvalidPassword)));
+        assert!(manager.validate_password("ValidPass123!").is_ok());
+        
+        // Mixed classes alone don't make a short password strong enough
+        manager.set_password_policy(PasswordPolicy {
+            min_length: 1,
+            ..PasswordPolicy::default()
+        });
+        assert!(manager.validate_password("Ab1").is_err());
+    }
+    
+    #[test]
+    fn test_pin_hashing_and_verification() {
+        let mut manager = SecurityManager::new().unwrap();
+        assert!(!manager.verify_pin("482916").unwrap());
+        
+        manager.set_pin("482916").unwrap();
+        assert!(manager.is_pin_enabled());
+        
+        let stored = manager.pin_hash.as_deref().unwrap();
+        assert!(!stored.contains("482916"));
+        
+        assert!(manager.verify_pin("482916").unwrap());
+        assert!(!manager.verify_pin("482917").unwrap());
+        
+        manager.disable_pin();
+        assert!(!manager.verify_pin("482916").unwrap());
+    }
+    
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret-hash", b"secret-hash"));
+        assert!(constant_time_eq(b"", b""));
+        assert!(!constant_time_eq(b"secret-hash", b"secret-hasH"));
+        assert!(!constant_time_eq(b"secret-hash", b"secret"));
+        assert!(!constant_time_eq(&[0u8; 32], &[1u8; 32]));
+    }
+    
+    #[test]
+    fn test_rejects_weak_pins() {
+        let mut manager = SecurityManager::new().unwrap();
+        for pin in ["0000", "1234", "98765", "123", "12a4"] {
+            assert!(matches!(manager.set_pin(pin), Err(MobileError::InvalidPin)));
+        }
+        assert!(!manager.is_pin_enabled());
+    }
+    
+    #[test]
+    fn test_auto_lock_after_inactivity() {
+        let manager = SecurityManager::new().unwrap();
+        manager.record_activity();
+        let now = now_secs();
+        
+        assert!(!manager.is_locked(now));
+        assert!(!manager.is_locked(now + DEFAULT_AUTO_LOCK_SECS - 1));
+        assert!(manager.is_locked(now + DEFAULT_AUTO_LOCK_SECS));
+        
+        manager.unlock();
+        assert!(!manager.is_locked(now_secs()));
+    }
+    
+    /// Accepts exactly one proof
+    struct MockAuthenticator(Vec<u8>);
+    
+    impl BiometricAuthenticator for MockAuthenticator {
+        fn verify(&self, proof: &BiometricProof) -> bool {
+            proof.0 == self.0
+        }
+    }
+    
+    #[test]
+    fn test_authenticate_biometric() {
+        let manager = SecurityManager::new().unwrap();
+        let proof = BiometricProof(b"face-ok".to_vec());
+        assert!(matches!(manager.authenticate_biometric(Some(&proof)), Err(MobileError::BiometricAuthFailed)));
+        
+        manager.set_biometric_authenticator(Arc::new(MockAuthenticator(b"face-ok".to_vec())));
+        assert!(manager.authenticate_biometric(Some(&proof)).is_ok());
+        assert!(matches!(
+            manager.authenticate_biometric(Some(&BiometricProof(b"face-bad".to_vec()))),
+            Err(MobileError::BiometricAuthFailed)
+        ));
+        assert!(matches!(manager.authenticate_biometric(None), Err(MobileError::BiometricAuthFailed)));
+    }
+    
+    #[test]
+    fn test_reauth_window() {
+        let manager = SecurityManager::new().unwrap();
+        assert!(matches!(manager.require_recent_auth(now_secs()), Err(MobileError::WalletLocked)));
+        
+        manager.unlock();
+        let now = now_secs();
+        assert!(manager.require_recent_auth(now).is_ok());
+        assert!(manager.require_recent_auth(now + REAUTH_WINDOW_SECS - 1).is_ok());
+        assert!(matches!(manager.require_recent_auth(now + REAUTH_WINDOW_SECS), Err(MobileError::WalletLocked)));
+        
+        // Activity alone doesn't extend the window
+        manager.record_activity();
+        assert!(!manager.is_recently_authenticated(now + REAUTH_WINDOW_SECS));
+        
+        // With biometrics on, a fresh accepted proof renews it
+        let proof = BiometricProof(b"face-ok".to_vec());
+        manager.set_biometric_authenticator(Arc::new(MockAuthenticator(b"face-ok".to_vec())));
+        manager.enable_biometric().unwrap();
+        manager.last_auth.store(now - REAUTH_WINDOW_SECS, Ordering::SeqCst);
+        assert!(matches!(manager.require_recent_auth(now), Err(MobileError::BiometricAuthFailed)));
+        manager.authenticate_biometric(Some(&proof)).unwrap();
+        assert!(manager.require_recent_auth(now_secs()).is_ok());
+    }
+    
+    #[test]
+    fn test_daily_spend_limit() {
+        let manager = SecurityManager::new().unwrap();
+        let start = 1_700_000_000;
+        
+        // Uncapped by default
+        manager.record_spend(1_000_000, start, false).unwrap();
+        manager.spends.lock().clear();
+        
+        manager.set_spend_policy(SpendPolicy { daily_limit: 10_000 });
+        manager.record_spend(6_000, start, false).unwrap();
+        manager.record_spend(4_000, start + 3_600, false).unwrap();
+        assert_eq!(manager.spent_in_window(start + 3_600), 10_000);
+        
+        // Over the cap fails and isn't counted, unless authentication was stepped up
+        assert!(matches!(manager.record_spend(1, start + 7_200, false), Err(MobileError::SpendLimitExceeded)));
+        assert_eq!(manager.spent_in_window(start + 7_200), 10_000);
+        manager.record_spend(500, start + 7_200, true).unwrap();
+        assert_eq!(manager.spent_in_window(start + 7_200), 10_500);
+        
+        // The first send leaves the window 24 hours on
+        assert!(manager.record_spend(5_000, start + SPEND_WINDOW_SECS - 1, false).is_err());
+        manager.record_spend(5_000, start + SPEND_WINDOW_SECS, false).unwrap();
+        assert_eq!(manager.spent_in_window(start + SPEND_WINDOW_SECS), 9_500);
+    }
+    
+    #[test]
+    fn test_check_spend_records_nothing() {
+        let manager = SecurityManager::new().unwrap();
+        let start = 1_700_000_000;
+        manager.set_spend_policy(SpendPolicy { daily_limit: 10_000 });
+        
+        manager.check_spend(10_000, start, false).unwrap();
+        assert_eq!(manager.spent_in_window(start), 0);
+        
+        manager.record_spend(6_000, start, false).unwrap();
+        assert!(matches!(manager.check_spend(5_000, start, false), Err(MobileError::SpendLimitExceeded)));
+        manager.check_spend(5_000, start, true).unwrap();
+        assert_eq!(manager.spent_in_window(start), 6_000);
+    }
+    
+    #[test]
+    fn test_recent_spends_survive_settings_round_trip() {
+        let manager = SecurityManager::new().unwrap();
+        let now = now_secs();
+        manager.set_spend_policy(SpendPolicy { daily_limit: 10_000 });
+        manager.record_spend(7_000, now, false).unwrap();
+        
+        let settings = manager.export_settings("ValidPass123").unwrap();
+        let restored = SecurityManager::from_settings(&settings, "ValidPass123").unwrap();
+        assert_eq!(restored.spent_in_window(now), 7_000);
+        assert!(matches!(restored.check_spend(4_000, now, false), Err(MobileError::SpendLimitExceeded)));
+    }
+    
+    #[test]
+    fn test_auto_lock_disabled() {
+        let mut manager = SecurityManager::new().unwrap();
+        manager.set_auto_lock_secs(0);
+        assert!(!manager.is_locked(now_secs() + 1_000_000));
+        
+        manager.lock();
+        assert!(manager.is_locked(now_secs()));
+    }
 }
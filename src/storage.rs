@@ -0,0 +1,151 @@
+//! Encodings for wallet files
+
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use crate::errors::{MobileError, Result};
+
+/// Header marking a bincode payload; anything else is read as JSON
+const BINCODE_MAGIC: &[u8] = b"SLVB\x01";
+
+/// Encoding of a saved wallet, before encryption
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageFormat {
+    /// JSON, readable once decrypted
+    #[default]
+    Json,
+    
+    /// Compact binary encoding for on-device storage
+    Bincode,
+}
+
+/// Value tree stored in bincode files
+///
+/// Bincode isn't self-describing, so the wallet goes through its JSON value first;
+/// this keeps schema migrations and serde defaults working for both formats.
+#[derive(Serialize, Deserialize)]
+enum Node {
+    Null,
+    Bool(bool),
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+    String(String),
+    /// Arrays of small integers, which is how byte fields serialize
+    Bytes(Vec<u8>),
+    Array(Vec<Node>),
+    Object(Vec<(String, Node)>),
+}
+
+impl From<Value> for Node {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => Node::Null,
+            Value::Bool(flag) => Node::Bool(flag),
+            Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+                (Some(unsigned), _) => Node::Unsigned(unsigned),
+                (None, Some(signed)) => Node::Signed(signed),
+                _ => Node::Float(number.as_f64().unwrap_or_default()),
+            },
+            Value::String(string) => Node::String(string),
+            Value::Array(items) => match as_bytes(&items) {
+                Some(bytes) => Node::Bytes(bytes),
+                None => Node::Array(items.into_iter().map(Node::from).collect()),
+            },
+            Value::Object(fields) => Node::Object(fields.into_iter().map(|(key, value)| (key, value.into())).collect()),
+        }
+    }
+}
+
+impl From<Node> for Value {
+    fn from(node: Node) -> Self {
+        match node {
+            Node::Null => Value::Null,
+            Node::Bool(flag) => Value::Bool(flag),
+            Node::Unsigned(unsigned) => Value::from(unsigned),
+            Node::Signed(signed) => Value::from(signed),
+            Node::Float(float) => Number::from_f64(float).map_or(Value::Null, Value::Number),
+            Node::String(string) => Value::String(string),
+            Node::Bytes(bytes) => Value::Array(bytes.into_iter().map(Value::from).collect()),
+            Node::Array(items) => Value::Array(items.into_iter().map(Value::from).collect()),
+            Node::Object(fields) => {
+                Value::Object(fields.into_iter().map(|(key, node)| (key, node.into())).collect::<Map<_, _>>())
+            }
+        }
+    }
+}
+
+/// The items as bytes, if the array is non-empty and every item fits in a byte
+fn as_bytes(items: &[Value]) -> Option<Vec<u8>> {
+    if items.is_empty() {
+        return None;
+    }
+    
+    items
+        .iter()
+        .map(|item| item.as_u64().and_then(|n| u8::try_from(n).ok()))
+        .collect()
+}
+
+/// Bincode settings: variable-length integers keep the tags and lengths short
+fn bincode_options() -> impl Options {
+    bincode::DefaultOptions::new()
+}
+
+/// Encode a wallet value in `format`
+pub(crate) fn encode(value: Value, format: StorageFormat) -> Result<Vec<u8>> {
+    match format {
+        StorageFormat::Json => serde_json::to_vec(&value)
+            .map_err(|e| MobileError::SerializationError(e.to_string())),
+        StorageFormat::Bincode => {
+            let mut encoded = BINCODE_MAGIC.to_vec();
+            let body = bincode_options()
+                .serialize(&Node::from(value))
+                .map_err(|e| MobileError::SerializationError(e.to_string()))?;
+            encoded.extend_from_slice(&body);
+            Ok(encoded)
+        }
+    }
+}
+
+/// Decode a wallet value, detecting its format from the header
+pub(crate) fn decode(bytes: &[u8]) -> Result<(Value, StorageFormat)> {
+    match bytes.strip_prefix(BINCODE_MAGIC) {
+        Some(body) => {
+            let node: Node = bincode_options()
+                .deserialize(body)
+                .map_err(|e| MobileError::SerializationError(e.to_string()))?;
+            Ok((node.into(), StorageFormat::Bincode))
+        }
+        None => {
+            let value = serde_json::from_slice(bytes)
+                .map_err(|e| MobileError::SerializationError(e.to_string()))?;
+            Ok((value, StorageFormat::Json))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_round_trip_both_formats() {
+        let value = serde_json::json!({
+            "name": "Main Wallet",
+            "balance": 5000,
+            "offset": -3,
+            "rate": 1.5,
+            "key": [0, 17, 255],
+            "wide": [1, 300],
+            "empty": [],
+            "memo": null,
+            "nested": {"flag": true},
+        });
+        
+        for format in [StorageFormat::Json, StorageFormat::Bincode] {
+            let encoded = encode(value.clone(), format).unwrap();
+            assert_eq!(decode(&encoded).unwrap(), (value.clone(), format));
+        }
+    }
+}
@@ -1,42 +1,1895 @@
 //! Wallet synchronization
 
-use crate::errors::Result;
-use crate::wallet::MobileWallet;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use crate::clock::{Clock, SystemClock};
+use crate::errors::{MobileError, NetworkErrorKind, Result};
+use crate::payment::percent_encode;
+use crate::transaction::{MobileTransaction, TransactionStatus};
+use crate::utxo::Utxo;
+use crate::wallet::MobileWallet;
+
+/// Default node endpoint
+pub const DEFAULT_NODE_URL: &str = "http://127.0.0.1:8545";
+
+/// Default timeout applied to every node request
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Header carrying the API key on every node request
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Header carrying a broadcast's idempotency key (the transaction ID)
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Code a node puts in a 409 body when it already has the submitted transaction
+pub const ALREADY_KNOWN_CODE: &str = "already_known";
+
+/// Default number of transactions requested per history page
+pub const DEFAULT_PAGE_SIZE: u32 = 100;
+
+/// Node endpoint settings
+#[derive(Clone)]
+pub struct NodeConfig {
+    /// Node URL (https, except for a node on this device, in release builds)
+    pub base_url: String,
+    
+    /// API key sent in `API_KEY_HEADER`, for rate-limited providers
+    pub api_key: Option<String>,
+    
+    /// Per-request timeout
+    pub timeout: Duration,
+}
+
+impl NodeConfig {
+    /// Settings for a node, without an API key and with the default timeout
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.to_string(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_NODE_URL.to_string(),
+            api_key: None,
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+}
+
+impl std::fmt::Debug for NodeConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("NodeConfig")
+            .field("base_url", &self.base_url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
 
 /// Sync manager
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct SyncManager {
-    /// Last sync timestamp
-    last_sync: u64,
+    /// Node URL
+    node_url: String,
+    
+    /// Per-request timeout
+    #[serde(default = "default_request_timeout")]
+    timeout: Duration,
+    
+    /// API key sent with every request (never serialized)
+    #[serde(skip)]
+    api_key: Option<String>,
+    
+    /// Transactions requested per history page
+    #[serde(default = "default_page_size")]
+    page_size: u32,
+    
+    /// Cursor of the next history page per address, left by a sync that failed part-way
+    #[serde(skip)]
+    cursors: Mutex<HashMap<String, String>>,
+    
+    /// Last successful sync timestamp (seconds since the Unix epoch)
+    last_sync: AtomicU64,
+}
+
+/// Flag for aborting a sync from another thread; clones share the flag
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Create a token that hasn't been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Ask every sync holding this token to stop before its next request
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+    
+    /// Has `cancel` been called
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+    
+    /// Fail with `SyncCancelled` once cancelled
+    fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(MobileError::SyncCancelled);
+        }
+        Ok(())
+    }
+}
+
+/// State fetched from the node, applied to a wallet in a single step
+#[derive(Debug, Clone)]
+pub struct SyncUpdate {
+    /// Per-account updates
+    pub accounts: Vec<AccountUpdate>,
+}
+
+/// State fetched from the node for a single account
+#[derive(Debug, Clone)]
+pub struct AccountUpdate {
+    /// Account address
+    pub address: String,
+    
+    /// Balance reported by the node
+    pub balance: u64,
+    
+    /// Unspent outputs reported by the node
+    pub utxos: Vec<Utxo>,
+    
+    /// Transactions reported by the node
+    pub transactions: Vec<MobileTransaction>,
+}
+
+/// Node response for a balance query
+#[derive(Deserialize)]
+struct BalanceResponse {
+    balance: u64,
+    
+    #[serde(default)]
+    utxos: Vec<Utxo>,
+}
+
+/// Node response for a fee rate query
+#[derive(Deserialize)]
+struct FeeRateResponse {
+    fee_rate: u64,
+}
+
+/// Node response for a page of transaction history
+#[derive(Deserialize)]
+struct TransactionsResponse {
+    transactions: Vec<MobileTransaction>,
+    
+    /// Cursor of the next page (absent on the last page and from nodes that don't page)
+    #[serde(default)]
+    next_cursor: Option<String>,
+}
+
+/// Node response for a submitted transaction
+#[derive(Deserialize)]
+struct BroadcastResponse {
+    txid: String,
+}
+
+/// Node response to a conflicting broadcast (HTTP 409)
+#[derive(Deserialize)]
+struct ConflictResponse {
+    #[serde(default)]
+    txid: Option<String>,
+    #[serde(default)]
+    code: Option<String>,
+}
+
+/// Node response for a transaction status query
+#[derive(Deserialize)]
+struct StatusResponse {
+    status: TransactionStatus,
+    
+    #[serde(default)]
+    confirmations: Option<u32>,
+}
+
+/// Per-account outcome of `SyncManager::sync_wallet`
+#[derive(Debug, Clone)]
+pub struct WalletSyncReport {
+    /// Each account's address and the error that stopped its sync, if any
+    pub accounts: Vec<(String, Option<MobileError>)>,
+}
+
+impl WalletSyncReport {
+    /// Did every account sync
+    pub fn is_complete(&self) -> bool {
+        self.accounts.iter().all(|(_, error)| error.is_none())
+    }
+    
+    /// Accounts that failed, with their errors
+    pub fn failures(&self) -> impl Iterator<Item = (&str, &MobileError)> {
+        self.accounts
+            .iter()
+            .filter_map(|(address, error)| error.as_ref().map(|error| (address.as_str(), error)))
+    }
+}
+
+/// Status and confirmation depth of a transaction as seen by the node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionState {
+    /// Status
+    pub status: TransactionStatus,
+    
+    /// Confirmation depth
+    pub confirmations: u32,
+}
+
+impl TransactionState {
+    /// Derive the state from a node response
+    ///
+    /// Nodes that report a depth decide confirmation by it; older nodes that
+    /// only report `Confirmed` count as one confirmation.
+    fn from_response(response: StatusResponse) -> Self {
+        match (response.status, response.confirmations) {
+            (TransactionStatus::Failed, _) => Self {
+                status: TransactionStatus::Failed,
+                confirmations: 0,
+            },
+            (_, Some(0)) => Self {
+                status: TransactionStatus::Pending,
+                confirmations: 0,
+            },
+            (_, Some(confirmations)) => Self {
+                status: TransactionStatus::Confirmed,
+                confirmations,
+            },
+            (TransactionStatus::Confirmed, None) => Self {
+                status: TransactionStatus::Confirmed,
+                confirmations: 1,
+            },
+            (status, None) => Self {
+                status,
+                confirmations: 0,
+            },
+        }
+    }
 }
 
 impl SyncManager {
-    /// Create a new sync manager
+    /// Create a new sync manager using the default node
     pub fn new() -> Result<Self> {
-        Ok(Self { last_sync: 0 })
+        Self::with_node_url(DEFAULT_NODE_URL)
     }
     
-    /// Sync wallet
-    pub fn sync(&self, _wallet: &MobileWallet) -> Result<()> {
-        // Sync wallet with blockchain
-        // This would connect to a node and fetch balance/transactions
+    /// Create a new sync manager for a specific node
+    pub fn with_node_url(node_url: &str) -> Result<Self> {
+        Self::with_config(NodeConfig::new(node_url))
+    }
+    
+    /// Create a new sync manager from node settings
+    pub fn with_config(config: NodeConfig) -> Result<Self> {
+        check_node_url(&config.base_url, !cfg!(debug_assertions))?;
+        
+        Ok(Self {
+            node_url: config.base_url.trim_end_matches('/').to_string(),
+            timeout: config.timeout,
+            api_key: config.api_key,
+            page_size: DEFAULT_PAGE_SIZE,
+            cursors: Mutex::new(HashMap::new()),
+            last_sync: AtomicU64::new(0),
+        })
+    }
+    
+    /// Use a different per-request timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    
+    /// Request history in pages of `page_size` transactions (at least 1)
+    pub fn with_page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+    
+    /// Get node URL
+    pub fn node_url(&self) -> &str {
+        &self.node_url
+    }
+    
+    /// Sync wallet with the node, recording history one page at a time
+    ///
+    /// Balances are only updated once an account's history is complete. If a page
+    /// fails, the pages before it stay recorded and the next sync resumes from it.
+    pub fn sync(&self, wallet: &mut MobileWallet) -> Result<()> {
+        self.sync_since(wallet, 0)
+    }
+    
+    /// Sync wallet, only requesting transactions newer than `from_timestamp`
+    pub fn sync_since(&self, wallet: &mut MobileWallet, from_timestamp: u64) -> Result<()> {
+        self.sync_since_cancellable(wallet, from_timestamp, &CancelToken::new())
+    }
+    
+    /// Sync as `sync` does, stopping with `SyncCancelled` between requests once `cancel` is set
+    ///
+    /// Pages already fetched stay recorded and the next sync resumes after them, as after a failure.
+    pub fn sync_cancellable(&self, wallet: &mut MobileWallet, cancel: &CancelToken) -> Result<()> {
+        self.sync_since_cancellable(wallet, 0, cancel)
+    }
+    
+    /// Sync transactions newer than `from_timestamp`, stopping once `cancel` is set
+    pub fn sync_since_cancellable(
+        &self,
+        wallet: &mut MobileWallet,
+        from_timestamp: u64,
+        cancel: &CancelToken,
+    ) -> Result<()> {
+        let addresses: Vec<String> = wallet
+            .accounts()
+            .iter()
+            .map(|account| account.address().to_string())
+            .collect();
+        let _span = log_span!("sync", addresses = addresses.len(), from_timestamp);
+        
+        let client = self.client()?;
+        let balances = addresses
+            .iter()
+            .map(|address| {
+                cancel.check()?;
+                self.get::<BalanceResponse>(&client, &balance_path(address))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        
+        for (address, balance) in addresses.into_iter().zip(balances) {
+            self.sync_history(&client, wallet, &address, from_timestamp, cancel)?;
+            let update = AccountUpdate {
+                address,
+                balance: balance.balance,
+                utxos: balance.utxos,
+                transactions: Vec::new(),
+            };
+            update.apply(wallet);
+        }
+        
+        self.last_sync.store(now_secs(), Ordering::SeqCst);
+        Ok(())
+    }
+    
+    /// Sync every account of the wallet, carrying on past accounts that fail
+    ///
+    /// Each account is updated as soon as its own balance and history are in, so one
+    /// unreachable account doesn't hold back the others. Check the report for failures.
+    pub fn sync_wallet(&self, wallet: &mut MobileWallet) -> Result<WalletSyncReport> {
+        self.sync_wallet_cancellable(wallet, &CancelToken::new())
+    }
+    
+    /// Sync every account as `sync_wallet` does, stopping with `SyncCancelled` once `cancel` is set
+    ///
+    /// Accounts finished before the cancellation keep their update.
+    pub fn sync_wallet_cancellable(&self, wallet: &mut MobileWallet, cancel: &CancelToken) -> Result<WalletSyncReport> {
+        let addresses: Vec<String> = wallet
+            .accounts()
+            .iter()
+            .map(|account| account.address().to_string())
+            .collect();
+        let _span = log_span!("sync_wallet", addresses = addresses.len());
+        
+        let client = self.client()?;
+        let mut accounts: Vec<(String, Option<MobileError>)> = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            cancel.check()?;
+            match self.sync_account(&client, wallet, &address, cancel) {
+                Err(MobileError::SyncCancelled) => return Err(MobileError::SyncCancelled),
+                result => accounts.push((address, result.err())),
+            }
+        }
+        
+        let report = WalletSyncReport { accounts };
+        if report.is_complete() {
+            self.last_sync.store(now_secs(), Ordering::SeqCst);
+        }
+        Ok(report)
+    }
+    
+    /// Sync one account's balance and full history
+    fn sync_account(
+        &self,
+        client: &reqwest::blocking::Client,
+        wallet: &mut MobileWallet,
+        address: &str,
+        cancel: &CancelToken,
+    ) -> Result<()> {
+        let balance: BalanceResponse = self.get(client, &balance_path(address))?;
+        self.sync_history(client, wallet, address, 0, cancel)?;
+        
+        let update = AccountUpdate {
+            address: address.to_string(),
+            balance: balance.balance,
+            utxos: balance.utxos,
+            transactions: Vec::new(),
+        };
+        update.apply(wallet);
+        Ok(())
+    }
+    
+    /// Record an address's history page by page, resuming from a cursor left by a failed sync
+    fn sync_history(
+        &self,
+        client: &reqwest::blocking::Client,
+        wallet: &mut MobileWallet,
+        address: &str,
+        from_timestamp: u64,
+        cancel: &CancelToken,
+    ) -> Result<()> {
+        let cursor = self.cursors.lock().get(address).cloned();
+        let mut pages = self.history_pages(address, from_timestamp, cursor);
+        loop {
+            cancel.check()?;
+            let page: TransactionsResponse = self.get(client, &pages.path())?;
+            record_transactions(wallet, page.transactions);
+            
+            let Some(next_cursor) = pages.advance(page.next_cursor)? else {
+                self.cursors.lock().remove(address);
+                return Ok(());
+            };
+            self.cursors.lock().insert(address.to_string(), next_cursor.to_string());
+        }
+    }
+    
+    /// Pages of an address's history, starting from `cursor` (None for the first page)
+    fn history_pages<'a>(&self, address: &'a str, from_timestamp: u64, cursor: Option<String>) -> HistoryPages<'a> {
+        HistoryPages {
+            address,
+            from_timestamp,
+            limit: self.page_size,
+            cursor,
+        }
+    }
+    
+    /// Fetch balances and transactions for addresses without touching any wallet
+    pub fn fetch(&self, addresses: &[String]) -> Result<SyncUpdate> {
+        self.fetch_since(addresses, 0)
+    }
+    
+    /// Fetch balances and transactions newer than `from_timestamp` (0 fetches everything)
+    pub fn fetch_since(&self, addresses: &[String], from_timestamp: u64) -> Result<SyncUpdate> {
+        let _span = log_span!("sync_fetch", addresses = addresses.len(), from_timestamp);
+        let client = self.client()?;
+        
+        let accounts = addresses
+            .iter()
+            .map(|address| self.fetch_account(&client, address, from_timestamp))
+            .collect::<Result<Vec<_>>>()?;
+        
+        self.last_sync.store(now_secs(), Ordering::SeqCst);
+        
+        Ok(SyncUpdate { accounts })
+    }
+    
+    /// Has the node seen any transactions for an address
+    pub fn has_activity(&self, address: &str) -> Result<bool> {
+        let client = self.client()?;
+        let history: TransactionsResponse = self.get(&client, &history_path(address, 0, 1, None))?;
+        Ok(!history.transactions.is_empty())
+    }
+    
+    /// Fetch the node's current base fee rate (base units per byte)
+    pub fn fetch_fee_rate(&self) -> Result<u64> {
+        let client = self.client()?;
+        let response: FeeRateResponse = self.get(&client, "fee_rate")?;
+        Ok(response.fee_rate)
+    }
+    
+    /// Update a wallet's fee rate from the node
+    pub fn sync_fee_rate(&self, wallet: &mut MobileWallet) -> Result<()> {
+        let fee_rate = self.fetch_fee_rate()?;
+        wallet.set_fee_rate(fee_rate);
         Ok(())
     }
     
+    /// Submit a signed transaction, returning the node-assigned txid
+    ///
+    /// The transaction ID is sent as an idempotency key, and a node answering that it
+    /// already has the transaction, e.g. after a retried timeout, counts as success. Any other
+    /// HTTP 409, such as a double-spend, fails with `TransactionConflict`.
+    /// A transaction past its `valid_until` fails with `TransactionExpired` without being sent.
+    pub fn broadcast(&self, tx: &MobileTransaction) -> Result<String> {
+        let result = self.submit(tx);
+        log_event!(tx = %tx.id, result = ?result, "broadcast finished");
+        result
+    }
+    
+    /// POST a transaction to the node
+    fn submit(&self, tx: &MobileTransaction) -> Result<String> {
+        if tx.is_expired(now_secs()) {
+            return Err(MobileError::TransactionExpired);
+        }
+        
+        let response = self.client()?
+            .post(format!("{}/transactions", self.node_url))
+            .header(IDEMPOTENCY_KEY_HEADER, tx.id.as_str())
+            .json(&tx.broadcast_payload())
+            .send()
+            .map_err(MobileError::from)?;
+        
+        if response.status() == reqwest::StatusCode::CONFLICT {
+            return already_known(tx, response.json().ok());
+        }
+        check_broadcast_status(response.status())?;
+        
+        let response: BroadcastResponse = response
+            .json()
+            .map_err(MobileError::from)?;
+        Ok(response.txid)
+    }
+    
+    /// Ask the node for a transaction's status (`Pending` until the node has seen it)
+    pub fn poll_status(&self, txid: &str) -> Result<TransactionState> {
+        let response = self.client()?
+            .get(format!("{}/{}", self.node_url, status_path(txid)))
+            .send()
+            .map_err(MobileError::from)?;
+        
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(TransactionState {
+                status: TransactionStatus::Pending,
+                confirmations: 0,
+            });
+        }
+        check_status(response.status())?;
+        
+        let response: StatusResponse = response
+            .json()
+            .map_err(MobileError::from)?;
+        Ok(TransactionState::from_response(response))
+    }
+    
+    /// Poll a transaction's status and record it in the wallet history
+    pub fn refresh_status(&self, wallet: &mut MobileWallet, txid: &str) -> Result<TransactionStatus> {
+        let state = self.poll_status(txid)?;
+        wallet.set_transaction_status(txid, state.status);
+        wallet.set_transaction_confirmations(txid, state.confirmations);
+        Ok(state.status)
+    }
+    
     /// Get last sync timestamp
     pub fn last_sync(&self) -> u64 {
-        self.last_sync
+        self.last_sync.load(Ordering::SeqCst)
+    }
+    
+    /// Is the last successful sync older than `max_age_secs` (or missing)
+    pub fn is_stale(&self, max_age_secs: u64) -> bool {
+        let last_sync = self.last_sync();
+        last_sync == 0 || now_secs().saturating_sub(last_sync) > max_age_secs
+    }
+    
+    /// Fetch balance and transactions for a single address
+    fn fetch_account(
+        &self,
+        client: &reqwest::blocking::Client,
+        address: &str,
+        from_timestamp: u64,
+    ) -> Result<AccountUpdate> {
+        let balance: BalanceResponse = self.get(client, &balance_path(address))?;
+        
+        let mut transactions = Vec::new();
+        let mut pages = self.history_pages(address, from_timestamp, None);
+        loop {
+            let page: TransactionsResponse = self.get(client, &pages.path())?;
+            transactions.extend(page.transactions);
+            
+            if pages.advance(page.next_cursor)?.is_none() {
+                break;
+            }
+        }
+        
+        Ok(AccountUpdate {
+            address: address.to_string(),
+            balance: balance.balance,
+            utxos: balance.utxos,
+            transactions,
+        })
+    }
+    
+    /// Build an HTTP client for a sync run
+    fn client(&self) -> Result<reqwest::blocking::Client> {
+        reqwest::blocking::Client::builder()
+            .timeout(self.timeout)
+            .default_headers(self.default_headers()?)
+            .build()
+            .map_err(MobileError::from)
+    }
+    
+    /// Headers sent with every request
+    fn default_headers(&self) -> Result<reqwest::header::HeaderMap> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(api_key) = &self.api_key {
+            let mut value = reqwest::header::HeaderValue::from_str(api_key)
+                .map_err(|_| MobileError::network(NetworkErrorKind::InvalidConfig, "Invalid API key"))?;
+            value.set_sensitive(true);
+            headers.insert(API_KEY_HEADER, value);
+        }
+        Ok(headers)
+    }
+    
+    /// GET a JSON document from the node
+    fn get<T: DeserializeOwned>(&self, client: &reqwest::blocking::Client, path: &str) -> Result<T> {
+        let response = client
+            .get(format!("{}/{}", self.node_url, path))
+            .send()
+            .map_err(MobileError::from);
+        log_event!(path, status = ?response.as_ref().map(|response| response.status().as_u16()), "node request");
+        let response = response?;
+        
+        check_status(response.status())?;
+        
+        response
+            .json()
+            .map_err(MobileError::from)
+    }
+}
+
+#[cfg(feature = "async")]
+impl SyncManager {
+    /// Sync wallet with the node without blocking, leaving it untouched if any request fails
+    pub async fn sync_async(&self, wallet: &mut MobileWallet) -> Result<()> {
+        let addresses: Vec<String> = wallet
+            .accounts()
+            .iter()
+            .map(|account| account.address().to_string())
+            .collect();
+        
+        let update = self.fetch_async(&addresses).await?;
+        update.apply(wallet);
+        Ok(())
+    }
+    
+    /// Fetch balances and transactions for addresses without blocking
+    pub async fn fetch_async(&self, addresses: &[String]) -> Result<SyncUpdate> {
+        let client = self.async_client()?;
+        
+        let mut accounts = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let balance: BalanceResponse = self.get_async(&client, &balance_path(address)).await?;
+            
+            let mut transactions = Vec::new();
+            let mut pages = self.history_pages(address, 0, None);
+            loop {
+                let page: TransactionsResponse = self.get_async(&client, &pages.path()).await?;
+                transactions.extend(page.transactions);
+                
+                if pages.advance(page.next_cursor)?.is_none() {
+                    break;
+                }
+            }
+            
+            accounts.push(AccountUpdate {
+                address: address.clone(),
+                balance: balance.balance,
+                utxos: balance.utxos,
+                transactions,
+            });
+        }
+        
+        self.last_sync.store(now_secs(), Ordering::SeqCst);
+        
+        Ok(SyncUpdate { accounts })
+    }
+    
+    /// Submit a signed transaction without blocking, returning the node-assigned txid
+    pub async fn broadcast_async(&self, tx: &MobileTransaction) -> Result<String> {
+        let result = self.submit_async(tx).await;
+        log_event!(tx = %tx.id, result = ?result, "broadcast finished");
+        result
+    }
+    
+    /// POST a transaction to the node without blocking
+    async fn submit_async(&self, tx: &MobileTransaction) -> Result<String> {
+        if tx.is_expired(now_secs()) {
+            return Err(MobileError::TransactionExpired);
+        }
+        
+        let response = self.async_client()?
+            .post(format!("{}/transactions", self.node_url))
+            .header(IDEMPOTENCY_KEY_HEADER, tx.id.as_str())
+            .json(&tx.broadcast_payload())
+            .send()
+            .await
+            .map_err(MobileError::from)?;
+        
+        if response.status() == reqwest::StatusCode::CONFLICT {
+            return already_known(tx, response.json().await.ok());
+        }
+        check_broadcast_status(response.status())?;
+        
+        let response: BroadcastResponse = response
+            .json()
+            .await
+            .map_err(MobileError::from)?;
+        Ok(response.txid)
+    }
+    
+    /// Build an async HTTP client
+    fn async_client(&self) -> Result<reqwest::Client> {
+        reqwest::Client::builder()
+            .timeout(self.timeout)
+            .default_headers(self.default_headers()?)
+            .build()
+            .map_err(MobileError::from)
+    }
+    
+    /// GET a JSON document from the node without blocking
+    async fn get_async<T: DeserializeOwned>(&self, client: &reqwest::Client, path: &str) -> Result<T> {
+        let response = client
+            .get(format!("{}/{}", self.node_url, path))
+            .send()
+            .await
+            .map_err(MobileError::from);
+        log_event!(path, status = ?response.as_ref().map(|response| response.status().as_u16()), "node request");
+        let response = response?;
+        
+        check_status(response.status())?;
+        
+        response
+            .json()
+            .await
+            .map_err(MobileError::from)
     }
 }
 
+impl Clone for SyncManager {
+    fn clone(&self) -> Self {
+        Self {
+            node_url: self.node_url.clone(),
+            timeout: self.timeout,
+            api_key: self.api_key.clone(),
+            page_size: self.page_size,
+            cursors: Mutex::new(self.cursors.lock().clone()),
+            last_sync: AtomicU64::new(self.last_sync()),
+        }
+    }
+}
+
+impl Default for SyncManager {
+    fn default() -> Self {
+        Self {
+            node_url: DEFAULT_NODE_URL.to_string(),
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+            api_key: None,
+            page_size: DEFAULT_PAGE_SIZE,
+            cursors: Mutex::new(HashMap::new()),
+            last_sync: AtomicU64::new(0),
+        }
+    }
+}
+
+impl SyncUpdate {
+    /// Apply the update to a wallet, appending only transactions it hasn't seen
+    ///
+    /// Known transactions only pick up the node's view of their status and depth.
+    pub fn apply(self, wallet: &mut MobileWallet) {
+        for account in self.accounts {
+            account.apply(wallet);
+        }
+    }
+    
+    /// Record only the transactions, for an update a newer sync has superseded
+    ///
+    /// Balances are the node's view at fetch time, so a stale one must not replace a newer one.
+    pub fn apply_transactions(self, wallet: &mut MobileWallet) {
+        for account in self.accounts {
+            record_transactions(wallet, account.transactions);
+        }
+    }
+}
+
+impl AccountUpdate {
+    /// Apply the update to its account, skipping accounts removed since the fetch
+    pub fn apply(self, wallet: &mut MobileWallet) {
+        let index = wallet
+            .accounts()
+            .iter()
+            .position(|existing| existing.address() == self.address);
+        
+        let Some(index) = index else {
+            return;
+        };
+        let _ = wallet.set_account_balance(index, self.balance);
+        let _ = wallet.set_account_utxos(index, self.utxos);
+        record_transactions(wallet, self.transactions);
+    }
+}
+
+/// Record transactions reported by the node, then expire pending ones past their `valid_until`
+fn record_transactions(wallet: &mut MobileWallet, transactions: Vec<MobileTransaction>) {
+    for mut transaction in transactions {
+        transaction.broadcast = true;
+        wallet.upsert_transaction(transaction);
+    }
+    wallet.expire_transactions(now_secs());
+}
+
+/// Cursor over the pages of an address's history, shared by every paging loop
+struct HistoryPages<'a> {
+    address: &'a str,
+    from_timestamp: u64,
+    limit: u32,
+    cursor: Option<String>,
+}
+
+impl HistoryPages<'_> {
+    /// Node path of the current page
+    fn path(&self) -> String {
+        history_path(self.address, self.from_timestamp, self.limit, self.cursor.as_deref())
+    }
+    
+    /// Move to the page after the one just read, returning its cursor (None after the last page)
+    ///
+    /// Fails when the node repeats the current cursor, which would page forever.
+    fn advance(&mut self, next_cursor: Option<String>) -> Result<Option<&str>> {
+        if next_cursor.is_some() && next_cursor == self.cursor {
+            return Err(MobileError::network(NetworkErrorKind::Decode, "Node repeated a history cursor"));
+        }
+        self.cursor = next_cursor;
+        Ok(self.cursor.as_deref())
+    }
+}
+
+impl std::fmt::Debug for SyncManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("SyncManager")
+            .field("node_url", &self.node_url)
+            .field("timeout", &self.timeout)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("page_size", &self.page_size)
+            .field("last_sync", &self.last_sync)
+            .finish()
+    }
+}
+
+/// Check a node URL is http(s), and https unless the node is on this device when `require_https`
+fn check_node_url(node_url: &str, require_https: bool) -> Result<()> {
+    if node_url.is_empty() {
+        return Err(MobileError::network(NetworkErrorKind::InvalidConfig, "Node URL is empty"));
+    }
+    
+    let url = reqwest::Url::parse(node_url)
+        .map_err(|e| MobileError::network(NetworkErrorKind::InvalidConfig, format!("Invalid node URL: {}", e)))?;
+    let loopback = matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    match url.scheme() {
+        "https" => Ok(()),
+        "http" if !require_https || loopback => Ok(()),
+        "http" => Err(MobileError::network(NetworkErrorKind::InvalidConfig, "Node URL must use https")),
+        scheme => Err(MobileError::network(
+            NetworkErrorKind::InvalidConfig,
+            format!("Unsupported node URL scheme: {}", scheme),
+        )),
+    }
+}
+
+/// Serde default for settings saved before the timeout was configurable
+fn default_request_timeout() -> Duration {
+    DEFAULT_REQUEST_TIMEOUT
+}
+
+/// Node path for an address balance
+fn balance_path(address: &str) -> String {
+    format!("accounts/{}/balance", address)
+}
+
+/// Serde default for settings saved before history was paged
+fn default_page_size() -> u32 {
+    DEFAULT_PAGE_SIZE
+}
+
+/// Node path for a page of address history, optionally limited to newer transactions
+fn history_path(address: &str, from_timestamp: u64, limit: u32, cursor: Option<&str>) -> String {
+    let mut query = Vec::new();
+    if from_timestamp > 0 {
+        query.push(format!("since={}", from_timestamp));
+    }
+    query.push(format!("limit={}", limit));
+    if let Some(cursor) = cursor {
+        query.push(format!("cursor={}", percent_encode(cursor)));
+    }
+    format!("accounts/{}/transactions?{}", address, query.join("&"))
+}
+
+/// Node path for a transaction status
+fn status_path(txid: &str) -> String {
+    format!("transactions/{}/status", txid)
+}
+
+/// Accept a 409 only when the node says it already has `tx`, returning the txid
+///
+/// It must name `tx`'s ID or send `ALREADY_KNOWN_CODE`; anything else, including a body
+/// that doesn't parse, is a conflict with another transaction.
+fn already_known(tx: &MobileTransaction, response: Option<ConflictResponse>) -> Result<String> {
+    let Some(response) = response else {
+        return Err(MobileError::TransactionConflict("unreadable conflict response".to_string()));
+    };
+    if response.code.as_deref() == Some(ALREADY_KNOWN_CODE) {
+        return Ok(response.txid.unwrap_or_else(|| tx.id.clone()));
+    }
+    match response.txid {
+        Some(txid) if txid == tx.id => Ok(txid),
+        Some(txid) => Err(MobileError::TransactionConflict(format!("conflicts with {}", txid))),
+        None => Err(MobileError::TransactionConflict(response.code.unwrap_or_default())),
+    }
+}
+
+/// Map a broadcast response status to an error, treating client errors as rejections
+fn check_broadcast_status(status: reqwest::StatusCode) -> Result<()> {
+    if status.is_client_error() {
+        return Err(MobileError::TransactionFailed(format!(
+            "Node rejected transaction (HTTP {})",
+            status.as_u16()
+        )));
+    }
+    check_status(status)
+}
+
+/// Map a non-success HTTP status to an error
+fn check_status(status: reqwest::StatusCode) -> Result<()> {
+    if !status.is_success() {
+        return Err(MobileError::network(
+            NetworkErrorKind::Http(status.as_u16()),
+            format!("Node returned HTTP {}", status.as_u16()),
+        ));
+    }
+    Ok(())
+}
+
+/// Current time in seconds since the Unix epoch
+pub(crate) fn now_secs() -> u64 {
+    SystemClock.unix_secs()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use mockito::Matcher;
     
     #[test]
     fn test_sync_manager_creation() {
         let manager = SyncManager::new();
         assert!(manager.is_ok());
     }
+    
+    #[test]
+    fn test_sync_updates_balance_and_history() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let address = wallet.active_account().unwrap().address().to_string();
+        
+        let mut server = mockito::Server::new();
+        let balance_mock = server
+            .mock("GET", format!("/accounts/{}/balance", address).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"balance": 5000}"#)
+            .create();
+        let history_mock = server
+            .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"transactions": [{{"id": "tx_1", "from": "silver_sender", "to": "{}",
+                    "amount": 5000, "fee": 10, "status": "Confirmed", "timestamp": 1700000000}}]}}"#,
+                address
+            ))
+            .create();
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        sync_manager.sync(&mut wallet).unwrap();
+        
+        balance_mock.assert();
+        history_mock.assert();
+        assert_eq!(wallet.balance(), 5000);
+        assert_eq!(wallet.transaction_history().len(), 1);
+        assert_eq!(wallet.transaction_history()[0].id, "tx_1");
+        assert!(wallet.transaction_history()[0].broadcast);
+        
+        // A second sync doesn't duplicate known transactions
+        sync_manager.sync(&mut wallet).unwrap();
+        assert_eq!(wallet.transaction_history().len(), 1);
+    }
+    
+    #[test]
+    fn test_sync_failure_leaves_wallet_untouched() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(1234);
+        let address = wallet.active_account().unwrap().address().to_string();
+        
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", format!("/accounts/{}/balance", address).as_str())
+            .with_status(200)
+            .with_body(r#"{"balance": 5000}"#)
+            .create();
+        server
+            .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+            .with_status(500)
+            .create();
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        let result = sync_manager.sync(&mut wallet);
+        
+        assert!(matches!(
+            result,
+            Err(MobileError::NetworkError { kind: NetworkErrorKind::Http(500), .. })
+        ));
+        assert_eq!(wallet.balance(), 1234);
+        assert!(wallet.transaction_history().is_empty());
+    }
+    
+    #[test]
+    fn test_last_sync_tracks_successful_syncs() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let address = wallet.active_account().unwrap().address().to_string();
+        
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", format!("/accounts/{}/balance", address).as_str())
+            .with_status(200)
+            .with_body(r#"{"balance": 100}"#)
+            .create();
+        let history_mock = server
+            .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+            .with_status(200)
+            .with_body(r#"{"transactions": []}"#)
+            .create();
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        assert_eq!(sync_manager.last_sync(), 0);
+        assert!(sync_manager.is_stale(60));
+        
+        sync_manager.sync(&mut wallet).unwrap();
+        let last_sync = sync_manager.last_sync();
+        assert!(last_sync > 0);
+        assert!(!sync_manager.is_stale(60));
+        
+        // A failed sync leaves the timestamp where it was
+        history_mock.remove();
+        server
+            .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+            .with_status(503)
+            .create();
+        assert!(sync_manager.sync(&mut wallet).is_err());
+        assert_eq!(sync_manager.last_sync(), last_sync);
+    }
+    
+    #[test]
+    fn test_sync_since_requests_newer_transactions() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let address = wallet.active_account().unwrap().address().to_string();
+        
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", format!("/accounts/{}/balance", address).as_str())
+            .with_status(200)
+            .with_body(r#"{"balance": 100}"#)
+            .create();
+        let history_mock = server
+            .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+            .match_query(Matcher::UrlEncoded("since".into(), "1700000000".into()))
+            .with_status(200)
+            .with_body(r#"{"transactions": []}"#)
+            .create();
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        sync_manager.sync_since(&mut wallet, 1_700_000_000).unwrap();
+        
+        history_mock.assert();
+        assert_eq!(wallet.balance(), 100);
+    }
+    
+    #[test]
+    fn test_sync_updates_every_account() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.add_account().unwrap();
+        wallet.add_account().unwrap();
+        
+        let mut server = mockito::Server::new();
+        for (account, balance) in wallet.accounts().iter().zip([100u64, 200, 300]) {
+            server
+                .mock("GET", format!("/accounts/{}/balance", account.address()).as_str())
+                .with_status(200)
+                .with_body(format!(r#"{{"balance": {}}}"#, balance))
+                .create();
+            server
+                .mock("GET", format!("/accounts/{}/transactions", account.address()).as_str())
+                .with_status(200)
+                .with_body(r#"{"transactions": []}"#)
+                .create();
+        }
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        sync_manager.sync(&mut wallet).unwrap();
+        
+        assert_eq!(wallet.account_balance(0).unwrap(), 100);
+        assert_eq!(wallet.account_balance(1).unwrap(), 200);
+        assert_eq!(wallet.account_balance(2).unwrap(), 300);
+        assert_eq!(wallet.total_balance(), 600);
+    }
+    
+    #[test]
+    fn test_sync_fee_rate() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/fee_rate")
+            .with_status(200)
+            .with_body(r#"{"fee_rate": 25}"#)
+            .create();
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        sync_manager.sync_fee_rate(&mut wallet).unwrap();
+        assert_eq!(wallet.fee_rate(), 25);
+    }
+    
+    #[test]
+    fn test_timeout_is_distinguishable() {
+        // Accepts connections into the backlog but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        
+        let sync_manager = SyncManager::with_node_url(&url)
+            .unwrap()
+            .with_timeout(Duration::from_millis(200));
+        let result = sync_manager.fetch_fee_rate();
+        
+        assert!(matches!(
+            result,
+            Err(MobileError::NetworkError { kind: NetworkErrorKind::Timeout, .. })
+        ));
+        drop(listener);
+    }
+    
+    #[test]
+    fn test_api_key_header_sent() {
+        let mut server = mockito::Server::new();
+        let fee_mock = server
+            .mock("GET", "/fee_rate")
+            .match_header(API_KEY_HEADER, "secret-key")
+            .with_status(200)
+            .with_body(r#"{"fee_rate": 7}"#)
+            .create();
+        
+        let config = NodeConfig {
+            api_key: Some("secret-key".to_string()),
+            ..NodeConfig::new(&server.url())
+        };
+        let sync_manager = SyncManager::with_config(config).unwrap();
+        assert_eq!(sync_manager.fetch_fee_rate().unwrap(), 7);
+        fee_mock.assert();
+        assert!(!format!("{:?}", sync_manager).contains("secret-key"));
+        
+        // Without a key the request doesn't match
+        let anonymous = SyncManager::with_node_url(&server.url()).unwrap();
+        assert!(anonymous.fetch_fee_rate().is_err());
+    }
+    
+    #[test]
+    fn test_config_timeout_is_enforced() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = NodeConfig {
+            timeout: Duration::from_millis(100),
+            ..NodeConfig::new(&format!("http://{}", listener.local_addr().unwrap()))
+        };
+        
+        let sync_manager = SyncManager::with_config(config).unwrap();
+        assert!(matches!(
+            sync_manager.fetch_fee_rate(),
+            Err(MobileError::NetworkError { kind: NetworkErrorKind::Timeout, .. })
+        ));
+        drop(listener);
+    }
+    
+    #[test]
+    fn test_node_url_requires_https_in_release() {
+        assert!(check_node_url("https://node.silverbitcoin.org", true).is_ok());
+        assert!(check_node_url("http://127.0.0.1:8545", true).is_ok());
+        assert!(check_node_url("http://node.silverbitcoin.org", false).is_ok());
+        
+        for url in ["http://node.silverbitcoin.org", "ftp://node.silverbitcoin.org", "not a url", ""] {
+            assert!(matches!(
+                check_node_url(url, true),
+                Err(MobileError::NetworkError { kind: NetworkErrorKind::InvalidConfig, .. })
+            ));
+        }
+    }
+    
+    #[test]
+    fn test_malformed_response_is_decode_error() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/fee_rate")
+            .with_status(200)
+            .with_body("not json")
+            .create();
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        assert!(matches!(
+            sync_manager.fetch_fee_rate(),
+            Err(MobileError::NetworkError { kind: NetworkErrorKind::Decode, .. })
+        ));
+    }
+    
+    /// Build a signed-looking transaction from the wallet's active account
+    fn outgoing_transaction(wallet: &mut MobileWallet) -> MobileTransaction {
+        wallet.set_balance(10_000);
+        let recipient = crate::account::Account::address_from_public_key(&[2u8; 33], wallet.network());
+        let mut tx = wallet.create_transaction(&recipient, 1000, 10).unwrap();
+        wallet.sign_transaction(&mut tx, "password123").unwrap();
+        tx
+    }
+    
+    #[test]
+    fn test_broadcast_returns_txid() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let tx = outgoing_transaction(&mut wallet);
+        
+        let mut server = mockito::Server::new();
+        let broadcast_mock = server
+            .mock("POST", "/transactions")
+            .match_body(Matcher::PartialJsonString(format!(r#"{{"id": "{}"}}"#, tx.id)))
+            .with_status(200)
+            .with_body(r#"{"txid": "tx_node_1"}"#)
+            .create();
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        assert_eq!(sync_manager.broadcast(&tx).unwrap(), "tx_node_1");
+        broadcast_mock.assert();
+    }
+    
+    #[test]
+    fn test_broadcast_rejected() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let tx = outgoing_transaction(&mut wallet);
+        
+        let mut server = mockito::Server::new();
+        server
+            .mock("POST", "/transactions")
+            .with_status(400)
+            .with_body("invalid signature")
+            .create();
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        let result = sync_manager.broadcast(&tx);
+        assert!(matches!(result, Err(MobileError::TransactionFailed(_))));
+    }
+    
+    #[test]
+    fn test_broadcast_already_known_is_success() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let tx = outgoing_transaction(&mut wallet);
+        
+        let mut server = mockito::Server::new();
+        let known = server
+            .mock("POST", "/transactions")
+            .match_header(IDEMPOTENCY_KEY_HEADER, tx.id.as_str())
+            .with_status(409)
+            .with_body(format!(r#"{{"txid": "{}"}}"#, tx.id))
+            .create();
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        assert_eq!(sync_manager.broadcast(&tx).unwrap(), tx.id);
+        known.assert();
+        
+        // An explicit code counts too, whatever txid the node assigned
+        known.remove();
+        server
+            .mock("POST", "/transactions")
+            .with_status(409)
+            .with_body(r#"{"txid": "node_txid", "code": "already_known"}"#)
+            .create();
+        assert_eq!(sync_manager.broadcast(&tx).unwrap(), "node_txid");
+    }
+    
+    #[test]
+    fn test_broadcast_conflict_is_an_error() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let tx = outgoing_transaction(&mut wallet);
+        let sync_manager_for = |server: &mockito::Server| SyncManager::with_node_url(&server.url()).unwrap();
+        
+        // A double-spend of the same inputs by another transaction
+        let mut server = mockito::Server::new();
+        server
+            .mock("POST", "/transactions")
+            .with_status(409)
+            .with_body(r#"{"txid": "tx_other", "code": "inputs_spent"}"#)
+            .create();
+        assert!(matches!(
+            sync_manager_for(&server).broadcast(&tx),
+            Err(MobileError::TransactionConflict(detail)) if detail.contains("tx_other")
+        ));
+        
+        let mut server = mockito::Server::new();
+        server
+            .mock("POST", "/transactions")
+            .with_status(409)
+            .with_body("conflict")
+            .create();
+        assert!(matches!(sync_manager_for(&server).broadcast(&tx), Err(MobileError::TransactionConflict(_))));
+    }
+    
+    #[test]
+    fn test_poll_status_confirms_transaction() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let tx = outgoing_transaction(&mut wallet);
+        let txid = tx.id.clone();
+        wallet.add_transaction(tx);
+        
+        let mut server = mockito::Server::new();
+        let unseen_mock = server
+            .mock("GET", format!("/transactions/{}/status", txid).as_str())
+            .with_status(404)
+            .create();
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        assert_eq!(sync_manager.refresh_status(&mut wallet, &txid).unwrap(), TransactionStatus::Pending);
+        assert_eq!(wallet.transaction_history()[0].status, TransactionStatus::Pending);
+        
+        unseen_mock.remove();
+        server
+            .mock("GET", format!("/transactions/{}/status", txid).as_str())
+            .with_status(200)
+            .with_body(r#"{"status": "Confirmed"}"#)
+            .create();
+        assert_eq!(sync_manager.refresh_status(&mut wallet, &txid).unwrap(), TransactionStatus::Confirmed);
+        assert_eq!(wallet.transaction_history()[0].status, TransactionStatus::Confirmed);
+    }
+    
+    #[test]
+    fn test_sync_updates_known_transaction_depth() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let address = wallet.active_account().unwrap().address().to_string();
+        
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", format!("/accounts/{}/balance", address).as_str())
+            .with_status(200)
+            .with_body(r#"{"balance": 5000}"#)
+            .create();
+        let history = |confirmations: u32| {
+            format!(
+                r#"{{"transactions": [{{"id": "tx_1", "from": "silver_sender", "to": "{}",
+                    "amount": 5000, "fee": 10, "status": "Confirmed", "timestamp": 1700000000,
+                    "confirmations": {}}}]}}"#,
+                address, confirmations
+            )
+        };
+        let history_path = format!("/accounts/{}/transactions", address);
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        let first = server
+            .mock("GET", history_path.as_str())
+            .with_status(200)
+            .with_body(history(1))
+            .create();
+        sync_manager.sync(&mut wallet).unwrap();
+        first.remove();
+        assert!(!wallet.transaction_history()[0].is_final(6));
+        
+        server
+            .mock("GET", history_path.as_str())
+            .with_status(200)
+            .with_body(history(6))
+            .create();
+        sync_manager.sync(&mut wallet).unwrap();
+        assert_eq!(wallet.transaction_history().len(), 1);
+        assert!(wallet.transaction_history()[0].is_final(6));
+    }
+    
+    #[test]
+    fn test_sync_same_transaction_twice() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let address = wallet.active_account().unwrap().address().to_string();
+        
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", format!("/accounts/{}/balance", address).as_str())
+            .with_status(200)
+            .with_body(r#"{"balance": 5000}"#)
+            .create();
+        let history = |status: &str| {
+            format!(
+                r#"{{"transactions": [{{"id": "tx_1", "from": "silver_sender", "to": "{}",
+                    "amount": 5000, "fee": 10, "status": "{}", "timestamp": 1700000000}}]}}"#,
+                address, status
+            )
+        };
+        let history_path = format!("/accounts/{}/transactions", address);
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        let first = server
+            .mock("GET", history_path.as_str())
+            .with_status(200)
+            .with_body(history("Pending"))
+            .create();
+        sync_manager.sync(&mut wallet).unwrap();
+        first.remove();
+        assert_eq!(wallet.transaction_history()[0].status, TransactionStatus::Pending);
+        
+        server
+            .mock("GET", history_path.as_str())
+            .with_status(200)
+            .with_body(history("Confirmed"))
+            .create();
+        sync_manager.sync(&mut wallet).unwrap();
+        assert_eq!(wallet.transaction_history().len(), 1);
+        assert_eq!(wallet.transaction_history()[0].status, TransactionStatus::Confirmed);
+    }
+    
+    #[test]
+    fn test_sync_marks_replaced_transaction() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let address = wallet.active_account().unwrap().address().to_string();
+        let input = r#"{"txid": "funding", "vout": 0, "amount": 5000}"#;
+        let original = serde_json::from_str::<MobileTransaction>(&format!(
+            r#"{{"id": "tx_a", "from": "{}", "to": "silver_recipient", "amount": 1000, "fee": 10,
+                "status": "Pending", "timestamp": 1700000000, "inputs": [{}]}}"#,
+            address, input
+        )).unwrap();
+        wallet.add_transaction(original);
+        
+        // The node reports a fee bump of the same spend under a new txid
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", format!("/accounts/{}/balance", address).as_str())
+            .with_status(200)
+            .with_body(r#"{"balance": 3990}"#)
+            .create();
+        server
+            .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+            .with_status(200)
+            .with_body(format!(
+                r#"{{"transactions": [
+                    {{"id": "tx_b", "from": "{0}", "to": "silver_recipient", "amount": 1000, "fee": 50,
+                        "status": "Pending", "timestamp": 1700000060, "inputs": [{1}]}},
+                    {{"id": "tx_a", "from": "{0}", "to": "silver_recipient", "amount": 1000, "fee": 10,
+                        "status": "Pending", "timestamp": 1700000000, "inputs": [{1}]}}
+                ]}}"#,
+                address, input
+            ))
+            .create();
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        sync_manager.sync(&mut wallet).unwrap();
+        
+        assert_eq!(wallet.find_transaction("tx_a").unwrap().status, TransactionStatus::Replaced);
+        assert_eq!(wallet.find_transaction("tx_b").unwrap().status, TransactionStatus::Pending);
+        let pending: Vec<String> = wallet
+            .transaction_history()
+            .into_iter()
+            .filter(|tx| tx.status == TransactionStatus::Pending)
+            .map(|tx| tx.id)
+            .collect();
+        assert_eq!(pending, vec!["tx_b"]);
+    }
+    
+    /// Clock of a device that created a transaction an hour ago
+    struct HourAgoClock;
+    
+    impl Clock for HourAgoClock {
+        fn now(&self) -> std::time::SystemTime {
+            std::time::SystemTime::now() - Duration::from_secs(3600)
+        }
+    }
+    
+    #[test]
+    fn test_broadcast_rejects_expired_transaction() {
+        let mut tx = MobileTransaction::new_with_clock(
+            "silver_sender".to_string(),
+            "silver_recipient".to_string(),
+            1000,
+            10,
+            &HourAgoClock,
+        ).unwrap();
+        tx.valid_until = Some(tx.timestamp + 60);
+        tx.refresh_id();
+        
+        let mut server = mockito::Server::new();
+        let broadcast_mock = server.mock("POST", "/transactions").expect(0).create();
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        assert!(matches!(sync_manager.broadcast(&tx), Err(MobileError::TransactionExpired)));
+        broadcast_mock.assert();
+    }
+    
+    #[test]
+    fn test_sync_marks_expired_transaction() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let address = wallet.active_account().unwrap().address().to_string();
+        let now = now_secs();
+        let pending = |id: &str, valid_until: u64| {
+            serde_json::from_str::<MobileTransaction>(&format!(
+                r#"{{"id": "{}", "from": "{}", "to": "silver_recipient", "amount": 1000, "fee": 10,
+                    "status": "Pending", "timestamp": {}, "valid_until": {}}}"#,
+                id, address, now - 3600, valid_until
+            )).unwrap()
+        };
+        wallet.add_transaction(pending("tx_stale", now - 60));
+        wallet.add_transaction(pending("tx_fresh", now + 3600));
+        
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", format!("/accounts/{}/balance", address).as_str())
+            .with_status(200)
+            .with_body(r#"{"balance": 5000}"#)
+            .create();
+        server
+            .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+            .with_status(200)
+            .with_body(r#"{"transactions": []}"#)
+            .create();
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        sync_manager.sync(&mut wallet).unwrap();
+        
+        assert_eq!(wallet.find_transaction("tx_stale").unwrap().status, TransactionStatus::Expired);
+        assert_eq!(wallet.find_transaction("tx_fresh").unwrap().status, TransactionStatus::Pending);
+        assert_eq!(wallet.pending_balance(), 1010);
+    }
+    
+    #[test]
+    fn test_sync_wallet_continues_past_failed_account() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.add_account().unwrap();
+        wallet.add_account().unwrap();
+        let addresses: Vec<String> = wallet
+            .accounts()
+            .iter()
+            .map(|account| account.address().to_string())
+            .collect();
+        
+        let mut server = mockito::Server::new();
+        for (index, address) in addresses.iter().enumerate() {
+            let balance = server.mock("GET", format!("/accounts/{}/balance", address).as_str());
+            if index == 1 {
+                balance.with_status(503).create();
+            } else {
+                balance
+                    .with_status(200)
+                    .with_body(format!(r#"{{"balance": {}}}"#, 1000 * (index + 1)))
+                    .create();
+            }
+            server
+                .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+                .with_status(200)
+                .with_body(r#"{"transactions": []}"#)
+                .create();
+        }
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        let report = sync_manager.sync_wallet(&mut wallet).unwrap();
+        
+        assert!(!report.is_complete());
+        let failures: Vec<&str> = report.failures().map(|(address, _)| address).collect();
+        assert_eq!(failures, vec![addresses[1].as_str()]);
+        assert!(matches!(
+            report.failures().next().unwrap().1,
+            MobileError::NetworkError { kind: NetworkErrorKind::Http(503), .. }
+        ));
+        
+        let balances: Vec<u64> = wallet.accounts().iter().map(|account| account.balance()).collect();
+        assert_eq!(balances, vec![1000, 0, 3000]);
+        assert_eq!(sync_manager.last_sync(), 0);
+    }
+    
+    #[test]
+    fn test_history_pages_follow_cursor() {
+        let manager = SyncManager::new().unwrap().with_page_size(2);
+        let mut pages = manager.history_pages("addr", 0, None);
+        assert_eq!(pages.path(), "accounts/addr/transactions?limit=2");
+        
+        assert_eq!(pages.advance(Some("c2".to_string())).unwrap(), Some("c2"));
+        assert_eq!(pages.path(), "accounts/addr/transactions?limit=2&cursor=c2");
+        assert!(pages.advance(Some("c2".to_string())).is_err());
+        assert_eq!(pages.advance(None).unwrap(), None);
+    }
+    
+    #[test]
+    fn test_paged_sync_resumes_from_cursor() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let address = wallet.active_account().unwrap().address().to_string();
+        let page = |ids: &[&str], next_cursor: Option<&str>| {
+            let transactions: Vec<String> = ids
+                .iter()
+                .map(|id| format!(
+                    r#"{{"id": "{}", "from": "silver_sender", "to": "{}", "amount": 100,
+                        "fee": 1, "status": "Confirmed", "timestamp": 1700000000}}"#,
+                    id, address
+                ))
+                .collect();
+            let next_cursor = next_cursor.map_or("null".to_string(), |cursor| format!("\"{}\"", cursor));
+            format!(r#"{{"transactions": [{}], "next_cursor": {}}}"#, transactions.join(","), next_cursor)
+        };
+        let history_path = format!("/accounts/{}/transactions", address);
+        let cursor_query = |cursor: &str| Matcher::AllOf(vec![
+            Matcher::UrlEncoded("limit".into(), "2".into()),
+            Matcher::UrlEncoded("cursor".into(), cursor.into()),
+        ]);
+        
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", format!("/accounts/{}/balance", address).as_str())
+            .with_status(200)
+            .with_body(r#"{"balance": 500}"#)
+            .create();
+        let first_page = server
+            .mock("GET", history_path.as_str())
+            .match_query(Matcher::Exact("limit=2".into()))
+            .with_status(200)
+            .with_body(page(&["tx_1", "tx_2"], Some("c2")))
+            .expect(1)
+            .create();
+        let failing_page = server
+            .mock("GET", history_path.as_str())
+            .match_query(cursor_query("c2"))
+            .with_status(503)
+            .create();
+        let third_page = server
+            .mock("GET", history_path.as_str())
+            .match_query(cursor_query("c3"))
+            .with_status(200)
+            .with_body(page(&["tx_5"], None))
+            .expect(1)
+            .create();
+        
+        // The first page stays recorded when the second fails, and the balance waits
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap().with_page_size(2);
+        assert!(sync_manager.sync(&mut wallet).is_err());
+        assert_eq!(wallet.transaction_history().len(), 2);
+        assert_eq!(wallet.balance(), 0);
+        
+        // The next sync picks up at the failed page rather than the start
+        failing_page.remove();
+        let second_page = server
+            .mock("GET", history_path.as_str())
+            .match_query(cursor_query("c2"))
+            .with_status(200)
+            .with_body(page(&["tx_3", "tx_4"], Some("c3")))
+            .expect(1)
+            .create();
+        sync_manager.sync(&mut wallet).unwrap();
+        
+        first_page.assert();
+        second_page.assert();
+        third_page.assert();
+        let ids: Vec<String> = wallet.transaction_history().into_iter().map(|tx| tx.id).collect();
+        assert_eq!(ids, ["tx_1", "tx_2", "tx_3", "tx_4", "tx_5"]);
+        assert_eq!(wallet.balance(), 500);
+    }
+    
+    #[test]
+    fn test_cancelled_sync_keeps_fetched_pages() {
+        use std::io::Write;
+        
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let address = wallet.active_account().unwrap().address().to_string();
+        let page = |id: &str, next_cursor: &str| format!(
+            r#"{{"transactions": [{{"id": "{}", "from": "silver_sender", "to": "{}", "amount": 100,
+                "fee": 1, "status": "Confirmed", "timestamp": 1700000000}}], "next_cursor": {}}}"#,
+            id, address, next_cursor
+        );
+        let history_path = format!("/accounts/{}/transactions", address);
+        let cursor_query = Matcher::AllOf(vec![
+            Matcher::UrlEncoded("limit".into(), "1".into()),
+            Matcher::UrlEncoded("cursor".into(), "c2".into()),
+        ]);
+        
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", format!("/accounts/{}/balance", address).as_str())
+            .with_status(200)
+            .with_body(r#"{"balance": 200}"#)
+            .create();
+        
+        // The user navigates away while the first page is arriving
+        let cancel = CancelToken::new();
+        let first_body = page("tx_1", r#""c2""#);
+        let first_page = {
+            let cancel = cancel.clone();
+            server
+                .mock("GET", history_path.as_str())
+                .match_query(Matcher::Exact("limit=1".into()))
+                .with_status(200)
+                .with_chunked_body(move |writer| {
+                    cancel.cancel();
+                    writer.write_all(first_body.as_bytes())
+                })
+                .expect(1)
+                .create()
+        };
+        let skipped_page = server
+            .mock("GET", history_path.as_str())
+            .match_query(cursor_query.clone())
+            .expect(0)
+            .create();
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap().with_page_size(1);
+        assert!(matches!(
+            sync_manager.sync_cancellable(&mut wallet, &cancel),
+            Err(MobileError::SyncCancelled)
+        ));
+        first_page.assert();
+        skipped_page.assert();
+        assert_eq!(wallet.transaction_history().len(), 1);
+        assert_eq!(wallet.balance(), 0);
+        assert_eq!(sync_manager.last_sync(), 0);
+        
+        // A later sync resumes after the fetched page
+        skipped_page.remove();
+        let second_page = server
+            .mock("GET", history_path.as_str())
+            .match_query(cursor_query)
+            .with_status(200)
+            .with_body(page("tx_2", "null"))
+            .expect(1)
+            .create();
+        sync_manager.sync(&mut wallet).unwrap();
+        
+        second_page.assert();
+        let ids: Vec<String> = wallet.transaction_history().into_iter().map(|tx| tx.id).collect();
+        assert_eq!(ids, ["tx_1", "tx_2"]);
+        assert_eq!(wallet.balance(), 200);
+    }
+    
+    #[test]
+    fn test_poll_status_tracks_confirmations() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let tx = outgoing_transaction(&mut wallet);
+        let txid = tx.id.clone();
+        wallet.add_transaction(tx);
+        
+        let mut server = mockito::Server::new();
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        let path = format!("/transactions/{}/status", txid);
+        
+        for (confirmations, final_at_three) in [(0, false), (1, false), (2, false), (3, true)] {
+            let mock = server
+                .mock("GET", path.as_str())
+                .with_status(200)
+                .with_body(format!(r#"{{"status": "Pending", "confirmations": {}}}"#, confirmations))
+                .create();
+            sync_manager.refresh_status(&mut wallet, &txid).unwrap();
+            mock.remove();
+            
+            let recorded = &wallet.transaction_history()[0];
+            assert_eq!(recorded.confirmations, confirmations);
+            assert_eq!(recorded.is_final(3), final_at_three);
+        }
+    }
+    
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_sync_async_updates_wallet() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let address = wallet.active_account().unwrap().address().to_string();
+        
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", format!("/accounts/{}/balance", address).as_str())
+            .with_status(200)
+            .with_body(r#"{"balance": 700}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+            .with_status(200)
+            .with_body(r#"{"transactions": []}"#)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/transactions")
+            .with_status(200)
+            .with_body(r#"{"txid": "tx_node_1"}"#)
+            .create_async()
+            .await;
+        
+        let sync_manager = SyncManager::with_node_url(&server.url()).unwrap();
+        sync_manager.sync_async(&mut wallet).await.unwrap();
+        assert_eq!(wallet.balance(), 700);
+        assert!(sync_manager.last_sync() > 0);
+        
+        let tx = outgoing_transaction(&mut wallet);
+        assert_eq!(sync_manager.broadcast_async(&tx).await.unwrap(), "tx_node_1");
+    }
+    
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_sync_logs_span_without_secrets() {
+        use std::sync::Arc;
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata};
+        
+        /// Collects span names and every recorded field
+        #[derive(Default)]
+        struct Capture {
+            spans: Mutex<Vec<String>>,
+            fields: Mutex<Vec<String>>,
+            next_id: AtomicU64,
+        }
+        
+        struct Recorder<'a>(&'a Mutex<Vec<String>>);
+        
+        impl Visit for Recorder<'_> {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                self.0.lock().push(format!("{}={:?}", field.name(), value));
+            }
+        }
+        
+        #[derive(Clone, Default)]
+        struct Subscriber(Arc<Capture>);
+        
+        impl tracing::Subscriber for Subscriber {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                self.0.spans.lock().push(span.metadata().name().to_string());
+                span.record(&mut Recorder(&self.0.fields));
+                Id::from_u64(self.0.next_id.fetch_add(1, Ordering::SeqCst) + 1)
+            }
+            
+            fn record(&self, _: &Id, values: &Record<'_>) {
+                values.record(&mut Recorder(&self.0.fields));
+            }
+            
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            
+            fn event(&self, event: &Event<'_>) {
+                event.record(&mut Recorder(&self.0.fields));
+            }
+            
+            fn enter(&self, _: &Id) {}
+            
+            fn exit(&self, _: &Id) {}
+        }
+        
+        let phrase = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let subscriber = Subscriber::default();
+        let mut server = mockito::Server::new();
+        
+        tracing::subscriber::with_default(subscriber.clone(), || {
+            let mut wallet = MobileWallet::from_mnemonic(phrase, "password123").unwrap();
+            let address = wallet.active_account().unwrap().address().to_string();
+            server
+                .mock("GET", format!("/accounts/{}/balance", address).as_str())
+                .with_status(200)
+                .with_body(r#"{"balance": 500}"#)
+                .create();
+            server
+                .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+                .with_status(200)
+                .with_body(r#"{"transactions": []}"#)
+                .create();
+            
+            SyncManager::with_node_url(&server.url()).unwrap().sync(&mut wallet).unwrap();
+        });
+        
+        assert!(subscriber.0.spans.lock().iter().any(|name| name == "sync"));
+        let fields = subscriber.0.fields.lock();
+        assert!(fields.iter().any(|field| field.starts_with("elapsed_ms=")));
+        assert!(fields.iter().any(|field| field.starts_with("path=")));
+        
+        let seed_hex = hex::encode(&crate::mnemonic::to_seed(phrase, "")[..]);
+        for field in fields.iter() {
+            assert!(!field.contains("legal") && !field.contains("password123"), "{}", field);
+            assert!(!field.contains(&seed_hex[..16]), "{}", field);
+        }
+    }
 }
@@ -1,7 +1,47 @@
 //! Mobile transactions
 
+use secp256k1::{ecdsa::Signature, Message, PublicKey, Secp256k1, SecretKey};
 use serde::{Deserialize, Serialize};
+use crate::clock::{Clock, SystemClock};
 use crate::errors::{MobileError, Result};
+use crate::payment::PaymentRequest;
+use crate::utxo::Utxo;
+
+/// Default minimum amount (base units) a wallet will send
+pub const DEFAULT_DUST_LIMIT: u64 = 546;
+
+/// Maximum memo length in characters
+pub const MAX_MEMO_LEN: usize = 256;
+
+/// Fees above this percentage of the amount are flagged in previews
+pub const HIGH_FEE_PERCENT: u64 = 10;
+
+/// Fees above this percentage of the amount are flagged as a likely mistake
+pub const OVERPAY_FEE_PERCENT: u64 = 25;
+
+/// Fees above this many base units (0.01 SILVER) are flagged as a likely mistake whatever the amount
+pub const MAX_REASONABLE_FEE: u64 = 1_000_000;
+
+/// Fixed fields of a serialized transaction: timestamp, fee, change and the input/output counts
+const BASE_SIZE: usize = 5 * 8;
+
+/// Fixed part of an input: txid length prefix, vout and amount
+const INPUT_OVERHEAD: usize = 8 + 4 + 8;
+
+/// Fixed part of an output: address length prefix and amount
+const OUTPUT_OVERHEAD: usize = 8 + 8;
+
+/// Length of a hex txid, assumed for inputs not selected yet
+const TYPICAL_TXID_LEN: usize = 64;
+
+/// Compact ECDSA signature plus the compressed public key that verifies it
+const SIGNATURE_SIZE: usize = 64 + 33;
+
+/// Magic prefix of the unsigned transaction export format
+const PSBT_MAGIC: &[u8] = b"silver-psbt";
+
+/// Version of the unsigned transaction export format
+const PSBT_VERSION: u8 = 1;
 
 /// Mobile transaction
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +66,115 @@ pub struct MobileTransaction {
     
     /// Timestamp
     pub timestamp: u64,
+    
+    /// Compact ECDSA signature over `signing_hash` (empty until signed)
+    #[serde(default)]
+    pub signature: Vec<u8>,
+    
+    /// Outputs spent by this transaction
+    #[serde(default)]
+    pub inputs: Vec<Utxo>,
+    
+    /// Change returned to the sender
+    #[serde(default)]
+    pub change: u64,
+    
+    /// Internal-chain address the change is paid to (the sender's address when `None`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_address: Option<String>,
+    
+    /// Local note; never signed or broadcast
+    #[serde(default)]
+    pub memo: Option<String>,
+    
+    /// Confirmation depth reported by the node
+    #[serde(default)]
+    pub confirmations: u32,
+    
+    /// Every recipient of a batch transaction (empty for a single recipient)
+    ///
+    /// A batch keeps its first recipient in `to` and the sum of all outputs in `amount`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<TransactionOutput>,
+    
+    /// Has the node accepted or reported this transaction
+    #[serde(default)]
+    pub broadcast: bool,
+    
+    /// Unix time after which the transaction must not be broadcast (no expiry when `None`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<u64>,
+}
+
+/// Recipient and amount of one output in a batch transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionOutput {
+    /// Recipient address
+    pub address: String,
+    
+    /// Amount
+    pub amount: u64,
+}
+
+/// Checked totals of a transaction, for a confirmation screen before anything is signed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionPreview {
+    /// Sender address (the active account)
+    pub from: String,
+    
+    /// Recipient address
+    pub to: String,
+    
+    /// Amount sent
+    pub amount: u64,
+    
+    /// Fee, including any dropped change
+    pub fee: u64,
+    
+    /// Change below the dust limit, paid as fee rather than returned (0 when none)
+    pub dropped_change: u64,
+    
+    /// Amount plus fee
+    pub total: u64,
+    
+    /// Spendable balance left once the transaction is sent
+    pub remaining_balance: u64,
+    
+    /// Things the user should confirm, none of which block sending
+    pub warnings: Vec<TransactionWarning>,
+}
+
+/// Non-fatal concern about a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionWarning {
+    /// The fee is more than `HIGH_FEE_PERCENT` of the amount
+    HighFee,
+    
+    /// The fee is more than `OVERPAY_FEE_PERCENT` of the amount or above `MAX_REASONABLE_FEE`,
+    /// most likely a typo; replaces `HighFee`
+    Overpayment,
+    
+    /// The recipient is one of the wallet's own addresses
+    SelfSend,
+}
+
+/// Unsigned transaction fields carried by the offline-signing export
+#[derive(Serialize, Deserialize)]
+struct UnsignedTransaction {
+    id: String,
+    from: String,
+    to: String,
+    amount: u64,
+    fee: u64,
+    timestamp: u64,
+    inputs: Vec<Utxo>,
+    change: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    change_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    outputs: Vec<TransactionOutput>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid_until: Option<u64>,
 }
 
 /// Transaction status
@@ -39,39 +188,61 @@ pub enum TransactionStatus {
     
     /// Failed
     Failed,
+    
+    /// Withdrawn locally before it was broadcast
+    Cancelled,
+    
+    /// Superseded by another transaction spending the same inputs
+    Replaced,
+    
+    /// Passed its `valid_until` without confirming
+    Expired,
 }
 
 impl MobileTransaction {
-    /// Create a new transaction
+    /// Create a new transaction timestamped by the device clock
     pub fn new(from: String, to: String, amount: u64, fee: u64) -> Result<Self> {
+        Self::new_with_clock(from, to, amount, fee, &SystemClock)
+    }
+    
+    /// Create a new transaction timestamped by `clock`
+    ///
+    /// A clock set before the Unix epoch yields timestamp 0 rather than failing.
+    pub fn new_with_clock(from: String, to: String, amount: u64, fee: u64, clock: &dyn Clock) -> Result<Self> {
         if from.is_empty() || to.is_empty() {
             return Err(MobileError::InvalidTransaction);
         }
         
         if amount == 0 {
-            return Err(MobileError::InvalidTransaction);
+            return Err(MobileError::InvalidAmount);
         }
         
-        use blake3::Hasher;
-        let mut hasher = Hasher::new();
-        hasher.update(from.as_bytes());
-        hasher.update(to.as_bytes());
-        hasher.update(&amount.to_le_bytes());
-        
-        let id = format!("tx_{}", hex::encode(hasher.finalize().as_bytes()));
-        
-        Ok(Self {
-            id,
+        let mut transaction = Self {
+            id: String::new(),
             from,
             to,
             amount,
             fee,
             status: TransactionStatus::Pending,
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-        })
+            timestamp: clock.unix_secs(),
+            signature: Vec::new(),
+            inputs: Vec::new(),
+            change: 0,
+            change_address: None,
+            memo: None,
+            confirmations: 0,
+            outputs: Vec::new(),
+            broadcast: false,
+            valid_until: None,
+        };
+        transaction.refresh_id();
+        
+        Ok(transaction)
+    }
+    
+    /// Parse a scanned `silver:` payment URI into transaction parameters
+    pub fn parse_payment_uri(uri: &str) -> Result<PaymentRequest> {
+        PaymentRequest::from_uri(uri)
     }
     
     /// Get transaction ID
@@ -79,15 +250,258 @@ impl MobileTransaction {
         &self.id
     }
     
+    /// Recompute the ID from the canonical bytes, after changing signed fields
+    pub fn refresh_id(&mut self) {
+        self.id = format!("tx_{}", hex::encode(blake3::hash(&self.canonical_bytes()).as_bytes()));
+    }
+    
     /// Get total amount (amount + fee)
-    pub fn total(&self) -> u64 {
-        self.amount + self.fee
+    pub fn total(&self) -> Result<u64> {
+        self.amount
+            .checked_add(self.fee)
+            .ok_or(MobileError::InvalidTransaction)
+    }
+    
+    /// Get memo
+    pub fn memo(&self) -> Option<&str> {
+        self.memo.as_deref()
+    }
+    
+    /// Set memo, rejecting memos longer than `MAX_MEMO_LEN`
+    pub fn set_memo(&mut self, memo: Option<String>) -> Result<()> {
+        if memo.as_ref().is_some_and(|memo| memo.chars().count() > MAX_MEMO_LEN) {
+            return Err(MobileError::MemoTooLong);
+        }
+        
+        self.memo = memo;
+        Ok(())
+    }
+    
+    /// Export the unsigned transaction for offline signing
+    ///
+    /// Format: [magic "silver-psbt"] [version (1 byte)] [JSON of inputs, outputs and fee]
+    pub fn to_psbt_bytes(&self) -> Result<Vec<u8>> {
+        let unsigned = UnsignedTransaction {
+            id: self.id.clone(),
+            from: self.from.clone(),
+            to: self.to.clone(),
+            amount: self.amount,
+            fee: self.fee,
+            timestamp: self.timestamp,
+            inputs: self.inputs.clone(),
+            change: self.change,
+            change_address: self.change_address.clone(),
+            outputs: self.outputs.clone(),
+            valid_until: self.valid_until,
+        };
+        
+        let mut bytes = PSBT_MAGIC.to_vec();
+        bytes.push(PSBT_VERSION);
+        serde_json::to_writer(&mut bytes, &unsigned)
+            .map_err(|e| MobileError::SerializationError(e.to_string()))?;
+        Ok(bytes)
+    }
+    
+    /// Import an unsigned transaction exported by `to_psbt_bytes`
+    pub fn from_psbt_bytes(bytes: &[u8]) -> Result<Self> {
+        let body = bytes
+            .strip_prefix(PSBT_MAGIC)
+            .ok_or_else(|| MobileError::SerializationError("Not an unsigned transaction".to_string()))?;
+        
+        match body.split_first() {
+            Some((&PSBT_VERSION, json)) => {
+                let unsigned: UnsignedTransaction = serde_json::from_slice(json)
+                    .map_err(|e| MobileError::SerializationError(e.to_string()))?;
+                
+                Ok(Self {
+                    id: unsigned.id,
+                    from: unsigned.from,
+                    to: unsigned.to,
+                    amount: unsigned.amount,
+                    fee: unsigned.fee,
+                    status: TransactionStatus::Pending,
+                    timestamp: unsigned.timestamp,
+                    signature: Vec::new(),
+                    inputs: unsigned.inputs,
+                    change: unsigned.change,
+                    change_address: unsigned.change_address,
+                    memo: None,
+                    confirmations: 0,
+                    outputs: unsigned.outputs,
+                    broadcast: false,
+                    valid_until: unsigned.valid_until,
+                })
+            }
+            _ => Err(MobileError::SerializationError("Unsupported unsigned transaction version".to_string())),
+        }
+    }
+    
+    /// Copy of the transaction as sent to the node, without local-only fields
+    pub fn broadcast_payload(&self) -> Self {
+        Self {
+            memo: None,
+            confirmations: 0,
+            ..self.clone()
+        }
+    }
+    
+    /// Recipients and amounts, whether or not this is a batch
+    pub fn recipients(&self) -> Vec<TransactionOutput> {
+        if self.outputs.is_empty() {
+            vec![TransactionOutput {
+                address: self.to.clone(),
+                amount: self.amount,
+            }]
+        } else {
+            self.outputs.clone()
+        }
+    }
+    
+    /// Is `address` the sender or one of the recipients
+    pub fn involves(&self, address: &str) -> bool {
+        self.from == address
+            || self.to == address
+            || self.outputs.iter().any(|output| output.address == address)
+    }
+    
+    /// Estimated size in bytes once signed, used to price fees
+    ///
+    /// Counts at least one input, since even a balance-only transaction spends something.
+    pub fn estimated_size(&self) -> usize {
+        let sender = 8 + self.from.len();
+        let inputs = if self.inputs.is_empty() {
+            INPUT_OVERHEAD + TYPICAL_TXID_LEN
+        } else {
+            self.inputs
+                .iter()
+                .map(|input| INPUT_OVERHEAD + input.txid.len())
+                .sum()
+        };
+        let outputs: usize = self
+            .recipients()
+            .iter()
+            .map(|output| OUTPUT_OVERHEAD + output.address.len())
+            .sum();
+        let change_address = self
+            .change_address
+            .as_ref()
+            .map_or(0, |address| b"change_address".len() + 8 + address.len());
+        
+        BASE_SIZE + sender + inputs + outputs + change_address + SIGNATURE_SIZE
+    }
+    
+    /// Has the transaction passed its `valid_until` at `now` (Unix seconds)
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.valid_until.is_some_and(|valid_until| now > valid_until)
     }
     
     /// Set status
     pub fn set_status(&mut self, status: TransactionStatus) {
         self.status = status;
     }
+    
+    /// Set confirmation count
+    pub fn set_confirmations(&mut self, confirmations: u32) {
+        self.confirmations = confirmations;
+    }
+    
+    /// Is the transaction confirmed at least `min_confirmations` deep
+    ///
+    /// A confirmed transaction always counts as at least one confirmation deep.
+    pub fn is_final(&self, min_confirmations: u32) -> bool {
+        self.status == TransactionStatus::Confirmed && self.confirmations.max(1) >= min_confirmations
+    }
+    
+    /// Canonical encoding of the signed fields, hashed for both the ID and the signature
+    ///
+    /// Every integer is little-endian on every platform, and lengths are `u64`.
+    /// Strings and lists are length-prefixed. The memo, status, confirmations and
+    /// signature are local or derived and left out.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        fn put_u64(bytes: &mut Vec<u8>, value: u64) {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        fn put_u32(bytes: &mut Vec<u8>, value: u32) {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        fn put_str(bytes: &mut Vec<u8>, value: &str) {
+            put_u64(bytes, value.len() as u64);
+            bytes.extend_from_slice(value.as_bytes());
+        }
+        
+        let mut bytes = b"silver_transaction".to_vec();
+        put_str(&mut bytes, &self.from);
+        put_str(&mut bytes, &self.to);
+        put_u64(&mut bytes, self.amount);
+        put_u64(&mut bytes, self.fee);
+        put_u64(&mut bytes, self.timestamp);
+        put_u64(&mut bytes, self.inputs.len() as u64);
+        for input in &self.inputs {
+            put_str(&mut bytes, &input.txid);
+            put_u32(&mut bytes, input.vout);
+            put_u64(&mut bytes, input.amount);
+        }
+        put_u64(&mut bytes, self.change);
+        
+        // Appended only for batches, so single-recipient encodings are unchanged
+        if !self.outputs.is_empty() {
+            put_u64(&mut bytes, self.outputs.len() as u64);
+            for output in &self.outputs {
+                put_str(&mut bytes, &output.address);
+                put_u64(&mut bytes, output.amount);
+            }
+        }
+        
+        // Tagged and appended only when set, so transactions without an expiry are unchanged
+        if let Some(valid_until) = self.valid_until {
+            bytes.extend_from_slice(b"valid_until");
+            put_u64(&mut bytes, valid_until);
+        }
+        
+        // Likewise for change paid to an internal-chain address
+        if let Some(change_address) = &self.change_address {
+            bytes.extend_from_slice(b"change_address");
+            put_str(&mut bytes, change_address);
+        }
+        
+        bytes
+    }
+    
+    /// Hash of the signed transaction fields
+    pub fn signing_hash(&self) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+        
+        Sha256::digest(self.canonical_bytes()).into()
+    }
+    
+    /// Sign the transaction with a secret key
+    pub fn sign(&mut self, secret_key: &SecretKey) {
+        let secp = Secp256k1::new();
+        let message = Message::from_digest(self.signing_hash());
+        self.signature = secp.sign_ecdsa(&message, secret_key).serialize_compact().to_vec();
+    }
+    
+    /// Is the transaction signed
+    pub fn is_signed(&self) -> bool {
+        !self.signature.is_empty()
+    }
+    
+    /// Verify the signature against a compressed public key
+    pub fn verify(&self, public_key: &[u8]) -> bool {
+        let secp = Secp256k1::verification_only();
+        
+        let public_key = match PublicKey::from_slice(public_key) {
+            Ok(public_key) => public_key,
+            Err(_) => return false,
+        };
+        let signature = match Signature::from_compact(&self.signature) {
+            Ok(signature) => signature,
+            Err(_) => return false,
+        };
+        
+        let message = Message::from_digest(self.signing_hash());
+        secp.verify_ecdsa(&message, &signature, &public_key).is_ok()
+    }
 }
 
 #[cfg(test)]
@@ -105,6 +519,67 @@ mod tests {
         assert!(tx.is_ok());
     }
     
+    /// Clock of a factory-reset device, a day before the epoch
+    struct PreEpochClock;
+    
+    impl Clock for PreEpochClock {
+        fn now(&self) -> std::time::SystemTime {
+            std::time::UNIX_EPOCH - std::time::Duration::from_secs(86_400)
+        }
+    }
+    
+    #[test]
+    fn test_clock_before_epoch() {
+        let tx = MobileTransaction::new_with_clock(
+            "silver_abc123".to_string(),
+            "silver_def456".to_string(),
+            1000,
+            100,
+            &PreEpochClock,
+        ).unwrap();
+        
+        assert_eq!(tx.timestamp, 0);
+        assert!(tx.id.starts_with("tx_"));
+    }
+    
+    #[test]
+    fn test_valid_until() {
+        let mut tx = MobileTransaction::new(
+            "silver_sender".to_string(),
+            "silver_recipient".to_string(),
+            1000,
+            100,
+        ).unwrap();
+        let unexpiring = tx.canonical_bytes();
+        assert!(!tx.is_expired(u64::MAX));
+        
+        tx.valid_until = Some(tx.timestamp + 60);
+        assert!(!tx.is_expired(tx.timestamp + 60));
+        assert!(tx.is_expired(tx.timestamp + 61));
+        
+        // The expiry is signed, and survives the offline-signing round trip
+        assert_ne!(tx.canonical_bytes(), unexpiring);
+        let imported = MobileTransaction::from_psbt_bytes(&tx.to_psbt_bytes().unwrap()).unwrap();
+        assert_eq!(imported.valid_until, tx.valid_until);
+    }
+    
+    #[test]
+    fn test_change_address_is_signed() {
+        let mut tx = MobileTransaction::new(
+            "silver_sender".to_string(),
+            "silver_recipient".to_string(),
+            1000,
+            100,
+        ).unwrap();
+        tx.change = 500;
+        let to_sender = tx.canonical_bytes();
+        
+        tx.change_address = Some("silver_change".to_string());
+        assert_ne!(tx.canonical_bytes(), to_sender);
+        let imported = MobileTransaction::from_psbt_bytes(&tx.to_psbt_bytes().unwrap()).unwrap();
+        assert_eq!(imported.change_address, tx.change_address);
+    }
+    
     #[test]
     fn test_transaction_total() {
         let tx = MobileTransaction::new(
@@ -114,6 +589,247 @@ mod tests {
             100,
         ).unwrap();
         
-        assert_eq!(tx.total(), 1100);
+        assert_eq!(tx.total().unwrap(), 1100);
+    }
+    
+    #[test]
+    fn test_transaction_total_overflow() {
+        let tx = MobileTransaction::new(
+            "silver_abc123".to_string(),
+            "silver_def456".to_string(),
+            u64::MAX,
+            1,
+        ).unwrap();
+        
+        assert!(matches!(tx.total(), Err(MobileError::InvalidTransaction)));
+    }
+    
+    #[test]
+    fn test_sign_and_verify() {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[0x42; 32]).unwrap();
+        let public_key = PublicKey::from_secret_key(&secp, &secret_key).serialize();
+        
+        let mut tx = MobileTransaction::new(
+            "silver_abc123".to_string(),
+            "silver_def456".to_string(),
+            1000,
+            100,
+        ).unwrap();
+        assert!(!tx.verify(&public_key));
+        
+        tx.sign(&secret_key);
+        assert!(tx.is_signed());
+        assert!(tx.verify(&public_key));
+        
+        // Tampering with the amount invalidates the signature
+        tx.amount = 2000;
+        assert!(!tx.verify(&public_key));
+    }
+    
+    #[test]
+    fn test_parse_payment_uri() {
+        use crate::account::Account;
+        use crate::network::Network;
+        
+        let address = Account::address_from_public_key(&[2u8; 33], Network::Mainnet);
+        let request = MobileTransaction::parse_payment_uri(&format!("silver:{}?amount=1000", address)).unwrap();
+        
+        assert_eq!(request.address, address);
+        assert_eq!(request.amount, Some(1000));
+    }
+    
+    #[test]
+    fn test_zero_amount_rejected() {
+        let result = MobileTransaction::new(
+            "silver_abc123".to_string(),
+            "silver_def456".to_string(),
+            0,
+            100,
+        );
+        assert!(matches!(result, Err(MobileError::InvalidAmount)));
+    }
+    
+    #[test]
+    fn test_memo() {
+        let mut tx = MobileTransaction::new(
+            "silver_abc123".to_string(),
+            "silver_def456".to_string(),
+            1000,
+            100,
+        ).unwrap();
+        let hash = tx.signing_hash();
+        
+        tx.set_memo(Some("rent".to_string())).unwrap();
+        assert_eq!(tx.signing_hash(), hash);
+        assert!(tx.broadcast_payload().memo().is_none());
+        
+        let json = serde_json::to_string(&tx).unwrap();
+        let restored: MobileTransaction = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.memo(), Some("rent"));
+        
+        let result = tx.set_memo(Some("x".repeat(MAX_MEMO_LEN + 1)));
+        assert!(matches!(result, Err(MobileError::MemoTooLong)));
+        assert_eq!(tx.memo(), Some("rent"));
+    }
+    
+    #[test]
+    fn test_is_final_threshold() {
+        let mut tx = MobileTransaction::new(
+            "silver_sender".to_string(),
+            "silver_recipient".to_string(),
+            1000,
+            10,
+        ).unwrap();
+        assert!(!tx.is_final(0));
+        
+        tx.set_status(TransactionStatus::Confirmed);
+        tx.set_confirmations(1);
+        assert!(tx.is_final(1));
+        assert!(!tx.is_final(2));
+        assert!(!tx.is_final(3));
+        
+        tx.set_confirmations(3);
+        assert!(tx.is_final(3));
+        
+        tx.set_status(TransactionStatus::Failed);
+        assert!(!tx.is_final(3));
+    }
+    
+    #[test]
+    fn test_estimated_size_grows_with_outputs() {
+        let mut tx = MobileTransaction::new(
+            "silver_sender".to_string(),
+            "silver_recipient".to_string(),
+            1000,
+            10,
+        ).unwrap();
+        let single = tx.estimated_size();
+        
+        tx.outputs = vec![
+            TransactionOutput { address: "silver_recipient".to_string(), amount: 400 },
+            TransactionOutput { address: "silver_other".to_string(), amount: 600 },
+        ];
+        let batch = tx.estimated_size();
+        assert_eq!(batch, single + OUTPUT_OVERHEAD + "silver_other".len());
+        
+        // Signing doesn't change the estimate; it already accounts for the signature
+        tx.signature = vec![0u8; 64];
+        assert_eq!(tx.estimated_size(), batch);
+        
+        tx.inputs = vec![
+            Utxo { txid: "a".repeat(64), vout: 0, amount: 600 },
+            Utxo { txid: "b".repeat(64), vout: 1, amount: 600 },
+        ];
+        assert_eq!(tx.estimated_size(), batch + INPUT_OVERHEAD + 64);
+    }
+    
+    #[test]
+    fn test_id_covers_fee() {
+        let build = |fee: u64| {
+            let mut tx = MobileTransaction::new(
+                "silver_abc123".to_string(),
+                "silver_def456".to_string(),
+                1000,
+                fee,
+            ).unwrap();
+            tx.timestamp = 1_700_000_000;
+            tx.refresh_id();
+            tx
+        };
+        
+        let (low, high) = (build(10), build(20));
+        assert_ne!(low.id, high.id);
+        assert_ne!(low.signing_hash(), high.signing_hash());
+        assert_eq!(build(10).id, low.id);
+    }
+    
+    #[test]
+    fn test_canonical_bytes_known_answer() {
+        let mut tx = MobileTransaction::new(
+            "silver_a".to_string(),
+            "silver_b".to_string(),
+            0x0102_0304_0506_0708,
+            0x10,
+        ).unwrap();
+        tx.timestamp = 1_700_000_000;
+        tx.inputs = vec![Utxo { txid: "t".to_string(), vout: 1, amount: 2 }];
+        tx.change = 3;
+        tx.refresh_id();
+        
+        // Amount 0x0102030405060708 must appear as 08 07 06 05 04 03 02 01
+        let expected = concat!(
+            "73696c7665725f7472616e73616374696f6e",
+            "0800000000000000", "73696c7665725f61",
+            "0800000000000000", "73696c7665725f62",
+            "0807060504030201",
+            "1000000000000000",
+            "00f1536500000000",
+            "0100000000000000",
+            "0100000000000000", "74", "01000000", "0200000000000000",
+            "0300000000000000",
+        );
+        assert_eq!(hex::encode(tx.canonical_bytes()), expected);
+        assert_eq!(
+            hex::encode(tx.signing_hash()),
+            "0061e1bb369292cf0aee30c7f4c798003daac869e8ba2941e34060084b20382b"
+        );
+        assert_eq!(tx.id, format!("tx_{}", hex::encode(blake3::hash(&tx.canonical_bytes()).as_bytes())));
+    }
+    
+    #[test]
+    fn test_canonical_bytes_stable_across_serialization() {
+        let mut tx = MobileTransaction::new(
+            "silver_abc123".to_string(),
+            "silver_def456".to_string(),
+            1000,
+            100,
+        ).unwrap();
+        tx.inputs = vec![Utxo { txid: "tx_a".to_string(), vout: 1, amount: 1500 }];
+        tx.change = 400;
+        tx.outputs = vec![
+            TransactionOutput { address: "silver_def456".to_string(), amount: 600 },
+            TransactionOutput { address: "silver_ghi789".to_string(), amount: 400 },
+        ];
+        tx.refresh_id();
+        let bytes = tx.canonical_bytes();
+        
+        let json = serde_json::to_string(&tx).unwrap();
+        let restored: MobileTransaction = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.canonical_bytes(), bytes);
+        
+        let imported = MobileTransaction::from_psbt_bytes(&tx.to_psbt_bytes().unwrap()).unwrap();
+        assert_eq!(imported.canonical_bytes(), bytes);
+        
+        // Local-only fields don't affect the encoding
+        tx.set_memo(Some("rent".to_string())).unwrap();
+        tx.set_status(TransactionStatus::Confirmed);
+        tx.set_confirmations(3);
+        assert_eq!(tx.canonical_bytes(), bytes);
+    }
+    
+    #[test]
+    fn test_psbt_round_trip() {
+        let mut tx = MobileTransaction::new(
+            "silver_abc123".to_string(),
+            "silver_def456".to_string(),
+            1000,
+            100,
+        ).unwrap();
+        tx.inputs = vec![Utxo { txid: "tx_a".to_string(), vout: 0, amount: 1500 }];
+        tx.change = 400;
+        tx.signature = vec![1, 2, 3];
+        
+        let bytes = tx.to_psbt_bytes().unwrap();
+        let restored = MobileTransaction::from_psbt_bytes(&bytes).unwrap();
+        
+        assert!(!restored.is_signed());
+        assert_eq!(restored.inputs, tx.inputs);
+        assert_eq!(restored.signing_hash(), tx.signing_hash());
+        
+        assert!(MobileTransaction::from_psbt_bytes(b"garbage").is_err());
+        let mut future = bytes.clone();
+        future[PSBT_MAGIC.len()] = PSBT_VERSION + 1;
+        assert!(MobileTransaction::from_psbt_bytes(&future).is_err());
     }
 }
@@ -0,0 +1,89 @@
+//! Unspent outputs and coin selection
+
+use serde::{Deserialize, Serialize};
+use crate::errors::{MobileError, Result};
+
+/// Unspent transaction output
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Utxo {
+    /// ID of the transaction that created the output
+    pub txid: String,
+    
+    /// Output index within that transaction
+    pub vout: u32,
+    
+    /// Amount (base units)
+    pub amount: u64,
+}
+
+/// Sum output amounts, failing on overflow
+pub fn total(utxos: &[Utxo]) -> Result<u64> {
+    utxos
+        .iter()
+        .try_fold(0u64, |sum, utxo| sum.checked_add(utxo.amount))
+        .ok_or(MobileError::InvalidTransaction)
+}
+
+/// Select outputs covering `target`
+///
+/// A single output matching `target` exactly is preferred (no change);
+/// otherwise outputs are taken largest-first until the target is reached.
+pub fn select_coins(utxos: &[Utxo], target: u64) -> Result<Vec<Utxo>> {
+    if let Some(exact) = utxos.iter().find(|utxo| utxo.amount == target) {
+        return Ok(vec![exact.clone()]);
+    }
+    
+    let mut candidates: Vec<&Utxo> = utxos.iter().collect();
+    candidates.sort_by_key(|utxo| std::cmp::Reverse(utxo.amount));
+    
+    let mut selected = Vec::new();
+    let mut sum = 0u64;
+    for utxo in candidates {
+        if sum >= target {
+            break;
+        }
+        sum = sum.saturating_add(utxo.amount);
+        selected.push(utxo.clone());
+    }
+    
+    if sum < target {
+        return Err(MobileError::InsufficientBalance);
+    }
+    
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn utxo(vout: u32, amount: u64) -> Utxo {
+        Utxo {
+            txid: "tx_funding".to_string(),
+            vout,
+            amount,
+        }
+    }
+    
+    #[test]
+    fn test_exact_match_selection() {
+        let utxos = vec![utxo(0, 5000), utxo(1, 3000), utxo(2, 2000)];
+        assert_eq!(select_coins(&utxos, 3000).unwrap(), vec![utxo(1, 3000)]);
+    }
+    
+    #[test]
+    fn test_selection_needs_multiple_outputs() {
+        let utxos = vec![utxo(0, 2000), utxo(1, 5000), utxo(2, 3000)];
+        let selected = select_coins(&utxos, 7500).unwrap();
+        
+        assert_eq!(selected, vec![utxo(1, 5000), utxo(2, 3000)]);
+        assert_eq!(total(&selected).unwrap(), 8000);
+    }
+    
+    #[test]
+    fn test_selection_insufficient_funds() {
+        let utxos = vec![utxo(0, 2000), utxo(1, 3000)];
+        assert!(matches!(select_coins(&utxos, 5001), Err(MobileError::InsufficientBalance)));
+        assert!(matches!(select_coins(&[], 1), Err(MobileError::InsufficientBalance)));
+    }
+}
@@ -1,17 +1,94 @@
 //! Mobile wallet implementation
 
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use secp256k1::SecretKey;
 use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
 use crate::errors::{MobileError, Result};
-use crate::account::Account;
-use crate::transaction::MobileTransaction;
-use crate::keystore::Keystore;
+use crate::account::{self, Account, AccountKind};
+use crate::contacts::Contact;
+use crate::transaction::{self, MobileTransaction, TransactionOutput, TransactionPreview, TransactionStatus, TransactionWarning};
+use crate::keystore::{self, ImportedKey, KeyProvider, Keystore, KeystoreBackend};
+use crate::mnemonic;
+use crate::message;
+use crate::security::{self, SecurityManager, SecuritySettings};
+use crate::sync::SyncManager;
+use crate::hd::{self, DerivationPath, ExtendedPrivateKey, ExtendedPublicKey, Purpose};
+use crate::network::Network;
+use crate::fee::{self, FeePriority, FeeRate};
+use crate::history::{self, Direction, ExportRecord, HistoryFilter};
+use crate::utxo::{self, Utxo};
+use crate::price::{self, PriceProvider};
+use crate::payment::PaymentRequest;
+use crate::clock::{Clock, SystemClock};
+use crate::storage::{self, StorageFormat};
+
+/// Current version of the serialized wallet format
+///
+/// Version 1 is every wallet saved before the version was recorded.
+pub const WALLET_SCHEMA_VERSION: u32 = 3;
+
+/// Name given to wallets that haven't been renamed
+pub const DEFAULT_WALLET_NAME: &str = "Main Wallet";
+
+/// Default cap on the number of accounts in a wallet
+pub const DEFAULT_MAX_ACCOUNTS: usize = 256;
+
+/// Outcome of `MobileWallet::scan_for_accounts`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanReport {
+    /// Accounts added
+    pub added: usize,
+    
+    /// Signs that the scan missed accounts
+    pub warnings: Vec<ScanWarning>,
+}
+
+/// Sign that an account scan is incomplete, e.g. from a wrong derivation path or a partial restore
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanWarning {
+    /// An address past where the scan stopped has history; a larger gap limit would reach it
+    ActivityBeyondGap {
+        /// First such index
+        index: u32,
+    },
+    
+    /// More than half the gap limit of unused indices come before a used one
+    LargeGap {
+        /// First unused index
+        from: u32,
+        
+        /// Last unused index
+        to: u32,
+    },
+}
+
+/// Change-chain indices past the next unreserved one that are still accepted as the wallet's own
+///
+/// An offline signer never reserves change addresses itself, so it has to look ahead of its own
+/// counter to recognise change picked by the online wallet.
+pub const CHANGE_LOOKAHEAD: u32 = 1000;
 
 /// Mobile wallet
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MobileWallet {
+    /// Serialized format version
+    #[serde(default = "legacy_schema_version")]
+    schema_version: u32,
+    
     /// Wallet ID
     id: String,
     
+    /// Display name
+    #[serde(default = "default_name")]
+    name: String,
+    
+    /// Network
+    #[serde(default)]
+    network: Network,
+    
     /// Accounts
     accounts: Vec<Account>,
     
@@ -21,49 +98,199 @@ pub struct MobileWallet {
     /// Keystore
     keystore: Keystore,
     
-    /// Balance
-    balance: u64,
+    /// Imported private keys, each encrypted on its own
+    ///
+    /// Imported account slots count the keys still held by the keystore (hardware-sealed
+    /// wallets) first, then these.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    imported_keys: Vec<ImportedKey>,
+    
+    /// Extended public key of the BIP44 account, used to derive addresses
+    account_key: ExtendedPublicKey,
     
     /// Transaction history
     transaction_history: Vec<MobileTransaction>,
+    
+    /// Base fee rate (base units per byte) last observed from the node
+    #[serde(default = "default_fee_rate")]
+    fee_rate: u64,
+    
+    /// Address book
+    #[serde(default)]
+    contacts: Vec<Contact>,
+    
+    /// Amounts below this are rejected as dust
+    #[serde(default = "default_dust_limit")]
+    dust_limit: u64,
+    
+    /// Payment requests issued by this wallet
+    #[serde(default)]
+    payment_requests: Vec<PaymentRequest>,
+    
+    /// Most accounts the wallet may hold
+    #[serde(default = "default_max_accounts")]
+    max_accounts: usize,
+    
+    /// Seconds new transactions stay valid for (no expiry when `None`)
+    #[serde(default)]
+    transaction_ttl_secs: Option<u64>,
+    
+    /// Next unreserved index on the BIP44 change chain
+    #[serde(default)]
+    next_change_index: u32,
+    
+    /// Security preferences saved by `store_security_settings`
+    #[serde(default)]
+    security_settings: Option<SecuritySettings>,
+    
+    /// Encoding used by `save_to_path`, taken from the file on load
+    #[serde(skip)]
+    storage_format: StorageFormat,
 }
 
 impl MobileWallet {
-    /// Create a new wallet
+    /// Create a new mainnet wallet
     pub fn new(password: &str) -> Result<Self> {
+        Self::with_network(password, Network::Mainnet)
+    }
+    
+    /// Create a new wallet on the given network
+    pub fn with_network(password: &str, network: Network) -> Result<Self> {
         if password.len() < 8 {
             return Err(MobileError::InvalidPassword);
         }
         
         let keystore = Keystore::new(password)?;
-        let account = Account::new(0)?;
+        Self::from_keystore(keystore, password, network)
+    }
+    
+    /// Create a new wallet whose keystore secrets are sealed by `backend`
+    pub fn with_backend(password: &str, network: Network, backend: Arc<dyn KeystoreBackend>) -> Result<Self> {
+        if password.len() < 8 {
+            return Err(MobileError::InvalidPassword);
+        }
         
-        Ok(Self {
-            id: uuid::Uuid::new_v4().to_string(),
-            accounts: vec![account],
-            active_account: 0,
-            keystore,
-            balance: 0,
-            transaction_history: Vec::new(),
-        })
+        let keystore = Keystore::builder().backend(backend).build(password)?;
+        Self::from_keystore(keystore, password, network)
+    }
+    
+    /// Re-attach the keystore backend after loading a hardware-backed wallet
+    pub fn attach_keystore_backend(&mut self, backend: Arc<dyn KeystoreBackend>) -> Result<()> {
+        self.keystore.attach_backend(backend)
     }
     
-    /// Create wallet from mnemonic
+    /// Create mainnet wallet from mnemonic
     pub fn from_mnemonic(mnemonic: &str, password: &str) -> Result<Self> {
+        Self::from_mnemonic_with_network(mnemonic, password, Network::Mainnet)
+    }
+    
+    /// Create wallet from mnemonic on the given network
+    pub fn from_mnemonic_with_network(
+        mnemonic: &str,
+        password: &str,
+        network: Network,
+    ) -> Result<Self> {
         if password.len() < 8 {
             return Err(MobileError::InvalidPassword);
         }
         
         let keystore = Keystore::from_mnemonic(mnemonic, password)?;
-        let account = Account::new(0)?;
+        Self::from_keystore(keystore, password, network)
+    }
+    
+    /// Create wallet from a mnemonic even if it is a known test phrase (for deliberate test use)
+    pub fn from_mnemonic_allowing_weak(
+        mnemonic: &str,
+        password: &str,
+        network: Network,
+    ) -> Result<Self> {
+        if password.len() < 8 {
+            return Err(MobileError::InvalidPassword);
+        }
+        
+        let keystore = Keystore::builder()
+            .mnemonic(mnemonic)
+            .allow_weak_mnemonic()
+            .build(password)?;
+        Self::from_keystore(keystore, password, network)
+    }
+    
+    /// Create wallet from mnemonic and a BIP39 passphrase on the given network
+    ///
+    /// Different passphrases over the same mnemonic yield unrelated accounts.
+    pub fn from_mnemonic_with_passphrase(
+        mnemonic: &str,
+        passphrase: &str,
+        password: &str,
+        network: Network,
+    ) -> Result<Self> {
+        if password.len() < 8 {
+            return Err(MobileError::InvalidPassword);
+        }
+        
+        let keystore = Keystore::from_mnemonic_with_passphrase(mnemonic, passphrase, password)?;
+        Self::from_keystore(keystore, password, network)
+    }
+    
+    /// First `count` addresses a mnemonic yields from `path` on, without creating a wallet
+    ///
+    /// Lets the user confirm an import matches their old wallet before committing to it.
+    /// `passphrase` is the BIP39 passphrase, empty for none.
+    pub fn preview_addresses(
+        mnemonic: &str,
+        passphrase: &str,
+        path: DerivationPath,
+        network: Network,
+        count: usize,
+    ) -> Result<Vec<String>> {
+        if path.coin_type != network.coin_type() {
+            return Err(MobileError::NetworkMismatch);
+        }
+        
+        let mnemonic = mnemonic::normalize(mnemonic);
+        mnemonic::validate(&mnemonic)?;
+        let seed = mnemonic::to_seed(&mnemonic, passphrase);
+        let account_key = ExtendedPrivateKey::from_seed(&seed[..])?
+            .derive_path(&path.account_path())?
+            .public_key();
+        
+        (path.index..)
+            .take(count)
+            .map(|index| {
+                let path = DerivationPath { index, ..path };
+                Ok(Account::derived(path, &account_key, network)?.address().to_string())
+            })
+            .collect()
+    }
+    
+    /// Build a wallet around a keystore, deriving the first account from its seed
+    fn from_keystore(keystore: Keystore, password: &str, network: Network) -> Result<Self> {
+        let seed = keystore.seed(password)?;
+        let account_key = ExtendedPrivateKey::from_seed(&seed[..])?
+            .derive_path(&hd::account_path(network.coin_type()))?
+            .public_key();
+        let account = Account::new(0, &account_key, network)?;
         
         Ok(Self {
+            schema_version: WALLET_SCHEMA_VERSION,
             id: uuid::Uuid::new_v4().to_string(),
+            name: default_name(),
+            network,
             accounts: vec![account],
             active_account: 0,
             keystore,
-            balance: 0,
+            imported_keys: Vec::new(),
+            account_key,
             transaction_history: Vec::new(),
+            fee_rate: fee::DEFAULT_FEE_RATE,
+            contacts: Vec::new(),
+            dust_limit: transaction::DEFAULT_DUST_LIMIT,
+            payment_requests: Vec::new(),
+            max_accounts: DEFAULT_MAX_ACCOUNTS,
+            transaction_ttl_secs: None,
+            next_change_index: 0,
+            security_settings: None,
+            storage_format: StorageFormat::default(),
         })
     }
     
@@ -72,19 +299,118 @@ impl MobileWallet {
         &self.id
     }
     
-    /// Get balance
+    /// Short ID of the seed: the first 4 bytes of SHA-256 over the BIP44 account public key, in hex
+    ///
+    /// Unlike `id`, it is the same for every wallet restored from the same mnemonic and
+    /// passphrase, so users can check a restore; it reveals nothing about the seed.
+    pub fn fingerprint(&self) -> String {
+        use sha2::{Digest, Sha256};
+        
+        let digest = Sha256::digest(self.account_key.public_key());
+        hex::encode(&digest[..4])
+    }
+    
+    /// Get serialized format version
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+    
+    /// Get display name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    
+    /// Set display name, rejecting blank names and names over `MAX_NAME_LEN`
+    pub fn set_name(&mut self, name: &str) -> Result<()> {
+        account::validate_name(name)?;
+        self.name = name.to_string();
+        Ok(())
+    }
+    
+    /// Get network
+    pub fn network(&self) -> Network {
+        self.network
+    }
+    
+    /// Get balance of the active account
+    ///
+    /// Zero if the active account index is out of range.
     pub fn balance(&self) -> u64 {
-        self.balance
+        self.active_account().map(Account::balance).unwrap_or(0)
     }
     
-    /// Set balance
+    /// Set balance of the active account
     pub fn set_balance(&mut self, balance: u64) {
-        self.balance = balance;
+        if let Some(account) = self.accounts.get_mut(self.active_account) {
+            account.set_balance(balance);
+        }
+    }
+    
+    /// Get total balance across all accounts
+    pub fn total_balance(&self) -> u64 {
+        self.accounts
+            .iter()
+            .fold(0, |total, account| total.saturating_add(account.balance()))
+    }
+    
+    /// Amount (with fees) reserved by pending sends from the active account
+    pub fn pending_balance(&self) -> u64 {
+        let Ok(account) = self.active_account() else {
+            return 0;
+        };
+        let address = account.address();
+        self.transaction_history
+            .iter()
+            .filter(|tx| tx.status == TransactionStatus::Pending && tx.from == address)
+            .fold(0, |total, tx| total.saturating_add(tx.amount).saturating_add(tx.fee))
+    }
+    
+    /// Balance of the active account not reserved by pending sends
+    pub fn spendable_balance(&self) -> u64 {
+        self.balance().saturating_sub(self.pending_balance())
+    }
+    
+    /// Value of the total balance in `currency`, rounded to the nearest cent
+    pub fn balance_in_fiat(&self, provider: &dyn PriceProvider, currency: &str) -> Result<f64> {
+        let rate = provider.rate(currency)?;
+        price::to_fiat(self.total_balance(), rate)
+    }
+    
+    /// Get balance of the account at `index`
+    pub fn account_balance(&self, index: usize) -> Result<u64> {
+        self.accounts
+            .get(index)
+            .map(|account| account.balance())
+            .ok_or(MobileError::AccountNotFound)
+    }
+    
+    /// Set balance of the account at `index`
+    pub fn set_account_balance(&mut self, index: usize, balance: u64) -> Result<()> {
+        let account = self.accounts
+            .get_mut(index)
+            .ok_or(MobileError::AccountNotFound)?;
+        account.set_balance(balance);
+        Ok(())
     }
     
     /// Get active account
-    pub fn active_account(&self) -> &Account {
-        &self.accounts[self.active_account]
+    ///
+    /// Fails with `AccountNotFound` if the stored index is out of range, e.g. in a tampered save.
+    pub fn active_account(&self) -> Result<&Account> {
+        self.accounts
+            .get(self.active_account)
+            .ok_or(MobileError::AccountNotFound)
+    }
+    
+    /// Does any account of this wallet have `address`
+    pub fn owns_address(&self, address: &str) -> bool {
+        self.accounts.iter().any(|account| account.address() == address)
+    }
+    
+    /// Direction of a transaction relative to this wallet's accounts (None when it involves none)
+    pub fn transaction_direction(&self, transaction: &MobileTransaction) -> Option<Direction> {
+        let addresses: Vec<&str> = self.accounts.iter().map(|account| account.address()).collect();
+        Direction::classify(transaction, &addresses)
     }
     
     /// Get all accounts
@@ -92,96 +418,3097 @@ impl MobileWallet {
         &self.accounts
     }
     
-    /// Add account
+    /// Get active account index
+    pub fn active_account_index(&self) -> usize {
+        self.active_account
+    }
+    
+    /// Switch the active account
+    pub fn set_active_account(&mut self, index: usize) -> Result<()> {
+        if index >= self.accounts.len() {
+            return Err(MobileError::AccountNotFound);
+        }
+        
+        self.active_account = index;
+        Ok(())
+    }
+    
+    /// Get the account limit
+    pub fn max_accounts(&self) -> usize {
+        self.max_accounts
+    }
+    
+    /// Set the account limit; accounts already over it are kept, but none can be added
+    pub fn set_max_accounts(&mut self, max_accounts: usize) {
+        self.max_accounts = max_accounts;
+    }
+    
+    /// Get how long new transactions stay valid for, in seconds
+    pub fn transaction_ttl(&self) -> Option<u64> {
+        self.transaction_ttl_secs
+    }
+    
+    /// Set how long new transactions stay valid for, or `None` for no expiry
+    pub fn set_transaction_ttl(&mut self, ttl_secs: Option<u64>) {
+        self.transaction_ttl_secs = ttl_secs;
+    }
+    
+    /// Expire the transaction after the wallet's TTL, counted from its timestamp
+    fn apply_ttl(&self, transaction: &mut MobileTransaction) {
+        transaction.valid_until = self
+            .transaction_ttl_secs
+            .map(|ttl| transaction.timestamp.saturating_add(ttl));
+    }
+    
+    /// Fail with `AccountLimitReached` when no more accounts may be added
+    ///
+    /// Change accounts are registered by the wallet itself and don't count.
+    fn check_account_limit(&self) -> Result<()> {
+        if self.accounts.iter().filter(|account| !account.is_change()).count() >= self.max_accounts {
+            return Err(MobileError::AccountLimitReached);
+        }
+        Ok(())
+    }
+    
+    /// Add the next BIP44 account
     pub fn add_account(&mut self) -> Result<()> {
-        let account = Account::new(self.accounts.len() as u32)?;
+        self.check_account_limit()?;
+        
+        // Never reuse the derivation index of a removed account
+        let default_path = hd::account_path(self.network.coin_type());
+        let next_index = self.accounts
+            .iter()
+            .filter_map(|account| self.hd_path(account))
+            .filter(|path| path.account_path() == default_path && path.change == 0)
+            .map(|path| path.index + 1)
+            .max()
+            .unwrap_or(0);
+        
+        let account = Account::new(next_index, &self.account_key, self.network)?;
+        self.accounts.push(account);
+        Ok(())
+    }
+    
+    /// Discover used BIP44 accounts after a restore
+    ///
+    /// Derives successive indices and asks the node about each, stopping after
+    /// `gap_limit` consecutive addresses without history. Used addresses that
+    /// aren't accounts yet are added.
+    ///
+    /// The next `gap_limit` addresses are then probed for history the scan missed, which,
+    /// like a long run of unused indices, suggests a wrong path or a partial restore; both
+    /// come back as warnings. A failed probe only ends the look-ahead.
+    ///
+    /// The change chain is scanned the same way, so change from before the restore is tracked
+    /// and never handed out again.
+    pub fn scan_for_accounts(&mut self, sync: &SyncManager, gap_limit: u32) -> Result<ScanReport> {
+        let mut report = ScanReport::default();
+        let mut gap = 0;
+        let mut index = 0;
+        
+        while gap < gap_limit {
+            let account = Account::new(index, &self.account_key, self.network)?;
+            if sync.has_activity(account.address())? {
+                if gap > gap_limit / 2 {
+                    report.warnings.push(ScanWarning::LargeGap { from: index - gap, to: index - 1 });
+                }
+                gap = 0;
+                if !self.accounts.iter().any(|existing| existing.address() == account.address()) {
+                    self.check_account_limit()?;
+                    self.accounts.push(account);
+                    report.added += 1;
+                }
+            } else {
+                gap += 1;
+            }
+            index += 1;
+        }
+        
+        for probe in index..index.saturating_add(gap_limit) {
+            let account = Account::new(probe, &self.account_key, self.network)?;
+            match sync.has_activity(account.address()) {
+                Ok(true) => {
+                    report.warnings.push(ScanWarning::ActivityBeyondGap { index: probe });
+                    break;
+                }
+                Ok(false) => {}
+                Err(_) => break,
+            }
+        }
+        
+        let mut gap = 0;
+        let mut index = 0;
+        while gap < gap_limit {
+            if sync.has_activity(&self.change_address(index)?)? {
+                gap = 0;
+                let tracked = self.accounts.len();
+                self.register_change_account(index)?;
+                report.added += self.accounts.len() - tracked;
+                self.next_change_index = self.next_change_index.max(index + 1);
+            } else {
+                gap += 1;
+            }
+            index += 1;
+        }
+        
+        Ok(report)
+    }
+    
+    /// Add an account at an explicit derivation path, e.g. a BIP84 path from another wallet
+    ///
+    /// Needs the password because paths outside the BIP44 account are derived from the seed.
+    pub fn add_account_with_path(&mut self, path: DerivationPath, password: &str) -> Result<()> {
+        if path.coin_type != self.network.coin_type() {
+            return Err(MobileError::NetworkMismatch);
+        }
+        if self.accounts.iter().any(|account| self.hd_path(account) == Some(path)) {
+            return Err(MobileError::DuplicateAccount);
+        }
+        self.check_account_limit()?;
+        
+        let seed = self.keystore.seed(password)?;
+        let account_key = ExtendedPrivateKey::from_seed(&seed[..])?
+            .derive_path(&path.account_path())?
+            .public_key();
+        let account = Account::derived(path, &account_key, self.network)?;
         self.accounts.push(account);
         Ok(())
     }
     
+    /// Derivation path of an HD account, defaulting to BIP44 for accounts saved without one
+    fn hd_path(&self, account: &Account) -> Option<DerivationPath> {
+        match account.kind() {
+            AccountKind::Hd => Some(account.path().unwrap_or_else(|| {
+                DerivationPath::new(Purpose::Bip44, self.network.coin_type(), account.index())
+            })),
+            _ => None,
+        }
+    }
+    
+    /// Add a watch-only account that tracks an external address
+    pub fn add_watch_only(&mut self, address: String) -> Result<()> {
+        if !Account::validate_address(&address) {
+            return Err(MobileError::InvalidAddress);
+        }
+        
+        if Network::from_address(&address) != Some(self.network) {
+            return Err(MobileError::NetworkMismatch);
+        }
+        
+        if self.accounts.iter().any(|account| account.address() == address) {
+            return Err(MobileError::DuplicateAccount);
+        }
+        self.check_account_limit()?;
+        
+        self.accounts.push(Account::watch_only(address));
+        Ok(())
+    }
+    
+    /// Import a hex-encoded private key as a spendable single-key account
+    pub fn import_private_key(&mut self, key_hex: &str, password: &str) -> Result<()> {
+        let key_hex = key_hex.trim();
+        let key_hex = key_hex.strip_prefix("0x").unwrap_or(key_hex);
+        let mut key_bytes = Zeroizing::new([0u8; 32]);
+        if key_hex.len() != 64 {
+            return Err(MobileError::CryptoError("Invalid private key length".to_string()));
+        }
+        hex::decode_to_slice(key_hex, &mut key_bytes[..])
+            .map_err(|_| MobileError::CryptoError("Invalid private key encoding".to_string()))?;
+        
+        let public_key = public_key_for(&key_bytes)?;
+        
+        let address = Account::address_from_public_key(&public_key, self.network);
+        if self.accounts.iter().any(|account| account.address() == address) {
+            return Err(MobileError::DuplicateAccount);
+        }
+        self.check_account_limit()?;
+        
+        let slot = if self.keystore.backend_id() == keystore::SOFTWARE_BACKEND_ID {
+            // Make sure the key is sealed with the wallet password
+            self.keystore.export_mnemonic(password)?;
+            self.imported_keys.push(ImportedKey::new(&key_bytes, password, self.keystore.kdf())?);
+            self.keystore.imported_key_count() + self.imported_keys.len() as u32 - 1
+        } else {
+            // Keep the key sealed by the same hardware backend as the seed
+            self.keystore.add_private_key(&key_bytes, password)?
+        };
+        self.accounts.push(Account::imported(slot, public_key, self.network));
+        Ok(())
+    }
+    
+    /// Remove account, keeping the active account pointing at a valid entry
+    pub fn remove_account(&mut self, index: usize) -> Result<()> {
+        if index >= self.accounts.len() {
+            return Err(MobileError::AccountNotFound);
+        }
+        
+        if self.accounts.len() == 1 {
+            return Err(MobileError::CannotRemoveLastAccount);
+        }
+        
+        self.accounts.remove(index);
+        
+        // Shift down if an earlier account was removed, or clamp if the last one was
+        if self.active_account > index || self.active_account >= self.accounts.len() {
+            self.active_account -= 1;
+        }
+        
+        Ok(())
+    }
+    
     /// Create a transaction
     pub fn create_transaction(
-        &self,
+        &mut self,
         recipient: &str,
         amount: u64,
         fee: u64,
     ) -> Result<MobileTransaction> {
-        if amount + fee > self.balance {
-            return Err(MobileError::InsufficientBalance);
+        self.create_transaction_with_memo(recipient, amount, fee, None)
+    }
+    
+    /// Create a transaction with a local-only memo
+    ///
+    /// Any change goes to a freshly reserved change address.
+    pub fn create_transaction_with_memo(
+        &mut self,
+        recipient: &str,
+        amount: u64,
+        fee: u64,
+        memo: Option<String>,
+    ) -> Result<MobileTransaction> {
+        let transaction = self.draft_transaction(&[(recipient.to_string(), amount)], fee, memo)?;
+        self.reserve_change_address(&transaction)?;
+        Ok(transaction)
+    }
+    
+    /// Build a transaction paying `outputs` without reserving its change address
+    ///
+    /// A single output is the plain recipient; several are listed as a batch.
+    fn draft_transaction(
+        &self,
+        outputs: &[(String, u64)],
+        fee: u64,
+        memo: Option<String>,
+    ) -> Result<MobileTransaction> {
+        let Some((first_recipient, _)) = outputs.first() else {
+            return Err(MobileError::InvalidTransaction);
+        };
+        
+        let mut amount: u64 = 0;
+        for (recipient, output_amount) in outputs {
+            self.check_output(recipient, *output_amount)?;
+            amount = amount
+                .checked_add(*output_amount)
+                .ok_or(MobileError::InvalidTransaction)?;
+        }
+        
+        let account = self.active_account()?;
+        if account.is_watch_only() {
+            return Err(MobileError::WatchOnlyAccount);
         }
         
-        let account = self.active_account();
-        let transaction = MobileTransaction::new(
+        let total = amount
+            .checked_add(fee)
+            .ok_or(MobileError::InvalidTransaction)?;
+        let (inputs, change) = self.fund(total)?;
+        let (change, dropped_change) = self.drop_dust_change(change);
+        
+        let mut transaction = MobileTransaction::new(
             account.address().to_string(),
-            recipient.to_string(),
+            first_recipient.clone(),
             amount,
-            fee,
+            fee + dropped_change,
         )?;
+        transaction.inputs = inputs;
+        transaction.change = change;
+        if change > 0 {
+            transaction.change_address = self.change_address_for(account)?;
+        }
+        if outputs.len() > 1 {
+            transaction.outputs = outputs
+                .iter()
+                .map(|(address, amount)| TransactionOutput {
+                    address: address.clone(),
+                    amount: *amount,
+                })
+                .collect();
+        }
+        transaction.set_memo(memo)?;
+        self.apply_ttl(&mut transaction);
+        transaction.refresh_id();
         
         Ok(transaction)
     }
     
-    /// Add transaction to history
-    pub fn add_transaction(&mut self, transaction: MobileTransaction) {
-        self.transaction_history.push(transaction);
+    /// Check a transaction the way `create_transaction` would and summarize it, without creating it
+    pub fn validate_transaction(&self, recipient: &str, amount: u64, fee: u64) -> Result<TransactionPreview> {
+        let transaction = self.draft_transaction(&[(recipient.to_string(), amount)], fee, None)?;
+        let total = transaction.total()?;
+        let (fee, dropped_change) = (transaction.fee, transaction.fee - fee);
+        
+        let mut warnings = Vec::new();
+        let fee_above = |percent: u64| u128::from(fee) * 100 > u128::from(amount) * u128::from(percent);
+        if fee_above(transaction::OVERPAY_FEE_PERCENT) || fee > transaction::MAX_REASONABLE_FEE {
+            warnings.push(TransactionWarning::Overpayment);
+        } else if fee_above(transaction::HIGH_FEE_PERCENT) {
+            warnings.push(TransactionWarning::HighFee);
+        }
+        if self.owns_address(recipient) {
+            warnings.push(TransactionWarning::SelfSend);
+        }
+        
+        Ok(TransactionPreview {
+            from: transaction.from,
+            to: transaction.to,
+            amount,
+            fee,
+            dropped_change,
+            total,
+            remaining_balance: self.available_balance()?.saturating_sub(total),
+            warnings,
+        })
     }
     
-    /// Get transaction history
-    pub fn transaction_history(&self) -> Vec<MobileTransaction> {
-        self.transaction_history.clone()
+    /// Create a transaction moving the active account's whole spendable balance, less `fee`
+    pub fn create_sweep_transaction(&mut self, recipient: &str, fee: u64) -> Result<MobileTransaction> {
+        let spendable = self.available_balance()?;
+        if fee >= spendable {
+            return Err(MobileError::InsufficientBalance);
+        }
+        self.create_transaction(recipient, spendable - fee, fee)
     }
     
-    /// Export mnemonic
-    pub fn export_mnemonic(&self, password: &str) -> Result<String> {
-        self.keystore.export_mnemonic(password)
+    /// Largest amount the active account can send at `fee_rate` once the fee is paid (0 when none)
+    ///
+    /// Coin selection takes outputs largest-first, so each run of the largest outputs is priced
+    /// in turn; an output worth less than the fee to spend it is left out.
+    pub fn max_spendable(&self, fee_rate: FeeRate) -> u64 {
+        let Ok(account) = self.active_account() else {
+            return 0;
+        };
+        if account.is_watch_only() {
+            return 0;
+        }
+        
+        let address = account.address().to_string();
+        let Ok(mut template) = MobileTransaction::new(address.clone(), address, 1, 0) else {
+            return 0;
+        };
+        let amount_after_fee = |template: &MobileTransaction, value: u64| {
+            value.saturating_sub(fee_rate.fee_for(template.estimated_size()))
+        };
+        
+        let mut utxos = account.utxos().to_vec();
+        utxos.sort_by_key(|utxo| std::cmp::Reverse(utxo.amount));
+        let max = if utxos.is_empty() {
+            amount_after_fee(&template, self.spendable_balance())
+        } else {
+            let mut value = 0u64;
+            let mut max = 0;
+            for utxo in utxos {
+                value = value.saturating_add(utxo.amount);
+                template.inputs.push(utxo);
+                max = max.max(amount_after_fee(&template, value));
+            }
+            max
+        };
+        
+        if max < self.dust_limit {
+            0
+        } else {
+            max
+        }
     }
-}
-
-// UUID support
-mod uuid {
-    use std::fmt;
     
-    pub struct Uuid([u8; 16]);
+    /// Create one transaction paying several recipients
+    pub fn create_batch_transaction(&mut self, outputs: &[(String, u64)], fee: u64) -> Result<MobileTransaction> {
+        self.create_batch_transaction_with_memo(outputs, fee, None)
+    }
     
-    impl Uuid {
-        pub fn new_v4() -> Self {
-            use rand::Rng;
-            let mut rng = rand::thread_rng();
-            let mut bytes = [0u8; 16];
-            rng.fill(&mut bytes);
-            Uuid(bytes)
+    /// Create one transaction paying several recipients, with a local-only memo
+    pub fn create_batch_transaction_with_memo(
+        &mut self,
+        outputs: &[(String, u64)],
+        fee: u64,
+        memo: Option<String>,
+    ) -> Result<MobileTransaction> {
+        let transaction = self.draft_transaction(outputs, fee, memo)?;
+        self.reserve_change_address(&transaction)?;
+        Ok(transaction)
+    }
+    
+    /// What the active account can spend: its tracked outputs, or the unreserved balance
+    fn available_balance(&self) -> Result<u64> {
+        let account = self.active_account()?;
+        if account.utxos().is_empty() {
+            Ok(self.spendable_balance())
+        } else {
+            utxo::total(account.utxos())
         }
     }
     
-    impl fmt::Display for Uuid {
-        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            write!(
-                f,
-                "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
-                self.0[0], self.0[1], self.0[2], self.0[3],
-                self.0[4], self.0[5],
-                self.0[6], self.0[7],
-                self.0[8], self.0[9],
-                self.0[10], self.0[11], self.0[12], self.0[13], self.0[14], self.0[15]
-            )
+    /// Validate a recipient address and the amount sent to it
+    fn check_output(&self, recipient: &str, amount: u64) -> Result<()> {
+        if recipient.len() > account::MAX_ADDRESS_LEN {
+            return Err(MobileError::AddressTooLong);
+        }
+        
+        if !Account::validate_address(recipient) {
+            return Err(MobileError::InvalidAddress);
+        }
+        
+        if Network::from_address(recipient) != Some(self.network) {
+            return Err(MobileError::NetworkMismatch);
+        }
+        
+        if amount == 0 {
+            return Err(MobileError::InvalidAmount);
+        }
+        
+        if amount < self.dust_limit {
+            return Err(MobileError::DustAmount);
         }
+        
+        Ok(())
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
     
-    #[test]
-    fn test_wallet_creation() {
-        let wallet = MobileWallet::new("password123");
-        assert!(wallet.is_ok());
+    /// Choose inputs of the active account covering `total`, returning them with the change
+    fn fund(&self, total: u64) -> Result<(Vec<Utxo>, u64)> {
+        // Spend tracked outputs when the node reports them, otherwise check the unreserved balance
+        if self.active_account()?.utxos().is_empty() {
+            if total > self.spendable_balance() {
+                return Err(MobileError::InsufficientBalance);
+            }
+            Ok((Vec::new(), 0))
+        } else {
+            let inputs = self.select_coins(total)?;
+            let change = utxo::total(&inputs)? - total;
+            Ok((inputs, change))
+        }
     }
     
-    #[test]
-    fn test_wallet_balance() {
-        let mut wallet = MobileWallet::new("password123").unwrap();
-        wallet.set_balance(1000);
+    /// Address at `index` on the internal (change) chain of the BIP44 account
+    pub fn change_address(&self, index: u32) -> Result<String> {
+        let public_key = self.account_key
+            .derive_child(1)?
+            .derive_child(index)?
+            .public_key()
+            .to_vec();
+        Ok(Account::address_from_public_key(&public_key, self.network))
+    }
+    
+    /// Change address the next transaction with change will reserve
+    pub fn next_change_address(&self) -> Result<String> {
+        self.change_address(self.next_change_index)
+    }
+    
+    /// Is `address` on this wallet's change chain, within `CHANGE_LOOKAHEAD` of the next unreserved index
+    pub fn is_change_address(&self, address: &str) -> Result<bool> {
+        if self.accounts.iter().any(|account| account.is_change() && account.address() == address) {
+            return Ok(true);
+        }
+        
+        // Derive the internal chain once and walk its children, rather than re-deriving per index
+        let chain = self.account_key.derive_child(1)?;
+        for child in chain.derive_children(0..self.next_change_index.saturating_add(CHANGE_LOOKAHEAD)) {
+            if Account::address_from_public_key(child?.public_key(), self.network) == address {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+    
+    /// Where change from `account` goes: the next change address for accounts of the BIP44
+    /// account, or back to the sender (`None`) for keys with no change chain
+    fn change_address_for(&self, account: &Account) -> Result<Option<String>> {
+        match self.hd_path(account) {
+            Some(path) if path.purpose == Purpose::Bip44 && path.account == 0 => self.next_change_address().map(Some),
+            _ => Ok(None),
+        }
+    }
+    
+    /// Claim the change address a new transaction pays to, and track it as a change account
+    ///
+    /// The account is what syncs the change's balance and outputs and signs when they are spent.
+    fn reserve_change_address(&mut self, transaction: &MobileTransaction) -> Result<()> {
+        let Some(change_address) = &transaction.change_address else {
+            return Ok(());
+        };
+        if *change_address != self.next_change_address()? {
+            return Err(MobileError::UnknownChangeAddress);
+        }
+        
+        self.register_change_account(self.next_change_index)?;
+        self.next_change_index += 1;
+        Ok(())
+    }
+    
+    /// Add the change account at `index` unless it is already tracked
+    fn register_change_account(&mut self, index: u32) -> Result<()> {
+        let path = DerivationPath {
+            change: 1,
+            ..DerivationPath::new(Purpose::Bip44, self.network.coin_type(), index)
+        };
+        let account = Account::derived(path, &self.account_key, self.network)?;
+        if !self.accounts.iter().any(|existing| existing.address() == account.address()) {
+            self.accounts.push(account);
+        }
+        Ok(())
+    }
+    
+    /// Split change into what is returned and what is too small to be worth an output
+    ///
+    /// Change below the dust limit would cost more to spend than it is worth, so it goes to the fee.
+    fn drop_dust_change(&self, change: u64) -> (u64, u64) {
+        if change > 0 && change < self.dust_limit {
+            (0, change)
+        } else {
+            (change, 0)
+        }
+    }
+    
+    /// Select unspent outputs of the active account covering `target`
+    pub fn select_coins(&self, target: u64) -> Result<Vec<Utxo>> {
+        utxo::select_coins(self.active_account()?.utxos(), target)
+    }
+    
+    /// Set unspent outputs of the account at `index`
+    pub fn set_account_utxos(&mut self, index: usize, utxos: Vec<Utxo>) -> Result<()> {
+        let account = self.accounts
+            .get_mut(index)
+            .ok_or(MobileError::AccountNotFound)?;
+        account.set_utxos(utxos);
+        Ok(())
+    }
+    
+    /// Create a transaction with the fee estimated from `priority`
+    pub fn create_transaction_with_priority(
+        &mut self,
+        recipient: &str,
+        amount: u64,
+        priority: FeePriority,
+    ) -> Result<MobileTransaction> {
+        let base_rate = self.fee_rate;
+        self.create_transaction_paying(&[(recipient.to_string(), amount)], |transaction| {
+            fee::estimate_transaction_fee(transaction, priority, base_rate)
+        })
+    }
+    
+    /// Create a transaction paying `fee_rate` for its estimated signed size, rounded up
+    pub fn create_transaction_with_rate(
+        &mut self,
+        recipient: &str,
+        amount: u64,
+        fee_rate: FeeRate,
+    ) -> Result<MobileTransaction> {
+        self.create_batch_transaction_with_rate(&[(recipient.to_string(), amount)], fee_rate)
+    }
+    
+    /// Create one transaction paying several recipients at `fee_rate` for its estimated signed size
+    pub fn create_batch_transaction_with_rate(
+        &mut self,
+        outputs: &[(String, u64)],
+        fee_rate: FeeRate,
+    ) -> Result<MobileTransaction> {
+        fee_rate.check()?;
+        self.create_transaction_paying(outputs, |transaction| {
+            fee_rate.fee_for(transaction.estimated_size())
+        })
+    }
+    
+    /// Create a transaction paying `outputs` whose fee covers what `needed_fee` asks for it
+    fn create_transaction_paying(
+        &mut self,
+        outputs: &[(String, u64)],
+        needed_fee: impl Fn(&MobileTransaction) -> u64,
+    ) -> Result<MobileTransaction> {
+        // A higher fee may pull in more inputs and so grow the transaction; stop once the fee covers it
+        let mut fee = 0;
+        loop {
+            let transaction = self.draft_transaction(outputs, fee, None)?;
+            let needed = needed_fee(&transaction);
+            if needed <= fee {
+                self.reserve_change_address(&transaction)?;
+                return Ok(transaction);
+            }
+            fee = needed;
+        }
+    }
+    
+    /// Get base fee rate
+    pub fn fee_rate(&self) -> u64 {
+        self.fee_rate
+    }
+    
+    /// Set base fee rate, e.g. from a rate observed by the sync layer
+    pub fn set_fee_rate(&mut self, fee_rate: u64) {
+        self.fee_rate = fee_rate;
+    }
+    
+    /// Get dust limit
+    pub fn dust_limit(&self) -> u64 {
+        self.dust_limit
+    }
+    
+    /// Set dust limit
+    pub fn set_dust_limit(&mut self, dust_limit: u64) {
+        self.dust_limit = dust_limit;
+    }
+    
+    /// Sign a transaction with the active account's key
+    pub fn sign_transaction(&self, tx: &mut MobileTransaction, password: &str) -> Result<()> {
+        let account = self.active_account()?;
+        if tx.from != account.address() {
+            return Err(MobileError::InvalidTransaction);
+        }
+        
+        self.sign_with_account(tx, account, password)
+    }
+    
+    /// Sign an unsigned transaction export with whichever account owns its sender address
+    pub fn sign_psbt(&self, bytes: &[u8], password: &str) -> Result<MobileTransaction> {
+        let mut tx = MobileTransaction::from_psbt_bytes(bytes)?;
+        
+        let account = self.accounts
+            .iter()
+            .find(|account| account.address() == tx.from)
+            .ok_or(MobileError::AccountNotFound)?;
+        self.sign_with_account(&mut tx, account, password)?;
+        
+        Ok(tx)
+    }
+    
+    /// Sign a transaction with an account's key
+    fn sign_with_account(&self, tx: &mut MobileTransaction, account: &Account, password: &str) -> Result<()> {
+        if account.is_watch_only() {
+            return Err(MobileError::WatchOnlyAccount);
+        }
+        // Change elsewhere would be a payment the confirmation screen never showed
+        if let Some(change_address) = &tx.change_address {
+            if !self.is_change_address(change_address)? {
+                return Err(MobileError::UnknownChangeAddress);
+            }
+        }
+        
+        let mut secret_key = self.key_provider(account)?.secret_key(password)?;
+        tx.sign(&secret_key);
+        secret_key.non_secure_erase();
+        
+        Ok(())
+    }
+    
+    /// Sign a free-form message with the key of the account at `account_index`
+    ///
+    /// The message is hashed under [`message::MESSAGE_PREFIX`], so the signature is never valid for a transaction.
+    pub fn sign_message(&self, account_index: usize, message: &[u8], password: &str) -> Result<Vec<u8>> {
+        let account = self.accounts
+            .get(account_index)
+            .ok_or(MobileError::AccountNotFound)?;
+        if account.is_watch_only() {
+            return Err(MobileError::WatchOnlyAccount);
+        }
+        
+        let mut secret_key = self.key_provider(account)?.secret_key(password)?;
+        let signature = message::sign_message(&secret_key, message);
+        secret_key.non_secure_erase();
+        
+        Ok(signature)
+    }
+    
+    /// Source of an account's signing key: the seed for HD accounts, or its imported-key slot
+    fn key_provider<'a>(&'a self, account: &Account) -> Result<Box<dyn KeyProvider + 'a>> {
+        match account.kind() {
+            AccountKind::Hd => {
+                let path = self.hd_path(account).ok_or(MobileError::AccountNotFound)?;
+                Ok(Box::new(HdKey { keystore: &self.keystore, path }))
+            }
+            AccountKind::Imported => {
+                let slot = account.index();
+                let keystore_slots = self.keystore.imported_key_count();
+                if slot < keystore_slots {
+                    return Ok(Box::new(KeystoreSlot { keystore: &self.keystore, slot }));
+                }
+                
+                let key = self.imported_keys
+                    .get((slot - keystore_slots) as usize)
+                    .ok_or(MobileError::AccountNotFound)?;
+                Ok(Box::new(key))
+            }
+            AccountKind::WatchOnly => Err(MobileError::WatchOnlyAccount),
+        }
+    }
+    
+    /// Add transaction to history, dropping the outputs it spends
+    ///
+    /// A transaction already in the history is updated in place rather than added twice.
+    pub fn add_transaction(&mut self, transaction: MobileTransaction) {
+        self.upsert_transaction(transaction);
+    }
+    
+    /// Insert a transaction, or update the status and depth of the entry with its ID
+    ///
+    /// A new transaction spending the inputs of a pending one (a fee bump or a double-spend)
+    /// marks that one `Replaced`. Returns `true` when the transaction was new.
+    pub fn upsert_transaction(&mut self, transaction: MobileTransaction) -> bool {
+        if let Some(existing) = self.find_transaction(&transaction.id) {
+            // A replaced transaction the node still reports as pending stays replaced
+            let superseded = existing.status == TransactionStatus::Replaced
+                && transaction.status == TransactionStatus::Pending;
+            if !superseded {
+                self.set_transaction_status(&transaction.id, transaction.status);
+            }
+            self.set_transaction_confirmations(&transaction.id, transaction.confirmations);
+            if transaction.broadcast {
+                self.mark_broadcast(&transaction.id);
+            }
+            return false;
+        }
+        
+        if !transaction.inputs.is_empty() {
+            for replaced in self.transaction_history.iter_mut().filter(|tx| {
+                tx.status == TransactionStatus::Pending
+                    && tx.inputs.iter().any(|input| transaction.inputs.contains(input))
+            }) {
+                replaced.set_status(TransactionStatus::Replaced);
+            }
+            for account in self.accounts.iter_mut() {
+                account.utxos.retain(|utxo| !transaction.inputs.contains(utxo));
+            }
+        }
+        
+        // Credit the oldest open request an incoming payment satisfies
+        if !self.owns_address(&transaction.from) {
+            if let Some(request) = self.payment_requests.iter_mut().find(|request| request.is_paid_by(&transaction)) {
+                request.fulfilled_by = Some(transaction.id.clone());
+            }
+        }
+        
+        self.transaction_history.push(transaction);
+        true
+    }
+    
+    /// Is a transaction with this ID already in the history
+    pub fn contains_transaction(&self, id: &str) -> bool {
+        self.find_transaction(id).is_some()
+    }
+    
+    /// Look up a transaction in the history by ID
+    ///
+    /// This scans the history, which is O(n) in its length.
+    pub fn find_transaction(&self, id: &str) -> Option<&MobileTransaction> {
+        self.transaction_history.iter().find(|tx| tx.id == id)
+    }
+    
+    /// Get transaction history
+    pub fn transaction_history(&self) -> Vec<MobileTransaction> {
+        self.transaction_history.clone()
+    }
+    
+    /// Get the history of the account at `index`: transactions it sent or received
+    pub fn account_history(&self, index: usize) -> Result<Vec<MobileTransaction>> {
+        let account = self.accounts
+            .get(index)
+            .ok_or(MobileError::AccountNotFound)?;
+        
+        Ok(self.transaction_history
+            .iter()
+            .filter(|tx| tx.involves(account.address()))
+            .cloned()
+            .collect())
+    }
+    
+    /// Indices of the accounts a transaction belongs to (both ends of an internal transfer)
+    pub fn transaction_accounts(&self, transaction: &MobileTransaction) -> Vec<usize> {
+        self.accounts
+            .iter()
+            .enumerate()
+            .filter(|(_, account)| transaction.involves(account.address()))
+            .map(|(index, _)| index)
+            .collect()
+    }
+    
+    /// Update the status of a transaction in the history, returning whether it was found
+    ///
+    /// A send that fails, is cancelled or expires gives the outputs it spent back to its account.
+    /// A replaced send never does: its replacement spends the same outputs.
+    pub fn set_transaction_status(&mut self, id: &str, status: TransactionStatus) -> bool {
+        let Some(tx) = self.transaction_history.iter_mut().find(|tx| tx.id == id) else {
+            return false;
+        };
+        let released = |status| {
+            matches!(status, TransactionStatus::Failed | TransactionStatus::Cancelled | TransactionStatus::Expired)
+        };
+        let newly_released = released(status) && !released(tx.status) && tx.status != TransactionStatus::Replaced;
+        tx.set_status(status);
+        
+        if newly_released && !tx.inputs.is_empty() {
+            let (from, inputs) = (tx.from.clone(), tx.inputs.clone());
+            if let Some(account) = self.accounts.iter_mut().find(|account| account.address() == from) {
+                for input in inputs {
+                    if !account.utxos.contains(&input) {
+                        account.utxos.push(input);
+                    }
+                }
+            }
+        }
+        
+        // A payment that never lands reopens the request it fulfilled
+        if !matches!(status, TransactionStatus::Pending | TransactionStatus::Confirmed) {
+            for request in self.payment_requests.iter_mut().filter(|request| request.fulfilled_by.as_deref() == Some(id)) {
+                request.fulfilled_by = None;
+            }
+        }
+        true
+    }
+    
+    /// Mark pending transactions past their `valid_until` at `now` as `Expired`, returning how many
+    pub fn expire_transactions(&mut self, now: u64) -> usize {
+        let expired: Vec<String> = self
+            .transaction_history
+            .iter()
+            .filter(|tx| tx.status == TransactionStatus::Pending && tx.is_expired(now))
+            .map(|tx| tx.id.clone())
+            .collect();
+        
+        for id in &expired {
+            self.set_transaction_status(id, TransactionStatus::Expired);
+        }
+        expired.len()
+    }
+    
+    /// Note that the node has a transaction in the history, returning whether it was found
+    pub(crate) fn mark_broadcast(&mut self, id: &str) -> bool {
+        match self.transaction_history.iter_mut().find(|tx| tx.id == id) {
+            Some(tx) => {
+                tx.broadcast = true;
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Cancel a pending transaction that was never broadcast, releasing its reserved balance
+    ///
+    /// The entry stays in the history as `Cancelled`.
+    pub fn cancel_pending(&mut self, txid: &str) -> Result<()> {
+        let tx = self.find_transaction(txid).ok_or(MobileError::TransactionNotFound)?;
+        if tx.broadcast || tx.status != TransactionStatus::Pending {
+            return Err(MobileError::AlreadyBroadcast);
+        }
+        
+        self.set_transaction_status(txid, TransactionStatus::Cancelled);
+        Ok(())
+    }
+    
+    /// Record the confirmation depth of a transaction in the history, returning whether it was found
+    pub fn set_transaction_confirmations(&mut self, id: &str, confirmations: u32) -> bool {
+        match self.transaction_history.iter_mut().find(|tx| tx.id == id) {
+            Some(tx) => {
+                tx.set_confirmations(confirmations);
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Get a page of transaction history, newest first
+    pub fn history_page(&self, offset: usize, limit: usize) -> Vec<MobileTransaction> {
+        self.history_newest_first()
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+    
+    /// Get transactions matching a filter, newest first
+    pub fn history_filter(&self, filter: &HistoryFilter) -> Vec<MobileTransaction> {
+        let addresses: Vec<&str> = self.accounts.iter().map(|account| account.address()).collect();
+        self.history_newest_first()
+            .filter(|tx| filter.matches(tx, &addresses))
+            .cloned()
+            .collect()
+    }
+    
+    /// Export the history as CSV, oldest first, in the `history::ExportRecord` schema
+    pub fn export_history_csv(&self) -> String {
+        history::to_csv(&self.export_records())
+    }
+    
+    /// Export the history as a JSON array, oldest first, in the `history::ExportRecord` schema
+    pub fn export_history_json(&self) -> Result<String> {
+        serde_json::to_string(&self.export_records())
+            .map_err(|e| MobileError::SerializationError(e.to_string()))
+    }
+    
+    /// Export rows of the history by ascending timestamp (insertion order on ties)
+    fn export_records(&self) -> Vec<ExportRecord> {
+        let addresses: Vec<&str> = self.accounts.iter().map(|account| account.address()).collect();
+        let mut history: Vec<&MobileTransaction> = self.transaction_history.iter().collect();
+        history.sort_by_key(|tx| tx.timestamp);
+        history
+            .into_iter()
+            .map(|tx| ExportRecord::new(tx, &addresses))
+            .collect()
+    }
+    
+    /// Iterate history by descending timestamp (later insertions first on ties)
+    fn history_newest_first(&self) -> impl Iterator<Item = &MobileTransaction> {
+        let mut history: Vec<&MobileTransaction> = self.transaction_history.iter().rev().collect();
+        history.sort_by_key(|tx| std::cmp::Reverse(tx.timestamp));
+        history.into_iter()
+    }
+    
+    /// Issue a payment request to the active account, expiring `ttl_secs` from now
+    ///
+    /// A `ttl_secs` of 0 creates a request that never expires; an empty label is omitted.
+    pub fn create_payment_request(&mut self, amount: u64, label: &str, ttl_secs: u64) -> Result<PaymentRequest> {
+        if amount == 0 {
+            return Err(MobileError::InvalidAmount);
+        }
+        
+        let now = SystemClock.unix_secs();
+        let request = PaymentRequest {
+            address: self.active_account()?.address().to_string(),
+            amount: Some(amount),
+            label: (!label.is_empty()).then(|| label.to_string()),
+            message: None,
+            expires_at: (ttl_secs > 0).then(|| now.saturating_add(ttl_secs)),
+            fulfilled_by: None,
+        };
+        self.payment_requests.push(request.clone());
+        Ok(request)
+    }
+    
+    /// Payment requests issued by this wallet, oldest first
+    pub fn payment_requests(&self) -> &[PaymentRequest] {
+        &self.payment_requests
+    }
+    
+    /// Add a contact with a unique label
+    pub fn add_contact(&mut self, label: String, address: String, note: Option<String>) -> Result<()> {
+        let contact = Contact::new(label, address, note)?;
+        
+        if Network::from_address(&contact.address) != Some(self.network) {
+            return Err(MobileError::NetworkMismatch);
+        }
+        
+        if self.contacts.iter().any(|existing| existing.label == contact.label) {
+            return Err(MobileError::DuplicateContact);
+        }
+        
+        self.contacts.push(contact);
+        Ok(())
+    }
+    
+    /// Remove the contact with `label`
+    pub fn remove_contact(&mut self, label: &str) -> Result<()> {
+        let position = self.contacts
+            .iter()
+            .position(|contact| contact.label == label)
+            .ok_or(MobileError::ContactNotFound)?;
+        self.contacts.remove(position);
+        Ok(())
+    }
+    
+    /// Get all contacts
+    pub fn list_contacts(&self) -> &[Contact] {
+        &self.contacts
+    }
+    
+    /// Look up the address saved under `label`
+    pub fn resolve_contact(&self, label: &str) -> Option<&str> {
+        self.contacts
+            .iter()
+            .find(|contact| contact.label == label)
+            .map(|contact| contact.address.as_str())
+    }
+    
+    /// Export mnemonic
+    pub fn export_mnemonic(&self, password: &str) -> Result<Zeroizing<String>> {
+        self.keystore.export_mnemonic(password)
+    }
+    
+    /// Pick `count` distinct word positions (0-based) to quiz the user on after a backup
+    pub fn challenge_positions(&self, password: &str, count: usize) -> Result<Vec<usize>> {
+        let phrase = self.keystore.export_mnemonic(password)?;
+        Ok(mnemonic::challenge_positions(phrase.split(' ').count(), count))
+    }
+    
+    /// Check one word of the backed-up mnemonic, without revealing the phrase
+    ///
+    /// `position` is 0-based; the word is compared ignoring case and surrounding whitespace.
+    pub fn verify_backup_word(&self, password: &str, position: usize, word: &str) -> Result<bool> {
+        let phrase = self.keystore.export_mnemonic(password)?;
+        let expected = phrase
+            .split(' ')
+            .nth(position)
+            .ok_or(MobileError::InvalidWordPosition)?;
+        Ok(security::constant_time_eq(mnemonic::normalize(word).as_bytes(), expected.as_bytes()))
+    }
+    
+    /// Change the wallet password
+    pub fn change_password(
+        &mut self,
+        old_password: &str,
+        new_password: &str,
+        security: &SecurityManager,
+    ) -> Result<()> {
+        security.validate_password(new_password)?;
+        
+        // Re-encrypt copies of the imported keys and PIN hash, so a failure leaves everything under the old password
+        let mut imported_keys = self.imported_keys.clone();
+        for key in imported_keys.iter_mut() {
+            key.change_password(old_password, new_password)?;
+        }
+        let security_settings = self.security_settings
+            .as_ref()
+            .map(|settings| settings.reseal(old_password, new_password))
+            .transpose()?;
+        self.keystore.change_password(old_password, new_password)?;
+        self.imported_keys = imported_keys;
+        self.security_settings = security_settings;
+        Ok(())
+    }
+    
+    /// Encoding used by `save_to_path`
+    pub fn storage_format(&self) -> StorageFormat {
+        self.storage_format
+    }
+    
+    /// Choose the encoding used by `save_to_path` (JSON by default)
+    pub fn set_storage_format(&mut self, format: StorageFormat) {
+        self.storage_format = format;
+    }
+    
+    /// Keep a snapshot of `security`'s preferences, saved with the wallet from now on
+    pub fn store_security_settings(&mut self, security: &SecurityManager, password: &str) -> Result<()> {
+        // Make sure the PIN hash is sealed with the wallet password
+        self.keystore.export_mnemonic(password)?;
+        
+        self.security_settings = Some(security.export_settings(password)?);
+        Ok(())
+    }
+    
+    /// Security manager with the stored preferences, or the defaults when none were stored
+    pub fn restore_security_manager(&self, password: &str) -> Result<SecurityManager> {
+        match &self.security_settings {
+            Some(settings) => SecurityManager::from_settings(settings, password),
+            None => SecurityManager::new(),
+        }
+    }
+    
+    /// Save wallet to an encrypted file in its storage format
+    pub fn save_to_path(&self, path: &Path, password: &str) -> Result<()> {
+        // Make sure the file is sealed with the wallet password
+        self.keystore.export_mnemonic(password)?;
+        
+        let value = serde_json::to_value(self)
+            .map_err(|e| MobileError::SerializationError(e.to_string()))?;
+        let serialized = Zeroizing::new(storage::encode(value, self.storage_format)?);
+        let encrypted = Keystore::seal(&serialized, password)?;
+        
+        // Write to a temporary file and rename it so a crash never leaves a partial wallet
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+        
+        let mut file = std::fs::File::create(&tmp_path)
+            .map_err(|e| MobileError::StorageError(e.to_string()))?;
+        file.write_all(&encrypted)
+            .and_then(|_| file.sync_all())
+            .map_err(|e| MobileError::StorageError(e.to_string()))?;
+        std::fs::rename(&tmp_path, path)
+            .map_err(|e| MobileError::StorageError(e.to_string()))?;
+        
+        Ok(())
+    }
+    
+    /// Export the keystore as a versioned, password-protected JSON document
+    pub fn export_keystore_json(&self, password: &str) -> Result<String> {
+        self.keystore.export_json_with_keys(password, self.network, &self.imported_keys)
+    }
+    
+    /// Restore a wallet from a document produced by `export_keystore_json`
+    pub fn import_keystore_json(json: &str, password: &str) -> Result<Self> {
+        let (keystore, network, keys) = Keystore::import_json_with_keys(json, password)?;
+        let mut wallet = Self::from_keystore(keystore, password, network)?;
+        
+        // Keys from older documents are in the keystore's slots, which come first
+        let mut imported_keys = wallet.keystore.split_imported_keys();
+        imported_keys.extend(keys);
+        
+        // Recreate the accounts of imported keys
+        for (slot, key) in imported_keys.iter().enumerate() {
+            let public_key = public_key_for(&key.private_key(password)?)?;
+            wallet.accounts.push(Account::imported(slot as u32, public_key, network));
+        }
+        wallet.imported_keys = imported_keys;
+        
+        Ok(wallet)
+    }
+    
+    /// Load wallet from an encrypted file, detecting its storage format
+    pub fn load_from_path(path: &Path, password: &str) -> Result<Self> {
+        let encrypted = std::fs::read(path)
+            .map_err(|e| MobileError::StorageError(e.to_string()))?;
+        let serialized = Keystore::unseal(&encrypted, password)?;
+        
+        let (value, format) = storage::decode(&serialized)?;
+        let mut wallet = Self::from_value(value)?;
+        wallet.storage_format = format;
+        Ok(wallet)
+    }
+    
+    /// Deserialize a wallet, upgrading older formats to the current one
+    pub fn from_json(json: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)
+            .map_err(|e| MobileError::SerializationError(e.to_string()))?;
+        Self::from_value(value)
+    }
+    
+    /// Deserialize a wallet value, upgrading older formats to the current one
+    fn from_value(value: serde_json::Value) -> Result<Self> {
+        serde_json::from_value(migrate(value)?)
+            .map_err(|e| MobileError::SerializationError(e.to_string()))
+    }
+}
+
+/// Key derived from the wallet seed at `path`
+struct HdKey<'a> {
+    keystore: &'a Keystore,
+    path: DerivationPath,
+}
+
+impl KeyProvider for HdKey<'_> {
+    fn secret_key(&self, password: &str) -> Result<SecretKey> {
+        let seed = self.keystore.seed(password)?;
+        let key = ExtendedPrivateKey::from_seed(&seed[..])?
+            .derive_path(&self.path.to_string())?;
+        Ok(*key.secret_key())
+    }
+}
+
+/// Imported key held in a keystore slot
+struct KeystoreSlot<'a> {
+    keystore: &'a Keystore,
+    slot: u32,
+}
+
+impl KeyProvider for KeystoreSlot<'_> {
+    fn secret_key(&self, password: &str) -> Result<SecretKey> {
+        let key_bytes = self.keystore.private_key(self.slot, password)?;
+        SecretKey::from_slice(&key_bytes[..])
+            .map_err(|_| MobileError::KeystoreError("Corrupted private key".to_string()))
+    }
+}
+
+/// Compressed public key of a raw secp256k1 private key
+fn public_key_for(key_bytes: &[u8; 32]) -> Result<Vec<u8>> {
+    use secp256k1::{PublicKey, Secp256k1};
+    
+    let mut secret_key = SecretKey::from_slice(key_bytes)
+        .map_err(|_| MobileError::CryptoError("Invalid private key".to_string()))?;
+    let public_key = PublicKey::from_secret_key(&Secp256k1::new(), &secret_key)
+        .serialize()
+        .to_vec();
+    secret_key.non_secure_erase();
+    
+    Ok(public_key)
+}
+
+/// Upgrade a serialized wallet to `WALLET_SCHEMA_VERSION`, one version at a time
+fn migrate(mut value: serde_json::Value) -> Result<serde_json::Value> {
+    let version = value
+        .get("schema_version")
+        .map(|version| {
+            version
+                .as_u64()
+                .map(|version| u32::try_from(version).unwrap_or(u32::MAX))
+                .ok_or_else(|| MobileError::SerializationError("Invalid schema version".to_string()))
+        })
+        .transpose()?
+        .unwrap_or(1);
+    if version > WALLET_SCHEMA_VERSION {
+        return Err(MobileError::UnsupportedSchemaVersion(version));
+    }
+    
+    let wallet = value
+        .as_object_mut()
+        .ok_or_else(|| MobileError::SerializationError("Wallet is not an object".to_string()))?;
+    
+    if version < 2 {
+        migrate_v1(wallet)?;
+    }
+    if version < 3 {
+        migrate_v2(wallet)?;
+    }
+    
+    wallet.insert("schema_version".to_string(), WALLET_SCHEMA_VERSION.into());
+    Ok(value)
+}
+
+/// v1 → v2: watch-only accounts were flagged with `watch_only` before accounts had a `kind`
+fn migrate_v1(wallet: &mut serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    let Some(accounts) = wallet.get_mut("accounts").and_then(serde_json::Value::as_array_mut) else {
+        return Ok(());
+    };
+    
+    for account in accounts.iter_mut().filter_map(serde_json::Value::as_object_mut) {
+        let watch_only = account.remove("watch_only").and_then(|flag| flag.as_bool());
+        if watch_only == Some(true) && !account.contains_key("kind") {
+            let kind = serde_json::to_value(AccountKind::WatchOnly)
+                .map_err(|e| MobileError::SerializationError(e.to_string()))?;
+            account.insert("kind".to_string(), kind);
+        }
+    }
+    Ok(())
+}
+
+/// v2 → v3: imported keys were held in the keystore before each was encrypted on its own
+fn migrate_v2(wallet: &mut serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    let Some(keystore) = wallet.get("keystore") else {
+        return Ok(());
+    };
+    
+    let mut keystore: Keystore = serde_json::from_value(keystore.clone())
+        .map_err(|e| MobileError::SerializationError(e.to_string()))?;
+    let imported_keys = keystore.split_imported_keys();
+    if imported_keys.is_empty() {
+        return Ok(());
+    }
+    
+    let to_value = |value: serde_json::Result<serde_json::Value>| {
+        value.map_err(|e| MobileError::SerializationError(e.to_string()))
+    };
+    wallet.insert("keystore".to_string(), to_value(serde_json::to_value(&keystore))?);
+    wallet.insert("imported_keys".to_string(), to_value(serde_json::to_value(&imported_keys))?);
+    Ok(())
+}
+
+/// Serde default for wallets saved before the schema version was recorded
+fn legacy_schema_version() -> u32 {
+    1
+}
+
+/// Serde default for wallets saved before they had names
+fn default_name() -> String {
+    DEFAULT_WALLET_NAME.to_string()
+}
+
+/// Serde default for wallets saved before the fee rate was tracked
+fn default_fee_rate() -> u64 {
+    fee::DEFAULT_FEE_RATE
+}
+
+/// Serde default for wallets saved before the dust limit was configurable
+fn default_dust_limit() -> u64 {
+    transaction::DEFAULT_DUST_LIMIT
+}
+
+/// Serde default for wallets saved before the account limit existed
+fn default_max_accounts() -> usize {
+    DEFAULT_MAX_ACCOUNTS
+}
+
+// UUID support
+mod uuid {
+    use std::fmt;
+    
+    pub struct Uuid([u8; 16]);
+    
+    impl Uuid {
+        pub fn new_v4() -> Self {
+            use rand::Rng;
+            let mut rng = rand::thread_rng();
+            let mut bytes = [0u8; 16];
+            rng.fill(&mut bytes);
+            Uuid(bytes)
+        }
+    }
+    
+    impl fmt::Display for Uuid {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(
+                f,
+                "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+                self.0[0], self.0[1], self.0[2], self.0[3],
+                self.0[4], self.0[5],
+                self.0[6], self.0[7],
+                self.0[8], self.0[9],
+                self.0[10], self.0[11], self.0[12], self.0[13], self.0[14], self.0[15]
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn recipient(network: Network) -> String {
+        Account::address_from_public_key(&[2u8; 33], network)
+    }
+    
+    #[test]
+    fn test_wallet_creation() {
+        let wallet = MobileWallet::new("password123");
+        assert!(wallet.is_ok());
+    }
+    
+    #[test]
+    fn test_wallet_balance() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(1000);
+        assert_eq!(wallet.balance(), 1000);
+    }
+    
+    #[test]
+    fn test_wallets_from_different_mnemonics_have_different_addresses() {
+        let first = MobileWallet::from_mnemonic(
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            "password123",
+        ).unwrap();
+        let second = MobileWallet::from_mnemonic(
+            "letter advice cage absurd amount doctor acoustic avoid letter advice cage above",
+            "password123",
+        ).unwrap();
+        
+        assert_ne!(first.active_account().unwrap().address(), second.active_account().unwrap().address());
+    }
+    
+    #[test]
+    fn test_known_test_mnemonic_is_flagged() {
+        let weak = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        assert!(matches!(MobileWallet::from_mnemonic(weak, "password123"), Err(MobileError::WeakMnemonic)));
+        assert!(MobileWallet::from_mnemonic_allowing_weak(weak, "password123", Network::Testnet).is_ok());
+        
+        let random = crate::mnemonic::generate(12).unwrap();
+        assert!(MobileWallet::from_mnemonic(&random, "password123").is_ok());
+    }
+    
+    #[test]
+    fn test_preview_addresses_match_import() {
+        let phrase = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let network = Network::Testnet;
+        let coin_type = network.coin_type();
+        
+        let bip44 = DerivationPath::new(Purpose::Bip44, coin_type, 0);
+        let preview = MobileWallet::preview_addresses(phrase, "", bip44, network, 3).unwrap();
+        assert_eq!(preview.len(), 3);
+        let mut wallet = MobileWallet::from_mnemonic_with_network(phrase, "password123", network).unwrap();
+        assert_eq!(preview[0], wallet.accounts()[0].address());
+        
+        let bip84 = DerivationPath::new(Purpose::Bip84, coin_type, 0);
+        let preview = MobileWallet::preview_addresses(phrase, "", bip84, network, 2).unwrap();
+        for index in 0..2 {
+            wallet
+                .add_account_with_path(DerivationPath::new(Purpose::Bip84, coin_type, index), "password123")
+                .unwrap();
+        }
+        let imported: Vec<&str> = wallet.accounts()[1..].iter().map(|account| account.address()).collect();
+        assert_eq!(preview, imported);
+        
+        // A passphrase changes every address
+        let other = MobileWallet::preview_addresses(phrase, "extra", bip84, network, 2).unwrap();
+        assert_ne!(other[0], preview[0]);
+        
+        assert!(MobileWallet::preview_addresses("legal winner", "", bip44, network, 1).is_err());
+        assert!(matches!(
+            MobileWallet::preview_addresses(phrase, "", bip44, Network::Mainnet, 1),
+            Err(MobileError::NetworkMismatch)
+        ));
+    }
+    
+    #[test]
+    fn test_verify_backup_word() {
+        let wallet = MobileWallet::from_mnemonic(
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            "password123",
+        ).unwrap();
+        
+        assert!(wallet.verify_backup_word("password123", 0, "legal").unwrap());
+        assert!(wallet.verify_backup_word("password123", 11, " Yellow ").unwrap());
+        assert!(!wallet.verify_backup_word("password123", 2, "winner").unwrap());
+        assert!(matches!(wallet.verify_backup_word("password123", 12, "legal"), Err(MobileError::InvalidWordPosition)));
+        assert!(matches!(wallet.verify_backup_word("wrongpassword", 0, "legal"), Err(MobileError::CryptoError(_))));
+        
+        let positions = wallet.challenge_positions("password123", 3).unwrap();
+        assert_eq!(positions.len(), 3);
+        assert!(positions.iter().all(|&position| position < 12));
+    }
+    
+    #[test]
+    fn test_messy_mnemonic_matches_clean_import() {
+        let clean = MobileWallet::from_mnemonic(
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            "password123",
+        ).unwrap();
+        let messy = MobileWallet::from_mnemonic(
+            "  Legal winner thank  year wave\nsausage worth useful\tlegal winner thank Yellow\n",
+            "password123",
+        ).unwrap();
+        
+        assert_eq!(messy.active_account().unwrap().address(), clean.active_account().unwrap().address());
+        assert_eq!(messy.export_mnemonic("password123").unwrap(), clean.export_mnemonic("password123").unwrap());
+    }
+    
+    #[test]
+    fn test_same_mnemonic_derives_same_accounts() {
+        let mnemonic = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let mut first = MobileWallet::from_mnemonic(mnemonic, "password123").unwrap();
+        let mut second = MobileWallet::from_mnemonic(mnemonic, "password456").unwrap();
+        first.add_account().unwrap();
+        second.add_account().unwrap();
+        
+        assert_eq!(first.accounts()[1].address(), second.accounts()[1].address());
+        assert_ne!(first.accounts()[0].address(), first.accounts()[1].address());
+    }
+    
+    #[test]
+    fn test_same_index_under_two_purposes() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let coin_type = wallet.network().coin_type();
+        wallet
+            .add_account_with_path(DerivationPath::new(Purpose::Bip84, coin_type, 0), "password123")
+            .unwrap();
+        wallet
+            .add_account_with_path(DerivationPath::new(Purpose::Bip49, coin_type, 0), "password123")
+            .unwrap();
+        
+        let addresses: Vec<&str> = wallet.accounts().iter().map(|account| account.address()).collect();
+        assert_eq!(addresses.len(), 3);
+        assert_ne!(addresses[0], addresses[1]);
+        assert_ne!(addresses[1], addresses[2]);
+        assert_ne!(addresses[0], addresses[2]);
+        assert_eq!(wallet.accounts()[1].path().unwrap().to_string(), format!("m/84'/{}'/0'/0/0", coin_type));
+        
+        // The BIP44 chain keeps its own numbering
+        wallet.add_account().unwrap();
+        assert_eq!(wallet.accounts()[3].index(), 1);
+        
+        assert!(matches!(
+            wallet.add_account_with_path(DerivationPath::new(Purpose::Bip84, coin_type, 0), "password123"),
+            Err(MobileError::DuplicateAccount)
+        ));
+        assert!(matches!(
+            wallet.add_account_with_path(DerivationPath::new(Purpose::Bip84, coin_type + 1, 0), "password123"),
+            Err(MobileError::NetworkMismatch)
+        ));
+    }
+    
+    #[test]
+    fn test_sign_with_custom_path_account() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let path = DerivationPath::parse(&format!("m/84'/{}'/0'/0/5", wallet.network().coin_type())).unwrap();
+        wallet.add_account_with_path(path, "password123").unwrap();
+        wallet.set_active_account(1).unwrap();
+        wallet.set_balance(10_000);
+        
+        let mut tx = wallet
+            .create_transaction(&recipient(wallet.network()), 1000, 10)
+            .unwrap();
+        wallet.sign_transaction(&mut tx, "password123").unwrap();
+        assert!(tx.verify(wallet.active_account().unwrap().public_key()));
+    }
+    
+    #[test]
+    fn test_scan_for_accounts_within_gap_limit() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let mut server = mockito::Server::new();
+        
+        // Indices 0 and 2 have history; 1, 3 and 4 don't
+        let mut mocks = Vec::new();
+        for index in 0..5 {
+            let address = Account::new(index, &wallet.account_key, wallet.network())
+                .unwrap()
+                .address()
+                .to_string();
+            let body = if index == 0 || index == 2 {
+                format!(
+                    r#"{{"transactions": [{{"id": "tx_{}", "from": "silver_sender", "to": "{}",
+                        "amount": 5000, "fee": 10, "status": "Confirmed", "timestamp": 1700000000}}]}}"#,
+                    index, address
+                )
+            } else {
+                r#"{"transactions": []}"#.to_string()
+            };
+            let mock = server
+                .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+                .with_status(200)
+                .with_body(body)
+                .expect(1)
+                .create();
+            mocks.push(mock);
+        }
+        
+        // Change index 0 has history too
+        for index in 0..3 {
+            let address = wallet.change_address(index).unwrap();
+            let body = if index == 0 {
+                format!(
+                    r#"{{"transactions": [{{"id": "tx_change", "from": "silver_sender", "to": "{}",
+                        "amount": 5000, "fee": 10, "status": "Confirmed", "timestamp": 1700000000}}]}}"#,
+                    address
+                )
+            } else {
+                r#"{"transactions": []}"#.to_string()
+            };
+            let mock = server
+                .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+                .with_status(200)
+                .with_body(body)
+                .expect(1)
+                .create();
+            mocks.push(mock);
+        }
+        
+        let sync = SyncManager::with_node_url(&server.url()).unwrap();
+        let report = wallet.scan_for_accounts(&sync, 2).unwrap();
+        assert_eq!(report.added, 2);
+        assert!(report.warnings.is_empty());
+        
+        let indices: Vec<(u32, bool)> = wallet
+            .accounts()
+            .iter()
+            .map(|account| (account.index(), account.is_change()))
+            .collect();
+        assert_eq!(indices, vec![(0, false), (2, false), (0, true)]);
+        assert_eq!(wallet.next_change_address().unwrap(), wallet.change_address(1).unwrap());
+        for mock in mocks {
+            mock.assert();
+        }
+    }
+    
+    #[test]
+    fn test_scan_warns_of_activity_beyond_gap_limit() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let mut server = mockito::Server::new();
+        
+        // Only index 25 has history, past a gap limit of 20
+        let mut mocks = Vec::new();
+        for index in 0..=25 {
+            let address = Account::new(index, &wallet.account_key, wallet.network())
+                .unwrap()
+                .address()
+                .to_string();
+            let body = if index == 25 {
+                format!(
+                    r#"{{"transactions": [{{"id": "tx_25", "from": "silver_sender", "to": "{}",
+                        "amount": 5000, "fee": 10, "status": "Confirmed", "timestamp": 1700000000}}]}}"#,
+                    address
+                )
+            } else {
+                r#"{"transactions": []}"#.to_string()
+            };
+            let mock = server
+                .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+                .with_status(200)
+                .with_body(body)
+                .expect(1)
+                .create();
+            mocks.push(mock);
+        }
+        for index in 0..20 {
+            let address = wallet.change_address(index).unwrap();
+            let mock = server
+                .mock("GET", format!("/accounts/{}/transactions", address).as_str())
+                .with_status(200)
+                .with_body(r#"{"transactions": []}"#)
+                .expect(1)
+                .create();
+            mocks.push(mock);
+        }
+        
+        let sync = SyncManager::with_node_url(&server.url()).unwrap();
+        let report = wallet.scan_for_accounts(&sync, 20).unwrap();
+        assert_eq!(report.added, 0);
+        assert_eq!(report.warnings, vec![ScanWarning::ActivityBeyondGap { index: 25 }]);
+        assert_eq!(wallet.accounts().len(), 1);
+        for mock in mocks {
+            mock.assert();
+        }
+    }
+    
+    #[test]
+    fn test_batch_transaction_two_outputs() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(10_000);
+        let first = recipient(wallet.network());
+        let second = Account::address_from_public_key(&[3u8; 33], wallet.network());
+        
+        let mut tx = wallet
+            .create_batch_transaction(&[(first.clone(), 1000), (second.clone(), 2500)], 10)
+            .unwrap();
+        assert_eq!(tx.amount, 3500);
+        assert_eq!(tx.total().unwrap(), 3510);
+        assert_eq!(
+            tx.recipients(),
+            vec![
+                TransactionOutput { address: first, amount: 1000 },
+                TransactionOutput { address: second, amount: 2500 },
+            ]
+        );
+        
+        // Every output is covered by the signature
+        wallet.sign_transaction(&mut tx, "password123").unwrap();
+        assert!(tx.verify(wallet.active_account().unwrap().public_key()));
+        tx.outputs[1].amount = 2000;
+        assert!(!tx.verify(wallet.active_account().unwrap().public_key()));
+    }
+    
+    #[test]
+    fn test_batch_transaction_memo_and_rate() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_account_utxos(0, vec![Utxo { txid: "tx_a".to_string(), vout: 0, amount: 50_000 }]).unwrap();
+        let outputs = [
+            (recipient(wallet.network()), 1000),
+            (Account::address_from_public_key(&[3u8; 33], wallet.network()), 2500),
+        ];
+        
+        let tx = wallet
+            .create_batch_transaction_with_memo(&outputs, 10, Some("rent".to_string()))
+            .unwrap();
+        assert_eq!(tx.memo(), Some("rent"));
+        assert_eq!(tx.recipients().len(), 2);
+        
+        // The rate prices every output and the change address
+        let rate = FeeRate::from_units_per_byte(2);
+        let tx = wallet.create_batch_transaction_with_rate(&outputs, rate).unwrap();
+        assert_eq!(tx.amount, 3500);
+        assert!(tx.change_address.is_some());
+        assert!(tx.fee >= rate.fee_for(tx.estimated_size()));
+        assert_eq!(tx.change_address.unwrap(), wallet.change_address(1).unwrap());
+    }
+    
+    #[test]
+    fn test_batch_transaction_rejects_invalid_address() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(10_000);
+        
+        let outputs = [(recipient(wallet.network()), 1000), ("silver_not_an_address".to_string(), 1000)];
+        assert!(matches!(
+            wallet.create_batch_transaction(&outputs, 10),
+            Err(MobileError::InvalidAddress)
+        ));
+        assert!(matches!(wallet.create_batch_transaction(&[], 10), Err(MobileError::InvalidTransaction)));
+    }
+    
+    #[test]
+    fn test_batch_transaction_insufficient_for_sum() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(3000);
+        let outputs = [(recipient(wallet.network()), 1500), (recipient(wallet.network()), 1500)];
+        
+        // Each output fits alone; together with the fee they don't
+        assert!(wallet.create_transaction(&outputs[0].0, 1500, 10).is_ok());
+        assert!(matches!(
+            wallet.create_batch_transaction(&outputs, 10),
+            Err(MobileError::InsufficientBalance)
+        ));
+        
+        let overflow = [(recipient(wallet.network()), u64::MAX), (recipient(wallet.network()), 1000)];
+        assert!(matches!(
+            wallet.create_batch_transaction(&overflow, 10),
+            Err(MobileError::InvalidTransaction)
+        ));
+    }
+    
+    #[test]
+    fn test_sweep_transaction_spends_everything() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(10_000);
+        
+        let tx = wallet.create_sweep_transaction(&recipient(wallet.network()), 250).unwrap();
+        assert_eq!(tx.amount, 9750);
+        assert_eq!(tx.amount + tx.fee, wallet.balance());
+        
+        // With tracked outputs every one of them is spent, leaving no change
+        let utxos = vec![
+            Utxo { txid: "tx_a".to_string(), vout: 0, amount: 4000 },
+            Utxo { txid: "tx_b".to_string(), vout: 1, amount: 3000 },
+        ];
+        wallet.set_account_utxos(0, utxos).unwrap();
+        let tx = wallet.create_sweep_transaction(&recipient(wallet.network()), 250).unwrap();
+        assert_eq!(tx.amount + tx.fee, 7000);
+        assert_eq!(tx.inputs.len(), 2);
+        assert_eq!(tx.change, 0);
+    }
+    
+    #[test]
+    fn test_change_goes_to_fresh_internal_addresses() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_account_utxos(0, vec![
+            Utxo { txid: "tx_a".to_string(), vout: 0, amount: 50_000 },
+            Utxo { txid: "tx_b".to_string(), vout: 0, amount: 50_000 },
+        ]).unwrap();
+        let receive = wallet.active_account().unwrap().address().to_string();
+        
+        // No change, no change address
+        let exact = wallet.create_transaction(&recipient(Network::Mainnet), 49_990, 10).unwrap();
+        assert_eq!((exact.change, exact.change_address), (0, None));
+        assert_eq!(wallet.accounts().len(), 1);
+        
+        // Every transaction with change reserves the next address, recorded or not
+        let first = wallet.create_transaction(&recipient(Network::Mainnet), 1000, 10).unwrap();
+        let first_change = first.change_address.clone().unwrap();
+        assert_eq!(first_change, wallet.change_address(0).unwrap());
+        assert_ne!(first_change, receive);
+        
+        let second = wallet.create_transaction(&recipient(Network::Mainnet), 1000, 10).unwrap();
+        let second_change = second.change_address.clone().unwrap();
+        assert_eq!(second_change, wallet.change_address(1).unwrap());
+        assert_ne!(second_change, first_change);
+        assert_eq!(wallet.next_change_address().unwrap(), wallet.change_address(2).unwrap());
+        
+        // A change account tracks the change and signs when it is spent
+        let index = wallet.accounts()
+            .iter()
+            .position(|account| account.address() == first_change)
+            .unwrap();
+        assert!(wallet.accounts()[index].is_change());
+        assert!(wallet.owns_address(&first_change));
+        
+        wallet.set_account_utxos(index, vec![Utxo { txid: first.id.clone(), vout: 1, amount: first.change }]).unwrap();
+        wallet.set_active_account(index).unwrap();
+        let mut spend = wallet.create_transaction(&recipient(Network::Mainnet), 10_000, 10).unwrap();
+        wallet.sign_transaction(&mut spend, "password123").unwrap();
+        assert!(spend.verify(wallet.accounts()[index].public_key()));
+        assert_eq!(spend.change_address, Some(wallet.change_address(2).unwrap()));
+    }
+    
+    #[test]
+    fn test_imported_key_change_returns_to_sender() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.import_private_key(&"11".repeat(32), "password123").unwrap();
+        wallet.set_account_utxos(1, vec![Utxo { txid: "tx_a".to_string(), vout: 0, amount: 100_000 }]).unwrap();
+        wallet.set_active_account(1).unwrap();
+        
+        let tx = wallet.create_transaction(&recipient(Network::Mainnet), 1000, 10).unwrap();
+        assert!(tx.change > 0);
+        assert_eq!(tx.change_address, None);
+        assert_eq!(wallet.accounts().len(), 2);
+    }
+    
+    #[test]
+    fn test_sign_psbt_rejects_foreign_change_address() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_account_utxos(0, vec![Utxo { txid: "tx_a".to_string(), vout: 0, amount: 100_000 }]).unwrap();
+        let offline = wallet.clone();
+        
+        // The offline copy never reserved the change address but still recognises it
+        let tx = wallet.create_transaction(&recipient(Network::Mainnet), 1000, 10).unwrap();
+        let bytes = tx.to_psbt_bytes().unwrap();
+        assert!(offline.sign_psbt(&bytes, "password123").unwrap().verify(wallet.accounts()[0].public_key()));
+        
+        let mut tampered = MobileTransaction::from_psbt_bytes(&bytes).unwrap();
+        tampered.change_address = Some(Account::address_from_public_key(&[4u8; 33], Network::Mainnet));
+        assert!(matches!(
+            offline.sign_psbt(&tampered.to_psbt_bytes().unwrap(), "password123"),
+            Err(MobileError::UnknownChangeAddress)
+        ));
+        assert!(matches!(
+            wallet.sign_transaction(&mut tampered, "password123"),
+            Err(MobileError::UnknownChangeAddress)
+        ));
+    }
+    
+    #[test]
+    fn test_rate_fee_covers_change_address() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_account_utxos(0, vec![Utxo { txid: "tx_a".to_string(), vout: 0, amount: 50_000 }]).unwrap();
+        let rate = FeeRate::from_units_per_byte(3);
+        
+        let tx = wallet.create_transaction_with_rate(&recipient(Network::Mainnet), 1000, rate).unwrap();
+        let change_address = tx.change_address.clone().unwrap();
+        assert!(tx.change > 0);
+        
+        // The change address is signed, so it is priced too
+        let mut without_change_address = tx.clone();
+        without_change_address.change_address = None;
+        assert_eq!(
+            tx.estimated_size(),
+            without_change_address.estimated_size() + b"change_address".len() + 8 + change_address.len()
+        );
+        assert!(tx.fee >= rate.fee_for(tx.estimated_size()));
+    }
+    
+    #[test]
+    fn test_max_spendable() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let rate = FeeRate::from_units_per_byte(1);
+        wallet.set_balance(10_000);
+        
+        // The maximum plus its fee uses the whole balance, and one unit more is too much
+        let max = wallet.max_spendable(rate);
+        let tx = wallet.create_transaction_with_rate(&recipient(Network::Mainnet), max, rate).unwrap();
+        assert_eq!(tx.amount + tx.fee, 10_000);
+        assert!(matches!(
+            wallet.create_transaction_with_rate(&recipient(Network::Mainnet), max + 1, rate),
+            Err(MobileError::InsufficientBalance)
+        ));
+        
+        // An output worth less than its input fee isn't worth spending
+        wallet.set_account_utxos(0, vec![
+            Utxo { txid: "tx_a".to_string(), vout: 0, amount: 7000 },
+            Utxo { txid: "tx_b".to_string(), vout: 0, amount: 3000 },
+            Utxo { txid: "tx_c".to_string(), vout: 0, amount: 5 },
+        ]).unwrap();
+        let max = wallet.max_spendable(rate);
+        let tx = wallet.create_transaction_with_rate(&recipient(Network::Mainnet), max, rate).unwrap();
+        assert_eq!(tx.inputs.len(), 2);
+        assert_eq!(tx.amount + tx.fee, 10_000);
+        assert!(wallet.create_transaction_with_rate(&recipient(Network::Mainnet), max + 1, rate).is_err());
+    }
+    
+    #[test]
+    fn test_max_spendable_below_fee_and_dust() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let rate = FeeRate::from_units_per_byte(1);
+        
+        for balance in [0, 100, 800] {
+            wallet.set_balance(balance);
+            assert_eq!(wallet.max_spendable(rate), 0);
+        }
+    }
+    
+    #[test]
+    fn test_sweep_transaction_rejects_excessive_fee() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(1000);
+        
+        for fee in [1000, 5000] {
+            assert!(matches!(
+                wallet.create_sweep_transaction(&recipient(wallet.network()), fee),
+                Err(MobileError::InsufficientBalance)
+            ));
+        }
+    }
+    
+    #[test]
+    fn test_v1_wallet_migrates() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let watched = Account::address_from_public_key(&[4u8; 33], wallet.network());
+        wallet.add_watch_only(watched).unwrap();
+        
+        // Strip everything v1 wallets didn't record
+        let mut v1 = serde_json::to_value(&wallet).unwrap();
+        let object = v1.as_object_mut().unwrap();
+        for field in ["schema_version", "name", "fee_rate", "contacts", "dust_limit"] {
+            object.remove(field);
+        }
+        for account in object["accounts"].as_array_mut().unwrap() {
+            let account = account.as_object_mut().unwrap();
+            let watch_only = account.remove("kind") == Some(serde_json::json!("WatchOnly"));
+            account.insert("watch_only".to_string(), watch_only.into());
+            account.remove("utxos");
+            account.remove("path");
+        }
+        
+        let migrated = MobileWallet::from_json(&v1.to_string()).unwrap();
+        assert_eq!(migrated.schema_version(), WALLET_SCHEMA_VERSION);
+        assert_eq!(migrated.id(), wallet.id());
+        assert_eq!(migrated.name(), DEFAULT_WALLET_NAME);
+        assert_eq!(migrated.dust_limit(), transaction::DEFAULT_DUST_LIMIT);
+        assert_eq!(migrated.accounts()[0].kind(), AccountKind::Hd);
+        assert_eq!(migrated.accounts()[1].kind(), AccountKind::WatchOnly);
+        assert_eq!(migrated.accounts()[0].address(), wallet.accounts()[0].address());
+    }
+    
+    #[test]
+    fn test_newer_schema_rejected() {
+        let wallet = MobileWallet::new("password123").unwrap();
+        let mut json = serde_json::to_value(&wallet).unwrap();
+        json["schema_version"] = serde_json::json!(WALLET_SCHEMA_VERSION + 1);
+        
+        assert!(matches!(
+            MobileWallet::from_json(&json.to_string()),
+            Err(MobileError::UnsupportedSchemaVersion(version)) if version == WALLET_SCHEMA_VERSION + 1
+        ));
+    }
+    
+    #[test]
+    fn test_tampered_active_account_index() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(10_000);
+        let mut json = serde_json::to_value(&wallet).unwrap();
+        json["active_account"] = serde_json::json!(7);
+        let mut tampered = MobileWallet::from_json(&json.to_string()).unwrap();
+        
+        assert!(matches!(tampered.active_account(), Err(MobileError::AccountNotFound)));
+        assert_eq!(tampered.balance(), 0);
+        assert!(matches!(
+            tampered.create_transaction(&recipient(Network::Mainnet), 1000, 10),
+            Err(MobileError::AccountNotFound)
+        ));
+        
+        tampered.set_balance(500);
+        tampered.set_active_account(0).unwrap();
+        assert_eq!(tampered.balance(), 10_000);
+    }
+    
+    #[test]
+    fn test_wallet_name_is_serialized() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        assert_eq!(wallet.name(), DEFAULT_WALLET_NAME);
+        
+        wallet.set_name("Travel").unwrap();
+        let json = serde_json::to_string(&wallet).unwrap();
+        let restored: MobileWallet = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.name(), "Travel");
+    }
+    
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.dat");
+        
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.add_account().unwrap();
+        wallet.set_balance(5000);
+        wallet.save_to_path(&path, "password123").unwrap();
+        
+        let loaded = MobileWallet::load_from_path(&path, "password123").unwrap();
+        assert_eq!(loaded.id(), wallet.id());
+        assert_eq!(loaded.balance(), 5000);
+        assert_eq!(loaded.accounts().len(), 2);
+        assert_eq!(loaded.accounts()[1].address(), wallet.accounts()[1].address());
+    }
+    
+    #[test]
+    fn test_save_and_load_in_both_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.add_account().unwrap();
+        wallet.set_balance(5000);
+        let own = wallet.active_account().unwrap().address().to_string();
+        wallet.add_transaction(history_entry(&own, &recipient(Network::Mainnet), 1, TransactionStatus::Confirmed));
+        
+        let mut sizes = Vec::new();
+        for format in [StorageFormat::Json, StorageFormat::Bincode] {
+            let path = dir.path().join(format!("{:?}.dat", format));
+            wallet.set_storage_format(format);
+            wallet.save_to_path(&path, "password123").unwrap();
+            sizes.push(std::fs::metadata(&path).unwrap().len());
+            
+            let loaded = MobileWallet::load_from_path(&path, "password123").unwrap();
+            assert_eq!(loaded.storage_format(), format);
+            assert_eq!(loaded.id(), wallet.id());
+            assert_eq!(loaded.balance(), 5000);
+            assert_eq!(loaded.accounts()[1].address(), wallet.accounts()[1].address());
+            assert_eq!(loaded.transaction_history()[0].id, wallet.transaction_history()[0].id);
+            assert_eq!(loaded.export_mnemonic("password123").unwrap(), wallet.export_mnemonic("password123").unwrap());
+        }
+        assert!(sizes[1] < sizes[0], "bincode {} bytes vs JSON {} bytes", sizes[1], sizes[0]);
+    }
+    
+    #[test]
+    fn test_security_settings_survive_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.dat");
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        
+        let mut security = SecurityManager::new().unwrap();
+        security.enable_biometric().unwrap();
+        security.set_auto_lock_secs(60);
+        security.set_pin("2580").unwrap();
+        wallet.store_security_settings(&security, "password123").unwrap();
+        assert!(wallet.store_security_settings(&security, "wrongpassword").is_err());
+        
+        // The PIN hash only appears sealed
+        let serialized = serde_json::to_string(&wallet).unwrap();
+        assert!(!serialized.contains("$argon2"));
+        
+        wallet.save_to_path(&path, "password123").unwrap();
+        let loaded = MobileWallet::load_from_path(&path, "password123").unwrap();
+        let restored = loaded.restore_security_manager("password123").unwrap();
+        assert!(restored.is_biometric_enabled());
+        assert_eq!(restored.auto_lock_secs(), 60);
+        assert!(restored.verify_pin("2580").unwrap());
+        assert!(!restored.verify_pin("1357").unwrap());
+        
+        // Wallets saved without settings get the defaults
+        let fresh = MobileWallet::new("password123").unwrap().restore_security_manager("password123").unwrap();
+        assert!(!fresh.is_biometric_enabled());
+        assert!(!fresh.is_pin_enabled());
+    }
+    
+    #[test]
+    fn test_security_settings_follow_password_change() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let mut security = SecurityManager::new().unwrap();
+        security.set_pin("2580").unwrap();
+        wallet.store_security_settings(&security, "password123").unwrap();
+        
+        wallet.change_password("password123", "NewValidPass456", &security).unwrap();
+        assert!(matches!(wallet.restore_security_manager("password123"), Err(MobileError::CryptoError(_))));
+        let restored = wallet.restore_security_manager("NewValidPass456").unwrap();
+        assert!(restored.verify_pin("2580").unwrap());
+    }
+    
+    #[test]
+    fn test_fingerprint() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.dat");
+        let phrase = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let wallet = MobileWallet::from_mnemonic(phrase, "password123").unwrap();
+        
+        let fingerprint = wallet.fingerprint();
+        assert_eq!(fingerprint.len(), 8);
+        assert!(fingerprint.chars().all(|c| c.is_ascii_hexdigit()));
+        
+        // Stable across save/load and a second restore of the same seed
+        wallet.save_to_path(&path, "password123").unwrap();
+        assert_eq!(MobileWallet::load_from_path(&path, "password123").unwrap().fingerprint(), fingerprint);
+        let restored = MobileWallet::from_mnemonic(phrase, "otherpassword").unwrap();
+        assert_ne!(restored.id(), wallet.id());
+        assert_eq!(restored.fingerprint(), fingerprint);
+        
+        assert_ne!(MobileWallet::new("password123").unwrap().fingerprint(), fingerprint);
+    }
+    
+    #[test]
+    fn test_load_corrupt_wallet_is_serialization_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let wallet = MobileWallet::new("password123").unwrap();
+        let json = serde_json::to_string(&wallet).unwrap();
+        
+        let corrupt: [&[u8]; 4] = [
+            b"{\"schema_version\": 2, \"id\": ",
+            &json.as_bytes()[..json.len() / 2],
+            br#"{"schema_version": 2, "id": 7}"#,
+            b"SLVB\x01\xff\xff",
+        ];
+        for (index, contents) in corrupt.iter().enumerate() {
+            // Sealed with the right password, so only decoding can fail
+            let path = dir.path().join(format!("corrupt-{}.dat", index));
+            std::fs::write(&path, Keystore::seal(contents, "password123").unwrap()).unwrap();
+            let result = MobileWallet::load_from_path(&path, "password123");
+            assert!(matches!(result, Err(MobileError::SerializationError(_))), "case {}: {:?}", index, result.err());
+        }
+        
+        assert!(matches!(MobileWallet::from_json("[1, 2"), Err(MobileError::SerializationError(_))));
+        assert!(matches!(MobileWallet::from_json("[]"), Err(MobileError::SerializationError(_))));
+    }
+    
+    #[test]
+    fn test_load_with_wrong_password_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.dat");
+        
+        let wallet = MobileWallet::new("password123").unwrap();
+        wallet.save_to_path(&path, "password123").unwrap();
+        
+        assert!(MobileWallet::load_from_path(&path, "wrongpassword").is_err());
+    }
+    
+    #[test]
+    fn test_change_password() {
+        let security = SecurityManager::new().unwrap();
+        let mut wallet = MobileWallet::new("ValidPass123").unwrap();
+        let mnemonic = wallet.export_mnemonic("ValidPass123").unwrap();
+        
+        // Weak new passwords are rejected before touching the keystore
+        assert!(wallet.change_password("ValidPass123", "weak", &security).is_err());
+        
+        wallet.change_password("ValidPass123", "NewValidPass456", &security).unwrap();
+        assert_eq!(wallet.export_mnemonic("NewValidPass456").unwrap(), mnemonic);
+        assert!(wallet.export_mnemonic("ValidPass123").is_err());
+    }
+    
+    #[test]
+    fn test_sign_transaction() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(10000);
+        
+        let mut tx = wallet.create_transaction(&recipient(Network::Mainnet), 1000, 100).unwrap();
+        wallet.sign_transaction(&mut tx, "password123").unwrap();
+        assert!(tx.verify(wallet.active_account().unwrap().public_key()));
+        
+        tx.amount = 5000;
+        assert!(!tx.verify(wallet.active_account().unwrap().public_key()));
+    }
+    
+    #[test]
+    fn test_sign_transaction_wrong_password() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(10000);
+        
+        let mut tx = wallet.create_transaction(&recipient(Network::Mainnet), 1000, 100).unwrap();
+        assert!(wallet.sign_transaction(&mut tx, "wrongpassword").is_err());
+        assert!(!tx.is_signed());
+    }
+    
+    #[test]
+    fn test_testnet_wallet_rejects_mainnet_recipient() {
+        let mut wallet = MobileWallet::with_network("password123", Network::Testnet).unwrap();
+        wallet.set_balance(10000);
+        assert_eq!(wallet.network(), Network::Testnet);
+        assert!(wallet.active_account().unwrap().address().starts_with("tsilver_"));
+        
+        let result = wallet.create_transaction(&recipient(Network::Mainnet), 1000, 100);
+        assert!(matches!(result, Err(MobileError::NetworkMismatch)));
+        
+        assert!(wallet.create_transaction(&recipient(Network::Testnet), 1000, 100).is_ok());
+    }
+    
+    #[test]
+    fn test_create_transaction_rejects_invalid_address() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(10000);
+        
+        let result = wallet.create_transaction("silver_recipient", 1000, 100);
+        assert!(matches!(result, Err(MobileError::InvalidAddress)));
+    }
+    
+    #[test]
+    fn test_overpayment_warning() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(10_000_000_000);
+        let to = recipient(Network::Mainnet);
+        
+        let normal = wallet.validate_transaction(&to, 100_000, 1000).unwrap();
+        assert!(normal.warnings.is_empty());
+        
+        // A fee over a quarter of the amount, e.g. the two fields swapped
+        let swapped = wallet.validate_transaction(&to, 1000, 100_000).unwrap();
+        assert_eq!(swapped.warnings, vec![TransactionWarning::Overpayment]);
+        
+        // A small fraction of a large send, but over the absolute ceiling
+        let large = wallet.validate_transaction(&to, 5_000_000_000, transaction::MAX_REASONABLE_FEE + 1).unwrap();
+        assert_eq!(large.warnings, vec![TransactionWarning::Overpayment]);
+        
+        // Warnings never block sending
+        assert!(wallet.create_transaction(&to, 1000, 100_000).is_ok());
+    }
+    
+    #[test]
+    fn test_validate_transaction_preview() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(10_000);
+        let before = serde_json::to_string(&wallet).unwrap();
+        
+        let preview = wallet.validate_transaction(&recipient(Network::Mainnet), 4000, 100).unwrap();
+        assert_eq!(preview.from, wallet.active_account().unwrap().address());
+        assert_eq!(preview.total, 4100);
+        assert_eq!(preview.remaining_balance, 5900);
+        assert!(preview.warnings.is_empty());
+        
+        let expensive = wallet.validate_transaction(&recipient(Network::Mainnet), 1000, 200).unwrap();
+        assert_eq!(expensive.warnings, vec![TransactionWarning::HighFee]);
+        
+        // Nothing was reserved or recorded
+        assert_eq!(serde_json::to_string(&wallet).unwrap(), before);
+        assert_eq!(wallet.spendable_balance(), 10_000);
+    }
+    
+    #[test]
+    fn test_validate_transaction_insufficient_balance() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(1000);
+        
+        let result = wallet.validate_transaction(&recipient(Network::Mainnet), 1000, 10);
+        assert!(matches!(result, Err(MobileError::InsufficientBalance)));
+        assert!(matches!(
+            wallet.validate_transaction("silver_recipient", 100, 10),
+            Err(MobileError::InvalidAddress)
+        ));
+    }
+    
+    #[test]
+    fn test_create_transaction_rejects_overlong_recipient() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(10000);
+        
+        let valid = recipient(Network::Mainnet);
+        let result = wallet.create_transaction(&format!("{}{}", valid, "0".repeat(1 << 20)), 1000, 100);
+        assert!(matches!(result, Err(MobileError::AddressTooLong)));
+        assert!(wallet.create_transaction(&valid, 1000, 100).is_ok());
+    }
+    
+    #[test]
+    fn test_set_name_rejects_overlong_name() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let result = wallet.set_name(&"x".repeat(account::MAX_NAME_LEN + 1));
+        assert!(matches!(result, Err(MobileError::NameTooLong)));
+        assert!(matches!(wallet.set_name(""), Err(MobileError::EmptyName)));
+        assert_eq!(wallet.name(), DEFAULT_WALLET_NAME);
+    }
+    
+    #[test]
+    fn test_total_balance_across_accounts() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.add_account().unwrap();
+        wallet.add_account().unwrap();
+        
+        wallet.set_account_balance(0, 1000).unwrap();
+        wallet.set_account_balance(1, 2500).unwrap();
+        wallet.set_account_balance(2, 4000).unwrap();
+        
+        assert_eq!(wallet.total_balance(), 7500);
+        assert_eq!(wallet.account_balance(1).unwrap(), 2500);
         assert_eq!(wallet.balance(), 1000);
+        assert!(matches!(wallet.account_balance(3), Err(MobileError::AccountNotFound)));
+    }
+    
+    #[test]
+    fn test_switch_active_account() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.add_account().unwrap();
+        
+        wallet.set_active_account(1).unwrap();
+        assert_eq!(wallet.active_account_index(), 1);
+        assert_eq!(wallet.active_account().unwrap().address(), wallet.accounts()[1].address());
+        
+        assert!(matches!(wallet.set_active_account(2), Err(MobileError::AccountNotFound)));
+        assert_eq!(wallet.active_account_index(), 1);
+    }
+    
+    #[test]
+    fn test_remove_middle_account() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.add_account().unwrap();
+        wallet.add_account().unwrap();
+        let last_address = wallet.accounts()[2].address().to_string();
+        
+        wallet.set_active_account(2).unwrap();
+        wallet.remove_account(1).unwrap();
+        
+        assert_eq!(wallet.accounts().len(), 2);
+        assert_eq!(wallet.active_account_index(), 1);
+        assert_eq!(wallet.active_account().unwrap().address(), last_address);
+        
+        // The removed derivation index is not reused
+        wallet.add_account().unwrap();
+        assert_eq!(wallet.accounts()[2].index(), 3);
+    }
+    
+    #[test]
+    fn test_cannot_remove_only_account() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        
+        let result = wallet.remove_account(0);
+        assert!(matches!(result, Err(MobileError::CannotRemoveLastAccount)));
+        assert_eq!(wallet.accounts().len(), 1);
+    }
+    
+    #[test]
+    fn test_create_transaction_with_priority() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(100_000);
+        
+        let low = wallet.create_transaction_with_priority(&recipient(Network::Mainnet), 1000, FeePriority::Low).unwrap();
+        let high = wallet.create_transaction_with_priority(&recipient(Network::Mainnet), 1000, FeePriority::High).unwrap();
+        assert_eq!(low.fee, fee::estimate_fee(low.estimated_size(), FeePriority::Low));
+        assert!(high.fee > low.fee);
+        
+        wallet.set_fee_rate(fee::DEFAULT_FEE_RATE * 2);
+        let doubled = wallet.create_transaction_with_priority(&recipient(Network::Mainnet), 1000, FeePriority::Low).unwrap();
+        assert_eq!(doubled.fee, low.fee * 2);
+    }
+    
+    #[test]
+    fn test_create_transaction_with_fractional_rate() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(100_000);
+        
+        let rate = FeeRate::from_millis_per_byte(1250);
+        let tx = wallet.create_transaction_with_rate(&recipient(Network::Mainnet), 1000, rate).unwrap();
+        let size = tx.estimated_size() as u64;
+        assert_eq!(tx.fee, (size * 1250).div_ceil(1000));
+        
+        let result = wallet.create_transaction_with_rate(&recipient(Network::Mainnet), 1000, FeeRate::from_millis_per_byte(0));
+        assert!(matches!(result, Err(MobileError::InvalidFeeRate)));
+    }
+    
+    #[test]
+    fn test_create_transaction_overflow() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(u64::MAX);
+        
+        let result = wallet.create_transaction(&recipient(Network::Mainnet), u64::MAX, 1);
+        assert!(matches!(result, Err(MobileError::InvalidTransaction)));
+    }
+    
+    /// Build a transaction with a fixed timestamp and status
+    fn history_entry(from: &str, to: &str, timestamp: u64, status: TransactionStatus) -> MobileTransaction {
+        let mut tx = MobileTransaction::new(from.to_string(), to.to_string(), 1000 + timestamp, 10).unwrap();
+        tx.timestamp = timestamp;
+        tx.set_status(status);
+        tx
+    }
+    
+    #[test]
+    fn test_history_page() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let own = wallet.active_account().unwrap().address().to_string();
+        for timestamp in [3, 1, 5, 2, 4] {
+            wallet.add_transaction(history_entry(&own, &recipient(Network::Mainnet), timestamp, TransactionStatus::Confirmed));
+        }
+        
+        let timestamps = |page: Vec<MobileTransaction>| page.iter().map(|tx| tx.timestamp).collect::<Vec<_>>();
+        assert_eq!(timestamps(wallet.history_page(0, 2)), vec![5, 4]);
+        assert_eq!(timestamps(wallet.history_page(4, 2)), vec![1]);
+        assert!(wallet.history_page(5, 2).is_empty());
+        assert!(wallet.history_page(0, 0).is_empty());
+        assert_eq!(wallet.history_page(0, 100).len(), 5);
+    }
+    
+    #[test]
+    fn test_history_filter_status_and_direction() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let own = wallet.active_account().unwrap().address().to_string();
+        let other = recipient(Network::Mainnet);
+        wallet.add_transaction(history_entry(&own, &other, 1, TransactionStatus::Pending));
+        wallet.add_transaction(history_entry(&own, &other, 2, TransactionStatus::Confirmed));
+        wallet.add_transaction(history_entry(&other, &own, 3, TransactionStatus::Pending));
+        wallet.add_transaction(history_entry(&own, &other, 4, TransactionStatus::Pending));
+        
+        let filter = HistoryFilter {
+            status: Some(TransactionStatus::Pending),
+            direction: Some(Direction::Sent),
+            ..Default::default()
+        };
+        let matches = wallet.history_filter(&filter);
+        assert_eq!(matches.iter().map(|tx| tx.timestamp).collect::<Vec<_>>(), vec![4, 1]);
+    }
+    
+    #[test]
+    fn test_owns_address_across_accounts() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.add_account().unwrap();
+        let watched = Account::address_from_public_key(&[3u8; 33], Network::Mainnet);
+        wallet.add_watch_only(watched.clone()).unwrap();
+        
+        for account in wallet.accounts() {
+            assert!(wallet.owns_address(account.address()));
+        }
+        assert!(wallet.owns_address(&watched));
+        assert!(!wallet.owns_address(&recipient(Network::Mainnet)));
+        assert!(!wallet.owns_address(""));
+    }
+    
+    #[test]
+    fn test_self_send_is_classified() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.add_account().unwrap();
+        wallet.set_balance(10_000);
+        let first = wallet.accounts()[0].address().to_string();
+        let second = wallet.accounts()[1].address().to_string();
+        
+        let preview = wallet.validate_transaction(&second, 4000, 10).unwrap();
+        assert_eq!(preview.warnings, vec![TransactionWarning::SelfSend]);
+        
+        let internal = history_entry(&first, &second, 1, TransactionStatus::Confirmed);
+        let external = history_entry(&first, &recipient(Network::Mainnet), 2, TransactionStatus::Confirmed);
+        assert_eq!(wallet.transaction_direction(&internal), Some(Direction::SelfTransfer));
+        assert_eq!(wallet.transaction_direction(&external), Some(Direction::Sent));
+        wallet.add_transaction(internal);
+        wallet.add_transaction(external);
+        
+        let sent = HistoryFilter {
+            direction: Some(Direction::Sent),
+            ..Default::default()
+        };
+        let self_transfers = HistoryFilter {
+            direction: Some(Direction::SelfTransfer),
+            ..Default::default()
+        };
+        assert_eq!(wallet.history_filter(&sent).iter().map(|tx| tx.timestamp).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(wallet.history_filter(&self_transfers).iter().map(|tx| tx.timestamp).collect::<Vec<_>>(), vec![1]);
+        assert!(wallet.export_history_csv().lines().nth(1).unwrap().contains(",internal,"));
+    }
+    
+    #[test]
+    fn test_watch_only_account() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let watched = Account::address_from_public_key(&[3u8; 33], Network::Mainnet);
+        wallet.add_watch_only(watched.clone()).unwrap();
+        assert!(matches!(wallet.add_watch_only(watched.clone()), Err(MobileError::DuplicateAccount)));
+        
+        wallet.set_account_balance(1, 5000).unwrap();
+        wallet.set_active_account(1).unwrap();
+        assert!(wallet.active_account().unwrap().is_watch_only());
+        assert!(wallet.active_account().unwrap().public_key().is_empty());
+        assert_eq!(wallet.balance(), 5000);
+        
+        let result = wallet.create_transaction(&recipient(Network::Mainnet), 1000, 10);
+        assert!(matches!(result, Err(MobileError::WatchOnlyAccount)));
+        
+        let mut tx = MobileTransaction::new(watched, recipient(Network::Mainnet), 1000, 10).unwrap();
+        let result = wallet.sign_transaction(&mut tx, "password123");
+        assert!(matches!(result, Err(MobileError::WatchOnlyAccount)));
+        
+        // New HD accounts ignore the watch-only entry when picking an index
+        wallet.add_account().unwrap();
+        assert_eq!(wallet.accounts()[2].index(), 1);
+    }
+    
+    #[test]
+    fn test_passphrase_yields_different_accounts() {
+        let mnemonic = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let first = MobileWallet::from_mnemonic_with_passphrase(mnemonic, "first", "password123", Network::Mainnet).unwrap();
+        let second = MobileWallet::from_mnemonic_with_passphrase(mnemonic, "second", "password123", Network::Mainnet).unwrap();
+        let plain = MobileWallet::from_mnemonic(mnemonic, "password123").unwrap();
+        
+        assert_ne!(first.accounts()[0].address(), second.accounts()[0].address());
+        assert_ne!(first.accounts()[0].address(), plain.accounts()[0].address());
+        
+        // Signing uses the same passphrase-derived key as the address
+        let mut first = first;
+        first.set_balance(10_000);
+        let mut tx = first.create_transaction(&recipient(Network::Mainnet), 1000, 10).unwrap();
+        first.sign_transaction(&mut tx, "password123").unwrap();
+        assert!(tx.verify(first.active_account().unwrap().public_key()));
+    }
+    
+    #[test]
+    fn test_contacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.dat");
+        
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let address = recipient(Network::Mainnet);
+        wallet.add_contact("Landlord".to_string(), address.clone(), Some("rent".to_string())).unwrap();
+        assert_eq!(wallet.resolve_contact("Landlord"), Some(address.as_str()));
+        assert_eq!(wallet.resolve_contact("Nobody"), None);
+        
+        let result = wallet.add_contact("Landlord".to_string(), address.clone(), None);
+        assert!(matches!(result, Err(MobileError::DuplicateContact)));
+        
+        let result = wallet.add_contact("Testnet".to_string(), recipient(Network::Testnet), None);
+        assert!(matches!(result, Err(MobileError::NetworkMismatch)));
+        
+        // Contacts are saved with the wallet
+        wallet.save_to_path(&path, "password123").unwrap();
+        let mut loaded = MobileWallet::load_from_path(&path, "password123").unwrap();
+        assert_eq!(loaded.list_contacts(), wallet.list_contacts());
+        
+        loaded.remove_contact("Landlord").unwrap();
+        assert!(loaded.list_contacts().is_empty());
+        assert!(matches!(loaded.remove_contact("Landlord"), Err(MobileError::ContactNotFound)));
+    }
+    
+    #[test]
+    fn test_account_limit() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        assert_eq!(wallet.max_accounts(), DEFAULT_MAX_ACCOUNTS);
+        
+        wallet.set_max_accounts(4);
+        for _ in 1..4 {
+            wallet.add_account().unwrap();
+        }
+        assert_eq!(wallet.accounts().len(), 4);
+        
+        assert!(matches!(wallet.add_account(), Err(MobileError::AccountLimitReached)));
+        assert!(matches!(wallet.add_watch_only(recipient(Network::Mainnet)), Err(MobileError::AccountLimitReached)));
+        assert!(matches!(
+            wallet.import_private_key(&"11".repeat(32), "password123"),
+            Err(MobileError::AccountLimitReached)
+        ));
+        assert_eq!(wallet.accounts().len(), 4);
+        
+        // Removing one makes room again
+        wallet.remove_account(3).unwrap();
+        wallet.add_account().unwrap();
+    }
+    
+    #[test]
+    fn test_amount_and_dust_checks() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(100_000);
+        
+        let result = wallet.create_transaction(&recipient(Network::Mainnet), 0, 10);
+        assert!(matches!(result, Err(MobileError::InvalidAmount)));
+        
+        let result = wallet.create_transaction(&recipient(Network::Mainnet), transaction::DEFAULT_DUST_LIMIT - 1, 10);
+        assert!(matches!(result, Err(MobileError::DustAmount)));
+        
+        assert!(wallet.create_transaction(&recipient(Network::Mainnet), transaction::DEFAULT_DUST_LIMIT, 10).is_ok());
+        
+        wallet.set_dust_limit(5000);
+        let result = wallet.create_transaction(&recipient(Network::Mainnet), 1000, 10);
+        assert!(matches!(result, Err(MobileError::DustAmount)));
+    }
+    
+    #[test]
+    fn test_dust_change_is_added_to_fee() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(5000);
+        wallet.set_account_utxos(0, vec![Utxo { txid: "tx_a".to_string(), vout: 0, amount: 5000 }]).unwrap();
+        
+        // 400 left over is below the dust limit, so the fee absorbs it
+        let tx = wallet.create_transaction(&recipient(Network::Mainnet), 4500, 100).unwrap();
+        assert_eq!((tx.fee, tx.change), (500, 0));
+        assert_eq!(tx.total().unwrap(), 5000);
+        
+        let preview = wallet.validate_transaction(&recipient(Network::Mainnet), 4500, 100).unwrap();
+        assert_eq!((preview.fee, preview.dropped_change, preview.total), (500, 400, 5000));
+        
+        // Change at the dust limit is still returned
+        let tx = wallet.create_transaction(&recipient(Network::Mainnet), 5000 - 100 - 546, 100).unwrap();
+        assert_eq!((tx.fee, tx.change), (100, 546));
+        let preview = wallet.validate_transaction(&recipient(Network::Mainnet), 5000 - 100 - 546, 100).unwrap();
+        assert_eq!(preview.dropped_change, 0);
+    }
+    
+    #[test]
+    fn test_create_transaction_spends_utxos() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let utxos = vec![
+            Utxo { txid: "tx_a".to_string(), vout: 0, amount: 4000 },
+            Utxo { txid: "tx_b".to_string(), vout: 1, amount: 3000 },
+            Utxo { txid: "tx_c".to_string(), vout: 0, amount: 500 },
+        ];
+        wallet.set_balance(7500);
+        wallet.set_account_utxos(0, utxos).unwrap();
+        
+        let tx = wallet.create_transaction(&recipient(Network::Mainnet), 6000, 100).unwrap();
+        assert_eq!(tx.inputs.len(), 2);
+        assert_eq!(tx.change, 900);
+        
+        let result = wallet.create_transaction(&recipient(Network::Mainnet), 7500, 100);
+        assert!(matches!(result, Err(MobileError::InsufficientBalance)));
+        
+        // Recording the transaction removes the spent outputs
+        wallet.add_transaction(tx);
+        assert_eq!(wallet.active_account().unwrap().utxos().len(), 1);
+        assert_eq!(wallet.active_account().unwrap().utxos()[0].txid, "tx_c");
+    }
+    
+    #[test]
+    fn test_pending_send_reserves_balance() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(10_000);
+        
+        let tx = wallet.create_transaction(&recipient(Network::Mainnet), 6000, 100).unwrap();
+        let txid = tx.id.clone();
+        wallet.add_transaction(tx);
+        assert_eq!(wallet.balance(), 10_000);
+        assert_eq!(wallet.pending_balance(), 6100);
+        assert_eq!(wallet.spendable_balance(), 3900);
+        
+        // The reserved amount can't be spent twice
+        let result = wallet.create_transaction(&recipient(Network::Mainnet), 6000, 100);
+        assert!(matches!(result, Err(MobileError::InsufficientBalance)));
+        
+        // A failure releases the reservation
+        wallet.set_transaction_status(&txid, TransactionStatus::Failed);
+        assert_eq!(wallet.pending_balance(), 0);
+        assert_eq!(wallet.spendable_balance(), 10_000);
+        assert!(wallet.create_transaction(&recipient(Network::Mainnet), 6000, 100).is_ok());
+    }
+    
+    #[test]
+    fn test_failed_send_restores_outputs() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let utxos = vec![
+            Utxo { txid: "tx_a".to_string(), vout: 0, amount: 4000 },
+            Utxo { txid: "tx_b".to_string(), vout: 1, amount: 3000 },
+        ];
+        wallet.set_balance(7000);
+        wallet.set_account_utxos(0, utxos).unwrap();
+        
+        let tx = wallet.create_transaction(&recipient(Network::Mainnet), 3500, 100).unwrap();
+        let txid = tx.id.clone();
+        wallet.add_transaction(tx);
+        assert_eq!(wallet.active_account().unwrap().utxos().len(), 1);
+        
+        wallet.set_transaction_status(&txid, TransactionStatus::Failed);
+        assert_eq!(wallet.active_account().unwrap().utxos().len(), 2);
+    }
+    
+    #[test]
+    fn test_payment_request_expiry() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let now = SystemClock.unix_secs();
+        
+        let request = wallet.create_payment_request(5000, "Invoice 42", 600).unwrap();
+        assert_eq!(request.address, wallet.active_account().unwrap().address());
+        assert_eq!(request.label.as_deref(), Some("Invoice 42"));
+        assert!(request.is_open(now));
+        assert!(request.is_expired(now + 600));
+        assert!(request.to_uri().contains("expires="));
+        
+        let forever = wallet.create_payment_request(5000, "", 0).unwrap();
+        assert_eq!(forever.label, None);
+        assert!(!forever.is_expired(u64::MAX));
+        assert!(matches!(wallet.create_payment_request(0, "", 60), Err(MobileError::InvalidAmount)));
+    }
+    
+    #[test]
+    fn test_incoming_payment_fulfils_open_request() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let own = wallet.active_account().unwrap().address().to_string();
+        let payer = recipient(Network::Mainnet);
+        let request = wallet.create_payment_request(5000, "Invoice 42", 600).unwrap();
+        let expires_at = request.expires_at.unwrap();
+        
+        // Too little, or after the expiry, doesn't count
+        let mut short = history_entry(&payer, &own, expires_at - 10, TransactionStatus::Pending);
+        short.amount = 4999;
+        wallet.add_transaction(short);
+        let mut late = history_entry(&payer, &own, expires_at, TransactionStatus::Pending);
+        late.amount = 5000;
+        wallet.add_transaction(late);
+        assert_eq!(wallet.payment_requests()[0].fulfilled_by, None);
+        
+        let mut paid = history_entry(&payer, &own, expires_at - 5, TransactionStatus::Pending);
+        paid.amount = 5000;
+        let txid = paid.id.clone();
+        wallet.add_transaction(paid);
+        assert_eq!(wallet.payment_requests()[0].fulfilled_by.as_deref(), Some(txid.as_str()));
+        assert!(!wallet.payment_requests()[0].is_open(expires_at - 1));
+    }
+    
+    #[test]
+    fn test_only_live_incoming_payments_fulfil_requests() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.add_account().unwrap();
+        let own = wallet.active_account().unwrap().address().to_string();
+        let other_own = wallet.accounts()[1].address().to_string();
+        let payer = recipient(Network::Mainnet);
+        let request = wallet.create_payment_request(5000, "Invoice 42", 600).unwrap();
+        let timestamp = request.expires_at.unwrap() - 10;
+        
+        // Failed, cancelled, expired or replaced payments, and our own sends, don't count
+        for (index, status) in [
+            TransactionStatus::Failed,
+            TransactionStatus::Cancelled,
+            TransactionStatus::Expired,
+            TransactionStatus::Replaced,
+        ].into_iter().enumerate() {
+            let mut dead = history_entry(&payer, &own, timestamp - index as u64, status);
+            dead.amount = 5000;
+            dead.refresh_id();
+            wallet.add_transaction(dead);
+        }
+        let mut internal = history_entry(&other_own, &own, timestamp, TransactionStatus::Confirmed);
+        internal.amount = 5000;
+        internal.refresh_id();
+        wallet.add_transaction(internal);
+        assert_eq!(wallet.payment_requests()[0].fulfilled_by, None);
+        
+        // A batch pays the request through an output other than its first
+        let mut batch = history_entry(&payer, &recipient(Network::Mainnet), timestamp, TransactionStatus::Pending);
+        batch.outputs = vec![
+            TransactionOutput { address: recipient(Network::Mainnet), amount: 1000 },
+            TransactionOutput { address: own.clone(), amount: 5000 },
+        ];
+        batch.amount = 6000;
+        batch.refresh_id();
+        let txid = batch.id.clone();
+        wallet.add_transaction(batch);
+        assert_eq!(wallet.payment_requests()[0].fulfilled_by.as_deref(), Some(txid.as_str()));
+        
+        // And reopens if the payment later fails
+        wallet.set_transaction_status(&txid, TransactionStatus::Failed);
+        assert_eq!(wallet.payment_requests()[0].fulfilled_by, None);
+    }
+    
+    #[test]
+    fn test_find_transaction() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let own = wallet.active_account().unwrap().address().to_string();
+        let tx = history_entry(&own, &recipient(Network::Mainnet), 1, TransactionStatus::Confirmed);
+        let txid = tx.id.clone();
+        wallet.add_transaction(tx);
+        wallet.add_transaction(history_entry(&own, &recipient(Network::Mainnet), 2, TransactionStatus::Pending));
+        
+        let found = wallet.find_transaction(&txid).unwrap();
+        assert_eq!(found.id, txid);
+        assert_eq!(found.timestamp, 1);
+        assert!(wallet.find_transaction("tx_missing").is_none());
+    }
+    
+    #[test]
+    fn test_export_history_schema() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let own = wallet.active_account().unwrap().address().to_string();
+        let external = recipient(Network::Mainnet);
+        
+        let mut received = MobileTransaction::new(external.clone(), own.clone(), 150_000_000, 0).unwrap();
+        received.timestamp = 1_704_164_645;
+        received.set_status(TransactionStatus::Confirmed);
+        let mut sent = history_entry(&own, &external, 1_704_200_000, TransactionStatus::Pending);
+        sent.amount = 25_000_000;
+        sent.fee = 1000;
+        let replaced = history_entry(&own, &external, 1_704_300_000, TransactionStatus::Replaced);
+        let expired = history_entry(&own, &external, 1_704_400_000, TransactionStatus::Expired);
+        wallet.add_transaction(sent.clone());
+        wallet.add_transaction(received.clone());
+        wallet.add_transaction(replaced.clone());
+        wallet.add_transaction(expired.clone());
+        
+        let csv = wallet.export_history_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "id,timestamp,direction,amount,fee,status,counterparty");
+        assert_eq!(lines[1], format!("{},2024-01-02T03:04:05Z,received,1.5,0,confirmed,{}", received.id, external));
+        assert_eq!(lines[2], format!("{},2024-01-02T12:53:20Z,sent,0.25,0.00001,pending,{}", sent.id, external));
+        assert!(lines[3].starts_with(&format!("{},2024-01-03T16:40:00Z,sent,", replaced.id)));
+        assert!(lines[3].ends_with(&format!(",replaced,{}", external)));
+        assert!(lines[4].starts_with(&format!("{},2024-01-04T20:26:40Z,sent,", expired.id)));
+        assert!(lines[4].ends_with(&format!(",expired,{}", external)));
+        
+        let json: serde_json::Value = serde_json::from_str(&wallet.export_history_json().unwrap()).unwrap();
+        assert_eq!(json[0], serde_json::json!({
+            "id": received.id,
+            "timestamp": "2024-01-02T03:04:05Z",
+            "direction": "received",
+            "amount": "1.5",
+            "fee": "0",
+            "status": "confirmed",
+            "counterparty": external,
+        }));
+        assert_eq!(json[1]["direction"], "sent");
+        assert_eq!(json[2]["status"], "replaced");
+        assert_eq!(json[3]["status"], "expired");
+    }
+    
+    #[test]
+    fn test_cancel_pending_releases_reservation() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(7000);
+        wallet.set_account_utxos(0, vec![Utxo { txid: "tx_a".to_string(), vout: 0, amount: 7000 }]).unwrap();
+        
+        let tx = wallet.create_transaction(&recipient(Network::Mainnet), 5000, 100).unwrap();
+        let txid = tx.id.clone();
+        wallet.add_transaction(tx);
+        assert_eq!(wallet.pending_balance(), 5100);
+        assert!(wallet.active_account().unwrap().utxos().is_empty());
+        
+        wallet.cancel_pending(&txid).unwrap();
+        assert_eq!(wallet.transaction_history()[0].status, TransactionStatus::Cancelled);
+        assert_eq!(wallet.pending_balance(), 0);
+        assert_eq!(wallet.active_account().unwrap().utxos().len(), 1);
+        
+        // Cancelling twice or an unknown transaction fails
+        assert!(matches!(wallet.cancel_pending(&txid), Err(MobileError::AlreadyBroadcast)));
+        assert!(matches!(wallet.cancel_pending("tx_missing"), Err(MobileError::TransactionNotFound)));
+    }
+    
+    #[test]
+    fn test_transaction_ttl_expires_and_releases_inputs() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(7000);
+        wallet.set_account_utxos(0, vec![Utxo { txid: "tx_a".to_string(), vout: 0, amount: 7000 }]).unwrap();
+        wallet.set_transaction_ttl(Some(600));
+        
+        let tx = wallet.create_transaction(&recipient(Network::Mainnet), 5000, 100).unwrap();
+        assert_eq!(tx.valid_until, Some(tx.timestamp + 600));
+        let (txid, timestamp) = (tx.id.clone(), tx.timestamp);
+        wallet.add_transaction(tx);
+        
+        assert_eq!(wallet.expire_transactions(timestamp + 600), 0);
+        assert_eq!(wallet.expire_transactions(timestamp + 601), 1);
+        assert_eq!(wallet.find_transaction(&txid).unwrap().status, TransactionStatus::Expired);
+        assert_eq!(wallet.pending_balance(), 0);
+        assert_eq!(wallet.active_account().unwrap().utxos().len(), 1);
+        
+        wallet.set_transaction_ttl(None);
+        assert_eq!(wallet.create_transaction(&recipient(Network::Mainnet), 5000, 100).unwrap().valid_until, None);
+    }
+    
+    #[test]
+    fn test_failed_replaced_transaction_keeps_inputs_spent() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_account_utxos(0, vec![Utxo { txid: "tx_a".to_string(), vout: 0, amount: 7000 }]).unwrap();
+        
+        let original = wallet.create_transaction(&recipient(Network::Mainnet), 5000, 10).unwrap();
+        let mut bump = original.clone();
+        bump.fee = 50;
+        bump.refresh_id();
+        wallet.add_transaction(original.clone());
+        wallet.add_transaction(bump);
+        assert_eq!(wallet.find_transaction(&original.id).unwrap().status, TransactionStatus::Replaced);
+        
+        // The replacement still spends the input, so the replaced send failing must not return it
+        assert!(wallet.set_transaction_status(&original.id, TransactionStatus::Failed));
+        assert!(wallet.active_account().unwrap().utxos().is_empty());
+    }
+    
+    #[test]
+    fn test_cancel_pending_rejects_broadcast() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(10_000);
+        
+        let mut tx = wallet.create_transaction(&recipient(Network::Mainnet), 6000, 100).unwrap();
+        tx.broadcast = true;
+        let txid = tx.id.clone();
+        wallet.add_transaction(tx);
+        
+        assert!(matches!(wallet.cancel_pending(&txid), Err(MobileError::AlreadyBroadcast)));
+        assert_eq!(wallet.transaction_history()[0].status, TransactionStatus::Pending);
+        assert_eq!(wallet.pending_balance(), 6100);
+        
+        let confirmed = history_entry(&recipient(Network::Mainnet), &recipient(Network::Mainnet), 1, TransactionStatus::Confirmed);
+        let confirmed_id = confirmed.id.clone();
+        wallet.add_transaction(confirmed);
+        assert!(matches!(wallet.cancel_pending(&confirmed_id), Err(MobileError::AlreadyBroadcast)));
+    }
+    
+    #[test]
+    fn test_account_history_is_partitioned() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.add_account().unwrap();
+        let first = wallet.accounts()[0].address().to_string();
+        let second = wallet.accounts()[1].address().to_string();
+        let external = recipient(Network::Mainnet);
+        
+        wallet.add_transaction(history_entry(&first, &external, 1, TransactionStatus::Confirmed));
+        wallet.add_transaction(history_entry(&external, &second, 2, TransactionStatus::Confirmed));
+        wallet.add_transaction(history_entry(&first, &second, 3, TransactionStatus::Pending));
+        
+        let timestamps = |history: Vec<MobileTransaction>| history.iter().map(|tx| tx.timestamp).collect::<Vec<_>>();
+        assert_eq!(timestamps(wallet.account_history(0).unwrap()), vec![1, 3]);
+        assert_eq!(timestamps(wallet.account_history(1).unwrap()), vec![2, 3]);
+        assert_eq!(wallet.transaction_history().len(), 3);
+        assert!(matches!(wallet.account_history(2), Err(MobileError::AccountNotFound)));
+        
+        let history = wallet.transaction_history();
+        assert_eq!(wallet.transaction_accounts(&history[0]), vec![0]);
+        assert_eq!(wallet.transaction_accounts(&history[2]), vec![0, 1]);
+    }
+    
+    #[test]
+    fn test_upsert_transaction_deduplicates() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let own = wallet.active_account().unwrap().address().to_string();
+        let mut tx = history_entry(&recipient(Network::Mainnet), &own, 1, TransactionStatus::Pending);
+        
+        assert!(wallet.upsert_transaction(tx.clone()));
+        tx.set_status(TransactionStatus::Confirmed);
+        tx.set_confirmations(3);
+        assert!(!wallet.upsert_transaction(tx.clone()));
+        wallet.add_transaction(tx);
+        
+        let history = wallet.transaction_history();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].status, TransactionStatus::Confirmed);
+        assert_eq!(history[0].confirmations, 3);
+    }
+    
+    #[test]
+    fn test_create_transaction_with_memo() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(10_000);
+        
+        let tx = wallet
+            .create_transaction_with_memo(&recipient(Network::Mainnet), 1000, 10, Some("refund".to_string()))
+            .unwrap();
+        assert_eq!(tx.memo(), Some("refund"));
+        
+        let result = wallet.create_transaction_with_memo(
+            &recipient(Network::Mainnet),
+            1000,
+            10,
+            Some("x".repeat(transaction::MAX_MEMO_LEN + 1)),
+        );
+        assert!(matches!(result, Err(MobileError::MemoTooLong)));
+    }
+    
+    #[test]
+    fn test_balance_in_fiat() {
+        use crate::price::StaticPriceProvider;
+        
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(250_000_000);
+        
+        let provider = StaticPriceProvider::new().with_rate("USD", 1.5);
+        assert_eq!(wallet.balance_in_fiat(&provider, "USD").unwrap(), 3.75);
+        assert!(matches!(
+            wallet.balance_in_fiat(&provider, "JPY"),
+            Err(MobileError::UnsupportedCurrency(_))
+        ));
+    }
+    
+    #[test]
+    fn test_import_private_key() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let key_hex = "0000000000000000000000000000000000000000000000000000000000000001";
+        
+        assert!(matches!(wallet.import_private_key("abcd", "password123"), Err(MobileError::CryptoError(_))));
+        assert!(matches!(wallet.import_private_key(&"zz".repeat(32), "password123"), Err(MobileError::CryptoError(_))));
+        assert!(matches!(wallet.import_private_key(&"00".repeat(32), "password123"), Err(MobileError::CryptoError(_))));
+        
+        wallet.import_private_key(key_hex, "password123").unwrap();
+        assert!(matches!(wallet.import_private_key(key_hex, "password123"), Err(MobileError::DuplicateAccount)));
+        
+        // The key 1 maps to the secp256k1 generator point
+        let generator = hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798").unwrap();
+        let imported = &wallet.accounts()[1];
+        assert_eq!(imported.kind(), AccountKind::Imported);
+        assert_eq!(imported.public_key(), &generator[..]);
+        assert_eq!(imported.address(), Account::address_from_public_key(&generator, Network::Mainnet));
+        
+        wallet.set_active_account(1).unwrap();
+        wallet.set_balance(10_000);
+        let mut tx = wallet.create_transaction(&recipient(Network::Mainnet), 1000, 10).unwrap();
+        wallet.sign_transaction(&mut tx, "password123").unwrap();
+        assert!(tx.verify(&generator));
+        
+        // Imported keys don't take HD indices
+        wallet.add_account().unwrap();
+        assert_eq!(wallet.accounts()[2].index(), 1);
+    }
+    
+    #[test]
+    fn test_sign_with_hd_and_imported_keys() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.import_private_key(&"11".repeat(32), "password123").unwrap();
+        
+        // The imported key is encrypted on its own, not in the keystore
+        assert_eq!(wallet.imported_keys.len(), 1);
+        assert_eq!(wallet.keystore.imported_key_count(), 0);
+        
+        let security = SecurityManager::new().unwrap();
+        wallet.change_password("password123", "NewValidPass456", &security).unwrap();
+        
+        for index in 0..2 {
+            wallet.set_active_account(index).unwrap();
+            wallet.set_balance(10_000);
+            let mut tx = wallet.create_transaction(&recipient(Network::Mainnet), 1000, 10).unwrap();
+            assert!(wallet.sign_transaction(&mut tx, "password123").is_err());
+            wallet.sign_transaction(&mut tx, "NewValidPass456").unwrap();
+            assert!(tx.verify(wallet.accounts()[index].public_key()));
+        }
+    }
+    
+    #[test]
+    fn test_sign_message() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.import_private_key(&"11".repeat(32), "password123").unwrap();
+        
+        for index in 0..2 {
+            let public_key = wallet.accounts()[index].public_key().to_vec();
+            let signature = wallet.sign_message(index, b"prove ownership", "password123").unwrap();
+            assert!(message::verify_message(&public_key, b"prove ownership", &signature));
+            assert!(!message::verify_message(&public_key, b"prove something else", &signature));
+        }
+        
+        assert!(wallet.sign_message(0, b"prove ownership", "wrongpassword").is_err());
+        assert!(matches!(wallet.sign_message(5, b"prove ownership", "password123"), Err(MobileError::AccountNotFound)));
+    }
+    
+    #[test]
+    fn test_transaction_and_message_signatures_do_not_cross() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        wallet.set_balance(10_000);
+        let public_key = wallet.active_account().unwrap().public_key().to_vec();
+        
+        let mut tx = wallet.create_transaction(&recipient(Network::Mainnet), 1000, 10).unwrap();
+        wallet.sign_transaction(&mut tx, "password123").unwrap();
+        assert!(!message::verify_message(&public_key, &tx.canonical_bytes(), &tx.signature));
+        assert!(!message::verify_message(&public_key, &tx.signing_hash(), &tx.signature));
+        
+        // Nor does a message signature over the transaction bytes authorise the transaction
+        tx.signature = wallet.sign_message(0, &tx.canonical_bytes(), "password123").unwrap();
+        assert!(!tx.verify(&public_key));
+    }
+    
+    #[test]
+    fn test_migrate_imported_keys_out_of_keystore() {
+        let mut wallet = MobileWallet::new("password123").unwrap();
+        let secret = [0x22u8; 32];
+        let slot = wallet.keystore.add_private_key(&secret, "password123").unwrap();
+        wallet.accounts.push(Account::imported(slot, public_key_for(&secret).unwrap(), Network::Mainnet));
+        
+        // A v2 wallet kept imported keys in the keystore
+        let mut legacy = serde_json::to_value(&wallet).unwrap();
+        legacy["schema_version"] = serde_json::json!(2);
+        legacy.as_object_mut().unwrap().remove("imported_keys");
+        
+        let mut migrated = MobileWallet::from_json(&legacy.to_string()).unwrap();
+        assert_eq!(migrated.keystore.imported_key_count(), 0);
+        assert_eq!(*migrated.imported_keys[0].private_key("password123").unwrap(), secret);
+        
+        migrated.set_active_account(1).unwrap();
+        migrated.set_balance(10_000);
+        let mut tx = migrated.create_transaction(&recipient(Network::Mainnet), 1000, 10).unwrap();
+        migrated.sign_transaction(&mut tx, "password123").unwrap();
+        assert!(tx.verify(migrated.accounts()[1].public_key()));
+    }
+    
+    #[test]
+    fn test_offline_signing_round_trip() {
+        let mnemonic = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let mut online = MobileWallet::from_mnemonic(mnemonic, "password123").unwrap();
+        let offline = MobileWallet::from_mnemonic(mnemonic, "offlinepass456").unwrap();
+        
+        online.set_balance(10_000);
+        let tx = online.create_transaction(&recipient(Network::Mainnet), 1000, 10).unwrap();
+        let unsigned = tx.to_psbt_bytes().unwrap();
+        
+        let signed = offline.sign_psbt(&unsigned, "offlinepass456").unwrap();
+        assert!(signed.verify(online.active_account().unwrap().public_key()));
+        assert_eq!(signed.signing_hash(), tx.signing_hash());
+        
+        // A wallet that doesn't own the sender can't sign
+        let stranger = MobileWallet::new("password123").unwrap();
+        assert!(matches!(stranger.sign_psbt(&unsigned, "password123"), Err(MobileError::AccountNotFound)));
+    }
+    
+    #[test]
+    fn test_keystore_json_round_trip() {
+        let mut wallet = MobileWallet::with_network("password123", Network::Testnet).unwrap();
+        wallet
+            .import_private_key("0000000000000000000000000000000000000000000000000000000000000001", "password123")
+            .unwrap();
+        
+        let json = wallet.export_keystore_json("password123").unwrap();
+        let restored = MobileWallet::import_keystore_json(&json, "password123").unwrap();
+        
+        assert_eq!(restored.network(), Network::Testnet);
+        assert_eq!(restored.export_mnemonic("password123").unwrap(), wallet.export_mnemonic("password123").unwrap());
+        assert_eq!(restored.accounts().len(), 2);
+        assert_eq!(restored.accounts()[0].address(), wallet.accounts()[0].address());
+        assert_eq!(restored.accounts()[1].address(), wallet.accounts()[1].address());
+    }
+    
+    /// Backend that seals by reversal and counts every secret it seals
+    struct CountingBackend(std::sync::atomic::AtomicUsize);
+    
+    impl KeystoreBackend for CountingBackend {
+        fn id(&self) -> &str {
+            "android-keystore"
+        }
+        
+        fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(plaintext.iter().rev().copied().collect())
+        }
+        
+        fn decrypt(&self, ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+            Ok(Zeroizing::new(ciphertext.iter().rev().copied().collect()))
+        }
+    }
+    
+    #[test]
+    fn test_wallet_with_hardware_backend() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.json");
+        let backend = Arc::new(CountingBackend(Default::default()));
+        
+        let mut wallet = MobileWallet::with_backend("password123", Network::Testnet, backend.clone()).unwrap();
+        assert_eq!(backend.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+        
+        // The stored mnemonic is the backend's ciphertext of the exported phrase
+        let mnemonic = wallet.export_mnemonic("password123").unwrap();
+        let sealed: Vec<u8> = mnemonic.bytes().rev().collect();
+        let keystore = serde_json::to_value(&wallet.keystore).unwrap();
+        assert_eq!(keystore["encrypted_mnemonic"], serde_json::json!(sealed));
+        
+        let path84 = DerivationPath::new(Purpose::Bip84, Network::Testnet.coin_type(), 0);
+        wallet.add_account_with_path(path84, "password123").unwrap();
+        wallet.save_to_path(&path, "password123").unwrap();
+        
+        // Loaded wallets can't reach the seed until the backend is attached again
+        let mut loaded = MobileWallet::load_from_path(&path, "password123").unwrap();
+        loaded.remove_account(1).unwrap();
+        assert!(loaded.add_account_with_path(path84, "password123").is_err());
+        loaded.attach_keystore_backend(backend.clone()).unwrap();
+        loaded.add_account_with_path(path84, "password123").unwrap();
+        assert_eq!(loaded.accounts()[1].address(), wallet.accounts()[1].address());
+        assert_eq!(backend.0.load(std::sync::atomic::Ordering::SeqCst), 1);
     }
 }
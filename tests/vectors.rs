@@ -0,0 +1,162 @@
+//! Known-answer tests tying derivation to the BIP39, BIP32 and BIP44 standards
+
+use silver_mobile::hd::{ExtendedPrivateKey, COIN_TYPE};
+use silver_mobile::mnemonic;
+use silver_mobile::{MobileWallet, Network};
+
+/// BIP39 reference vectors: entropy, mnemonic and seed with the passphrase "TREZOR"
+const BIP39_VECTORS: &[(&str, &str, &str)] = &[
+    (
+        "00000000000000000000000000000000",
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+    ),
+    (
+        "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+        "legal winner thank year wave sausage worth useful legal winner thank yellow",
+        "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+    ),
+    (
+        "80808080808080808080808080808080",
+        "letter advice cage absurd amount doctor acoustic avoid letter advice cage above",
+        "d71de856f81a8acc65e6fc851a38d4d7ec216fd0796d0a6827a3ad6ed5511a30fa280f12eb2e47ed2ac03b5c462a0358d18d69fe4f985ec81778c1b370b652a8",
+    ),
+    (
+        "ffffffffffffffffffffffffffffffff",
+        "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+        "ac27495480225222079d7be181583751e86f571027b0497b5b5d11218e0a8a13332572917f0f8e5a589620c6f15b11c61dee327651a14c34e18231052e48c069",
+    ),
+    (
+        "000000000000000000000000000000000000000000000000",
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+         abandon abandon abandon abandon abandon agent",
+        "035895f2f481b1b0f01fcf8c289c794660b289981a78f8106447707fdd9666ca06da5a9a565181599b79f53b844d8a71dd9f439c52a3d7b3e8a79c906ac845fa",
+    ),
+    (
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+         abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art",
+        "bda85446c68413707090a52022edd26a1c9462295029f2e60cd7c4f2bbd3097170af7a4d73245cafa9c3cca8d561a7c3de6f5d4a10be8ed2a5e608d68f92fcc8",
+    ),
+    (
+        "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+        "legal winner thank year wave sausage worth useful legal winner thank year wave sausage worth \
+         useful legal winner thank year wave sausage worth title",
+        "bc09fca1804f7e69da93c2f2028eb238c227f2e9dda30cd63699232578480a4021b146ad717fbb7e451ce9eb835f43620bf5c514db0f8add49f5d121449d3e87",
+    ),
+    (
+        "8080808080808080808080808080808080808080808080808080808080808080",
+        "letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor \
+         acoustic avoid letter advice cage absurd amount doctor acoustic bless",
+        "c0c519bd0e91a2ed54357d9d1ebef6f5af218a153624cf4f2da911a0ed8f7a09e2ef61af0aca007096df430022f7a2b6fb91661a9589097069720d015e4e982f",
+    ),
+    (
+        "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+        "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad",
+    ),
+    (
+        "9e885d952ad362caeb4efe34a8e91bd2",
+        "ozone drill grab fiber curtain grace pudding thank cruise elder eight picnic",
+        "274ddc525802f7c828d8ef7ddbcdc5304e87ac3535913611fbbfa986d0c9e5476c91689f9c8a54fd55bd38606aa6a8595ad213d4c9c9f9aca3fb217069a41028",
+    ),
+    (
+        "68a79eaca2324873eacc50cb9c6eca8cc68ea5d936f98787c60c7ebc74e6ce7c",
+        "hamster diagram private dutch cause delay private meat slide toddler razor book happy fancy gospel \
+         tennis maple dilemma loan word shrug inflict delay length",
+        "64c87cde7e12ecf6704ab95bb1408bef047c22db4cc7491c4271d170a1b213d20b385bc1588d9c7b38f1b39d415665b8a9030c9ec653d75e65f847d8fc1fc440",
+    ),
+];
+
+/// The mnemonic every BIP44 reference wallet starts from
+const ABANDON_ABOUT: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+#[test]
+fn test_bip39_vectors() {
+    for (entropy_hex, phrase, seed_hex) in BIP39_VECTORS {
+        let entropy = hex::decode(entropy_hex).unwrap();
+        assert_eq!(mnemonic::from_entropy(&entropy).unwrap().as_str(), *phrase);
+        assert_eq!(&mnemonic::to_entropy(phrase).unwrap()[..], &entropy[..]);
+        assert_eq!(hex::encode(*mnemonic::to_seed(phrase, "TREZOR")), *seed_hex);
+    }
+}
+
+#[test]
+fn test_bip32_vectors() {
+    // Test vector 1, deepest path
+    let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+    let master = ExtendedPrivateKey::from_seed(&seed).unwrap();
+    assert_eq!(
+        hex::encode(master.public_key().public_key()),
+        "0339a36013301597daef41fbe593a02cc513d0b55527ec2df1050e2e8ff49c85c2"
+    );
+    let key = master.derive_path("m/0'/1/2'/2/1000000000").unwrap();
+    assert_eq!(
+        hex::encode(key.chain_code()),
+        "c783e67b921d2beb8f6b389cc646d7263b4145701dadd2161548a8b078e65e9e"
+    );
+    assert_eq!(
+        hex::encode(key.public_key().public_key()),
+        "022a471424da5e657499d1ff51cb43c47481a03b1e77f951fe64cec9f5a48f7011"
+    );
+    
+    // Test vector 2, with indices at the top of both the normal and hardened ranges
+    let seed = hex::decode(
+        "fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542",
+    ).unwrap();
+    let key = ExtendedPrivateKey::from_seed(&seed)
+        .unwrap()
+        .derive_path("m/0/2147483647'/1/2147483646'/2")
+        .unwrap();
+    assert_eq!(
+        hex::encode(key.chain_code()),
+        "9452b549be8cea3ecb7a84bec10dcfd94afe4d129ebfd3b3cb58eedf394ed271"
+    );
+    assert_eq!(
+        hex::encode(key.public_key().public_key()),
+        "024d902e1a2fc7a8755ab5b694c575fce742c48d9ff192e63df5193e4c7afe1f9c"
+    );
+}
+
+#[test]
+fn test_bip44_vector() {
+    let seed = mnemonic::to_seed(ABANDON_ABOUT, "");
+    let key = ExtendedPrivateKey::from_seed(&seed[..])
+        .unwrap()
+        .derive_path("m/44'/0'/0'/0/0")
+        .unwrap();
+    
+    assert_eq!(
+        hex::encode(key.secret_key().secret_bytes()),
+        "e284129cc0922579a535bbf4d1a3b25773090d28c909bc0fed73b5e0222cc372"
+    );
+    assert_eq!(
+        hex::encode(key.public_key().public_key()),
+        "03aaeb52dd7494c361049de67cc680e83ebcbbbdbeb13637d92cd845f70308af5e"
+    );
+}
+
+#[test]
+fn test_first_wallet_address() {
+    // m/44'/5342'/0'/0/0 on mainnet and m/44'/1'/0'/0/0 on testnet
+    let vectors = [
+        (
+            Network::Mainnet,
+            "0372e06eca9a772ba33912ae9ef27ea4483eac907877a463c75d83bd853d546429",
+            "silver_28038096b936b2336596e0219cff864b6985c3c47c08dbcd",
+        ),
+        (
+            Network::Testnet,
+            "02a7451395735369f2ecdfc829c0f774e88ef1303dfe5b2f04dbaab30a535dfdd6",
+            "tsilver_9177ff5ad39affc0940b7da1cad347ca9c7f1d837efdda2a",
+        ),
+    ];
+    assert_eq!(Network::Mainnet.coin_type(), COIN_TYPE);
+    
+    for (network, public_key, address) in vectors {
+        let wallet = MobileWallet::from_mnemonic_allowing_weak(ABANDON_ABOUT, "password123", network).unwrap();
+        let account = wallet.active_account().unwrap();
+        assert_eq!(hex::encode(account.public_key()), public_key);
+        assert_eq!(account.address(), address);
+    }
+}